@@ -58,11 +58,13 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                                 style: CssStyle("margin: 5px").to_style(&css_context),
                                 text: Text::from_section(
                                     "Text Example",
-                                    TextStyle {
-                                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                                        font_size: 30.0,
-                                        color: Color::WHITE,
-                                    },
+                                    CssStyle("font-size: 30px; color: white;").to_text_style(
+                                        &css_context,
+                                        &TextStyle {
+                                            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                                            ..Default::default()
+                                        },
+                                    ),
                                 ),
                                 ..Default::default()
                             });
@@ -84,11 +86,13 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                             .to_style(&css_context),
                         text: Text::from_section(
                             "Scrolling list",
-                            TextStyle {
-                                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                                font_size: 25.,
-                                color: Color::WHITE,
-                            },
+                            CssStyle("font-size: 25px; color: white;").to_text_style(
+                                &css_context,
+                                &TextStyle {
+                                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                                    ..Default::default()
+                                },
+                            ),
                         ),
                         ..Default::default()
                     });
@@ -120,12 +124,14 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                                                 .to_style(&css_context),
                                             text: Text::from_section(
                                                 format!("Item {}", i),
-                                                TextStyle {
-                                                    font: asset_server
-                                                        .load("fonts/FiraSans-Bold.ttf"),
-                                                    font_size: 20.,
-                                                    color: Color::WHITE,
-                                                },
+                                                CssStyle("font-size: 20px; color: white;").to_text_style(
+                                                    &css_context,
+                                                    &TextStyle {
+                                                        font: asset_server
+                                                            .load("fonts/FiraSans-Bold.ttf"),
+                                                        ..Default::default()
+                                                    },
+                                                ),
                                             ),
                                             ..Default::default()
                                         });
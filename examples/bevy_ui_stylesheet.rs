@@ -8,11 +8,17 @@ use bevy::{
     input::mouse::{MouseScrollUnit, MouseWheel},
     prelude::*,
 };
-use bevy_prototype_css::{CssPlugin, CssStylesheet, CssTag};
+use bevy_prototype_css::{CssPlugin, CssSettings, CssStylesheet, CssTag};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        // Run with `-- --log-style-stats` to log how many css rules were evaluated (and how long it
+        // took) whenever styles are applied.  Off otherwise, so the example is as the original.
+        .insert_resource(CssSettings {
+            log_style_stats: std::env::args().any(|arg| arg == "--log-style-stats"),
+            ..Default::default()
+        })
         // Adds the `Stylesheet` asset (with loader for `.css` files), and relevant systems
         .add_plugin(CssPlugin)
         .add_startup_system(setup)
@@ -0,0 +1,81 @@
+//! This example uses this `bevy_prototype_css` crate to size inline styles with the viewport units
+//! (`vw`, `vh`, `vmin` & `vmax`), using a `CssContext` made from the primary window.  The styles
+//! are re-applied whenever the window is resized, so the panels keep their proportions.
+
+use bevy::{
+    prelude::*,
+    window::WindowResized,
+};
+use bevy_prototype_css::{CssContext, CssStyle, ParsedInlineStyle};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(resize_viewport_styles)
+        .run()
+}
+
+/// The parsed inline css of a node, kept so it can be re-applied (without re-parsing) when the
+/// viewport size changes
+#[derive(Component)]
+struct ViewportStyle(ParsedInlineStyle);
+
+impl ViewportStyle {
+    fn bundle(css: &str, context: &CssContext) -> (NodeBundle, Self) {
+        let parsed = CssStyle(css).parse();
+        let bundle = NodeBundle {
+            style: parsed.to_style(context),
+            color: parsed.to_ui_color(),
+            ..Default::default()
+        };
+        (bundle, Self(parsed))
+    }
+}
+
+fn setup(mut commands: Commands, windows: Res<Windows>) {
+    commands.spawn_bundle(Camera2dBundle::default());
+
+    // The viewport lengths are resolved against the context's viewport size, so it is taken from
+    // the window rather than `CssContext::default()` (where `100vw` would be `0px`)
+    let css_context = windows.get_primary().map(CssContext::from_window).unwrap_or_default();
+
+    let (root, root_style) = ViewportStyle::bundle(
+        "width: 100vw; height: 100vh; justify-content: space-between; background-color: transparent;",
+        &css_context,
+    );
+    commands
+        .spawn_bundle(root)
+        .insert(root_style)
+        .with_children(|parent| {
+            // a sidebar, always a quarter of the window's width
+            let (sidebar, sidebar_style) = ViewportStyle::bundle(
+                "width: 25vw; height: 100vh; background-color: rgb(15%, 15%, 15%);",
+                &css_context,
+            );
+            parent.spawn_bundle(sidebar).insert(sidebar_style);
+            // a square, sized by the smaller side of the window
+            let (square, square_style) = ViewportStyle::bundle(
+                "width: 50vmin; height: 50vmin; margin: auto; background-color: rgb(40%, 40%, 100%);",
+                &css_context,
+            );
+            parent.spawn_bundle(square).insert(square_style);
+        });
+}
+
+fn resize_viewport_styles(
+    mut resized_events: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    mut query: Query<(&ViewportStyle, &mut Style)>,
+) {
+    // Only the last resize of the frame matters
+    if resized_events.iter().last().is_none() {
+        return
+    }
+    if let Some(window) = windows.get_primary() {
+        let css_context = CssContext::from_window(window);
+        for (viewport_style, mut style) in query.iter_mut() {
+            *style = viewport_style.0.to_style(&css_context)
+        }
+    }
+}
\ No newline at end of file
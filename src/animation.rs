@@ -0,0 +1,471 @@
+use bevy::{
+    prelude::*,
+    ui::{UiRect, Val},
+};
+use crate::{
+    context::CssContext,
+    errors::CssErrorSink,
+    properties::{custom, BevyPropertyDeclaration},
+    rules::BevyKeyframesRule,
+    values::{AnimationSpec, TransitionList},
+};
+
+/// Values that can be part way between two others
+pub trait Interpolate {
+    /// The value `progress` of the way from `self` to `to`, where `0` is `self` & `1` is `to`.  An
+    /// eased progress can be outside of that range (e.g. to overshoot).
+    fn interpolate(&self, to: &Self, progress: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    #[inline]
+    fn interpolate(&self, to: &Self, progress: f32) -> Self {
+        self + (to - self) * progress
+    }
+}
+
+impl Interpolate for Val {
+    /// Only values with the same unit can be interpolated, anything else changes immediately
+    fn interpolate(&self, to: &Self, progress: f32) -> Self {
+        match (*self, *to) {
+            (Val::Px(from), Val::Px(to)) => Val::Px(from.interpolate(&to, progress)),
+            (Val::Percent(from), Val::Percent(to)) => Val::Percent(from.interpolate(&to, progress)),
+            _ => *to,
+        }
+    }
+}
+
+impl Interpolate for Color {
+    /// Interpolated in (non-linear) sRGB, as browsers do by default
+    fn interpolate(&self, to: &Self, progress: f32) -> Self {
+        let [r, g, b, a] = self.as_rgba_f32();
+        let [to_r, to_g, to_b, to_a] = to.as_rgba_f32();
+        Color::rgba(
+            r.interpolate(&to_r, progress),
+            g.interpolate(&to_g, progress),
+            b.interpolate(&to_b, progress),
+            a.interpolate(&to_a, progress).clamp(0.0, 1.0),
+        )
+    }
+}
+
+/// The values of the components that transitions (and animations) change
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnimatedValues {
+    pub style: Option<Style>,
+    /// The `UiColor`, which is set by `background-color`, `color` & `opacity`
+    pub color: Option<Color>,
+}
+
+impl AnimatedValues {
+    pub fn capture(style: Option<&Style>, color: Option<&UiColor>) -> Self {
+        Self {
+            style: style.cloned(),
+            color: color.map(|color| color.0),
+        }
+    }
+
+    /// Sets the components to these values, where they are different (so Bevy's change detection
+    /// isn't triggered for nothing)
+    pub fn apply(&self, style: Option<&mut Mut<Style>>, color: Option<&mut Mut<UiColor>>) {
+        if let (Some(style), Some(value)) = (style, self.style.as_ref()) {
+            if **style != *value {
+                **style = value.clone()
+            }
+        }
+        if let (Some(color), Some(&value)) = (color, self.color.as_ref()) {
+            if color.0 != value {
+                color.0 = value
+            }
+        }
+    }
+
+    /// Applies the `declarations` to the values, as they would be applied to the components.  Any
+    /// `var()` that can't be substituted is reported to the `sink`.
+    pub fn apply_declarations<'a>(
+        &mut self,
+        declarations: impl IntoIterator<Item = &'a BevyPropertyDeclaration>,
+        context: &CssContext,
+        sink: &CssErrorSink,
+    ) {
+        let mut context = *context;
+        let mut ui_color = self.color.map(UiColor);
+        for property in custom::resolve_variables(declarations, sink) {
+            property.modify_context(&mut context);
+            if let Some(style) = self.style.as_mut() {
+                property.modify_style(&context, style)
+            }
+            if let Some(color) = ui_color.as_mut() {
+                property.modify_color(color)
+            }
+        }
+        self.color = ui_color.map(|color| color.0);
+    }
+
+    /// The values part way to `to`, where `progress` gives how far each (lowercase) property is.
+    /// Only numeric properties (e.g. `width`, `flex-grow` or the color) are interpolated, the rest
+    /// (e.g. `display`) change immediately, as do components that only one side has.
+    pub fn interpolate(&self, to: &Self, progress: impl Fn(&str) -> f32) -> Self {
+        let style = match (self.style.as_ref(), to.style.as_ref()) {
+            (Some(from), Some(to)) => Some(interpolate_style(from, to, &progress)),
+            (_, to) => to.cloned(),
+        };
+        let color = match (self.color, to.color) {
+            (Some(from), Some(to)) => {
+                // Each of these sets the `UiColor`, so the first one with a transition is used
+                let progress = ["background-color", "color", "opacity"].iter()
+                    .map(|&name| progress(name))
+                    .find(|&progress| progress < 1.0)
+                    .unwrap_or(1.0);
+                Some(from.interpolate(&to, progress))
+            },
+            (_, to) => to,
+        };
+        Self { style, color }
+    }
+}
+
+fn interpolate_style(from: &Style, to: &Style, progress: &impl Fn(&str) -> f32) -> Style {
+    let val = |name: &str, from: Val, to: Val| from.interpolate(&to, progress(name));
+    let sides = |name: &str, from: UiRect<Val>, to: UiRect<Val>| UiRect {
+        left: val(&format!("{}-left", name), from.left, to.left),
+        right: val(&format!("{}-right", name), from.right, to.right),
+        top: val(&format!("{}-top", name), from.top, to.top),
+        bottom: val(&format!("{}-bottom", name), from.bottom, to.bottom),
+    };
+    Style {
+        position: UiRect {
+            left: val("left", from.position.left, to.position.left),
+            right: val("right", from.position.right, to.position.right),
+            top: val("top", from.position.top, to.position.top),
+            bottom: val("bottom", from.position.bottom, to.position.bottom),
+        },
+        margin: sides("margin", from.margin, to.margin),
+        padding: sides("padding", from.padding, to.padding),
+        border: sides("border-width", from.border, to.border),
+        flex_grow: from.flex_grow.interpolate(&to.flex_grow, progress("flex-grow")),
+        flex_shrink: from.flex_shrink.interpolate(&to.flex_shrink, progress("flex-shrink")),
+        flex_basis: val("flex-basis", from.flex_basis, to.flex_basis),
+        size: Size::new(val("width", from.size.width, to.size.width), val("height", from.size.height, to.size.height)),
+        min_size: Size::new(
+            val("min-width", from.min_size.width, to.min_size.width),
+            val("min-height", from.min_size.height, to.min_size.height),
+        ),
+        max_size: Size::new(
+            val("max-width", from.max_size.width, to.max_size.width),
+            val("max-height", from.max_size.height, to.max_size.height),
+        ),
+        aspect_ratio: match (from.aspect_ratio, to.aspect_ratio) {
+            (Some(from), Some(to)) => Some(from.interpolate(&to, progress("aspect-ratio"))),
+            (_, to) => to,
+        },
+        ..to.clone()
+    }
+}
+
+/// A transition of an entity's components, from the values they had before it was restyled to the
+/// new ones, as set by its `transition` property.  It is added by the `CssPlugin`, and removed
+/// once the transition has finished.
+/// See also: https://drafts.csswg.org/css-transitions/
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct CssTransition {
+    pub from: AnimatedValues,
+    pub to: AnimatedValues,
+    pub transitions: TransitionList,
+    /// Seconds since the transition started
+    pub elapsed: f32,
+}
+
+impl CssTransition {
+    /// The values at `elapsed`
+    pub fn values(&self) -> AnimatedValues {
+        self.from.interpolate(&self.to, |name| self.transitions.progress(name, self.elapsed))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.transitions.total_duration()
+    }
+}
+
+/// Called after an entity has been restyled, with the values that were `shown` before, to start a
+/// transition to the new values (which are currently set) where the entity has a `transition`.
+/// A transition that is already heading to the new values is left to carry on.
+pub(crate) fn start_transition(
+    commands: &mut Commands,
+    entity: Entity,
+    transitions: Option<TransitionList>,
+    shown: AnimatedValues,
+    mut style: Option<Mut<Style>>,
+    mut color: Option<Mut<UiColor>>,
+    current: Option<&CssTransition>,
+) {
+    let target = AnimatedValues::capture(style.as_deref(), color.as_deref());
+    let transitions = match transitions {
+        Some(transitions) if target != shown => transitions,
+        _ => {
+            if current.is_some() {
+                commands.entity(entity).remove::<CssTransition>();
+            }
+            return
+        },
+    };
+    // Until the transition is next updated, the entity looks as it did
+    shown.apply(style.as_mut(), color.as_mut());
+    if let Some(current) = current {
+        if current.to == target && current.transitions == transitions {
+            return
+        }
+    }
+    commands.entity(entity).insert(CssTransition { from: shown, to: target, transitions, elapsed: 0.0 });
+}
+
+/// System to move every transition on by the frame's time
+pub(crate) fn update_transitions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut CssTransition, Option<&mut Style>, Option<&mut UiColor>)>,
+) {
+    for (entity, mut transition, mut style, mut color) in query.iter_mut() {
+        transition.elapsed += time.delta_seconds();
+        if transition.is_finished() {
+            transition.to.apply(style.as_mut(), color.as_mut());
+            commands.entity(entity).remove::<CssTransition>();
+        } else {
+            transition.values().apply(style.as_mut(), color.as_mut());
+        }
+    }
+}
+
+/// A css animation of an entity's components, through the `@keyframes` named by its `animation`
+/// property.  It is added (& removed) by the `CssPlugin` as the entity is restyled.  Once a finite
+/// animation has finished it is kept, without changing anything, so restyling doesn't restart it.
+/// See also: https://drafts.csswg.org/css-animations/
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct CssAnimation {
+    pub spec: AnimationSpec,
+    /// The values without the animation, which are shown before its delay & once it has finished
+    pub base: AnimatedValues,
+    /// The values at each keyframe, by their offset (from `0` to `1`), in order
+    pub frames: Vec<(f32, AnimatedValues)>,
+    /// Seconds since the animation started
+    pub elapsed: f32,
+}
+
+impl CssAnimation {
+    /// An animation through the `keyframes`, where the declarations of each keyframe are applied
+    /// (in the `context`) to the `base` values.  Without a `from` or `to` keyframe, the base values
+    /// are used for that end.
+    // @todo A property that a keyframe doesn't declare should be interpolated between the nearest
+    //   keyframes that do, rather than use its base value
+    pub fn new(
+        spec: AnimationSpec,
+        keyframes: &BevyKeyframesRule,
+        base: AnimatedValues,
+        context: &CssContext,
+        sink: &CssErrorSink,
+    ) -> Self {
+        let mut frames: Vec<(f32, AnimatedValues)> = Vec::new();
+        for keyframe in keyframes.frames.iter() {
+            match frames.last_mut() {
+                // Keyframes with the same offset are applied on top of each other
+                Some((offset, values)) if *offset == keyframe.offset =>
+                    values.apply_declarations(keyframe.declarations.iter(), context, sink),
+                _ => {
+                    let mut values = base.clone();
+                    values.apply_declarations(keyframe.declarations.iter(), context, sink);
+                    frames.push((keyframe.offset, values));
+                },
+            }
+        }
+        if frames.first().map_or(true, |&(offset, _)| offset > 0.0) {
+            frames.insert(0, (0.0, base.clone()));
+        }
+        if frames.last().map_or(true, |&(offset, _)| offset < 1.0) {
+            frames.push((1.0, base.clone()));
+        }
+        Self { spec, base, frames, elapsed: 0.0 }
+    }
+
+    /// The values at `elapsed`.  The timing function eases the progress between each pair of
+    /// keyframes, rather than over the whole animation.
+    pub fn values(&self) -> AnimatedValues {
+        let progress = match self.spec.progress(self.elapsed) {
+            Some(progress) => progress,
+            None => return self.base.clone(),
+        };
+        // The first keyframe after the progress, & the one before it
+        let next = self.frames.iter()
+            .position(|&(offset, _)| offset > progress)
+            .unwrap_or(self.frames.len() - 1)
+            .max(1);
+        let (from_offset, ref from) = self.frames[next - 1];
+        let (to_offset, ref to) = self.frames[next];
+        let progress = (progress - from_offset) / (to_offset - from_offset);
+        let eased = self.spec.timing_function.evaluate(progress.clamp(0.0, 1.0));
+        from.interpolate(to, |_| eased)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.spec.total_duration()
+    }
+}
+
+/// Called after an entity has been restyled (so its components have the `base` values), to start
+/// the `animation` that won the cascade, with its keyframes, or stop the `current` one if there is
+/// none.  An animation that is already running the same spec carries on from where it was.
+// Each styled component is passed separately, as either of them may be missing
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn start_animation(
+    commands: &mut Commands,
+    entity: Entity,
+    animation: Option<(AnimationSpec, &BevyKeyframesRule)>,
+    context: &CssContext,
+    sink: &CssErrorSink,
+    mut style: Option<Mut<Style>>,
+    mut color: Option<Mut<UiColor>>,
+    current: Option<&CssAnimation>,
+) {
+    let (spec, keyframes) = match animation {
+        Some(animation) => animation,
+        None => {
+            if current.is_some() {
+                commands.entity(entity).remove::<CssAnimation>();
+            }
+            return
+        },
+    };
+    let base = AnimatedValues::capture(style.as_deref(), color.as_deref());
+    let mut animation = CssAnimation::new(spec, keyframes, base, context, sink);
+    if let Some(current) = current.filter(|current| current.spec == animation.spec) {
+        animation.elapsed = current.elapsed
+    }
+    // Until the animation is next updated, the entity shows its current frame
+    if !animation.is_finished() {
+        animation.values().apply(style.as_mut(), color.as_mut());
+    }
+    if current != Some(&animation) {
+        commands.entity(entity).insert(animation);
+    }
+}
+
+/// System to move every unfinished animation on by the frame's time
+pub(crate) fn update_animations(
+    time: Res<Time>,
+    mut query: Query<(&mut CssAnimation, Option<&mut Style>, Option<&mut UiColor>)>,
+) {
+    for (mut animation, mut style, mut color) in query.iter_mut() {
+        if animation.is_finished() {
+            continue
+        }
+        animation.elapsed += time.delta_seconds();
+        // Once finished, this is the base values
+        animation.values().apply(style.as_mut(), color.as_mut());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        stylesheet::{CssStyle, CssStylesheet},
+        values::Parse,
+    };
+    use super::*;
+
+    fn values(css: &str) -> AnimatedValues {
+        let style = CssStyle(css);
+        AnimatedValues {
+            style: Some(style.to_style(&CssContext::default())),
+            color: Some(style.to_ui_color().0),
+        }
+    }
+
+    fn transition(transitions: &str, from: &str, to: &str, elapsed: f32) -> AnimatedValues {
+        CssTransition {
+            from: values(from),
+            to: values(to),
+            transitions: TransitionList::parse_str(transitions).unwrap(),
+            elapsed,
+        }.values()
+    }
+
+    #[test]
+    fn test_all_transition() {
+        let from = "width: 100px; background-color: red; display: flex;";
+        let to = "width: 200px; background-color: red; opacity: 0; display: none;";
+        let halfway = transition("all 1s linear", from, to, 0.5);
+        let style = halfway.style.unwrap();
+        assert_eq!(style.size.width, Val::Px(150.0));
+        assert_eq!(halfway.color.unwrap().a(), 0.5);
+        // Properties that can't be interpolated change immediately
+        assert_eq!(style.display, Display::None);
+        assert_eq!(transition("all 1s linear", from, to, 1.0), values(to));
+    }
+
+    #[test]
+    fn test_property_transitions() {
+        let from = "width: 100px; height: 10%; margin: 0px;";
+        let to = "width: 200px; height: 20%; margin: 10px; opacity: 0;";
+        let values = transition("width 2s linear, margin 1s linear", from, to, 0.5);
+        let style = values.style.unwrap();
+        assert_eq!(style.size.width, Val::Px(125.0));
+        assert_eq!(style.margin.top, Val::Px(5.0));
+        // Without a transition, they change at once
+        assert_eq!(style.size.height, Val::Percent(20.0));
+        assert_eq!(values.color.unwrap().a(), 0.0);
+        // Different units can't be interpolated
+        let style = transition("all 1s", "width: 10px;", "width: 10%;", 0.5).style.unwrap();
+        assert_eq!(style.size.width, Val::Percent(10.0));
+    }
+
+    #[test]
+    fn test_is_finished() {
+        let mut transition = CssTransition {
+            from: values("width: 1px;"),
+            to: values("width: 2px;"),
+            transitions: TransitionList::parse_str("width 1s 0.5s").unwrap(),
+            elapsed: 1.0,
+        };
+        assert!(!transition.is_finished());
+        transition.elapsed = 1.5;
+        assert!(transition.is_finished());
+    }
+
+    #[test]
+    fn test_animation_values() {
+        let sheet = CssStylesheet::from("@keyframes grow { from { width: 100px; } 50% { width: 300px; } }");
+        let spec = AnimationSpec::parse_str("grow 2s linear 2 1s").unwrap();
+        let base = values("width: 50px;");
+        let keyframes = sheet.keyframes("grow").unwrap();
+        let mut animation = CssAnimation::new(spec, keyframes, base, &CssContext::default(), &CssErrorSink::default());
+        // Without a `to` keyframe, it ends with the base values
+        assert_eq!(animation.frames.len(), 3);
+        assert_eq!(animation.frames[2], (1.0, animation.base.clone()));
+        let width = |animation: &CssAnimation| animation.values().style.unwrap().size.width;
+        // The base values are shown during the delay
+        assert_eq!(width(&animation), Val::Px(50.0));
+        animation.elapsed = 1.5;
+        assert_eq!(width(&animation), Val::Px(200.0));
+        animation.elapsed = 2.5;
+        assert_eq!(width(&animation), Val::Px(175.0));
+        // The second iteration
+        animation.elapsed = 3.5;
+        assert_eq!(width(&animation), Val::Px(200.0));
+        assert!(!animation.is_finished());
+        animation.elapsed = 5.0;
+        assert!(animation.is_finished());
+        assert_eq!(animation.values(), animation.base);
+    }
+
+    #[test]
+    fn test_animation_easing() {
+        let sheet = CssStylesheet::from("@keyframes fade { to { opacity: 0; } }");
+        let spec = AnimationSpec::parse_str("fade 1s steps(2)").unwrap();
+        let keyframes = sheet.keyframes("fade").unwrap();
+        let mut animation =
+            CssAnimation::new(spec, keyframes, values(""), &CssContext::default(), &CssErrorSink::default());
+        animation.elapsed = 0.25;
+        assert_eq!(animation.values().color.unwrap().a(), 1.0);
+        animation.elapsed = 0.75;
+        assert_eq!(animation.values().color.unwrap().a(), 0.5);
+    }
+}
\ No newline at end of file
@@ -1,6 +1,14 @@
 use bevy::{
     math::Vec2,
-    text::TextStyle,
+    text::{Font, TextStyle},
+    window::Window,
+};
+use crate::{
+    fonts,
+    values::{
+        length::{DEFAULT_X_HEIGHT_RATIO, DEFAULT_ZERO_ADVANCE_RATIO},
+        line_height::NORMAL_LINE_HEIGHT,
+    },
 };
 
 /// A simple data holding struct that can be passed around to help construct or convert various css
@@ -10,8 +18,29 @@ use bevy::{
 pub struct CssContext {
     pub font_size: f32,
     pub root_font_size: f32,
+    /// The computed `line-height` in pixels (used for `lh` units), or `None` for `normal`, which
+    /// follows `font_size`
+    pub line_height: Option<f32>,
+    /// The root element `line-height` (used for `rlh` units), or `None` for `normal`
+    pub root_line_height: Option<f32>,
     pub vertical_text: bool,
+    /// The x-height of the font, as a fraction of `font_size` (used for `ex` units).  The
+    /// `CssPlugin` measures it from each entity's font (see `with_font_metrics()`), otherwise it is
+    /// assumed to be `0.5`.
+    pub x_height_ratio: f32,
+    /// The advance width of the font's `0` glyph, as a fraction of `font_size` (used for `ch`
+    /// units in horizontal text).  Measured & assumed in the same way as `x_height_ratio`.
+    pub zero_advance_ratio: f32,
     pub viewport_size: Vec2,
+    /// A zoom factor for the UI, applied to all absolute lengths (e.g. `px`, `in`)
+    pub ui_scale: f32,
+    /// The number of Bevy pixels per (96 dpi) reference pixel.  Only used for the physical units
+    /// (e.g. `in`, `cm`), so they keep their real-world size where Bevy's pixels aren't reference
+    /// pixels (e.g. where the window scale factor has been overridden).
+    pub device_pixel_ratio: f32,
+    /// Whether to `warn!` where a min size is larger than its max size (e.g. `min-width: 200px;
+    /// max-width: 100px;`).  The max size is clamped to the min size either way.
+    pub warn_size_conflicts: bool,
 }
 
 impl Default for CssContext {
@@ -19,8 +48,78 @@ impl Default for CssContext {
         Self {
             font_size: TextStyle::default().font_size,
             root_font_size: TextStyle::default().font_size,
+            line_height: None,
+            root_line_height: None,
             vertical_text: false,
+            x_height_ratio: DEFAULT_X_HEIGHT_RATIO,
+            zero_advance_ratio: DEFAULT_ZERO_ADVANCE_RATIO,
             viewport_size: Vec2::default(),
+            ui_scale: 1.0,
+            device_pixel_ratio: 1.0,
+            warn_size_conflicts: true,
         }
     }
+}
+
+impl CssContext {
+    /// The same as `CssContext::default()`, to be followed by any of the `with_*` methods, e.g.
+    /// `CssContext::new().with_viewport_size(Vec2::new(800.0, 600.0)).with_font_size(16.0)`
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A context with the (logical) size of the `window` as its viewport, for the viewport lengths
+    /// (e.g. `100vw`) of inline styles.  The `CssPlugin` does the same for stylesheets, with the
+    /// primary window.
+    #[inline]
+    pub fn from_window(window: &Window) -> Self {
+        Self::new().with_viewport_size(Vec2::new(window.width(), window.height()))
+    }
+
+    #[inline]
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    #[inline]
+    pub fn with_root_font_size(mut self, root_font_size: f32) -> Self {
+        self.root_font_size = root_font_size;
+        self
+    }
+
+    #[inline]
+    pub fn with_viewport_size(mut self, viewport_size: Vec2) -> Self {
+        self.viewport_size = viewport_size;
+        self
+    }
+
+    #[inline]
+    pub fn with_vertical_text(mut self, vertical_text: bool) -> Self {
+        self.vertical_text = vertical_text;
+        self
+    }
+
+    /// The `x_height_ratio` & `zero_advance_ratio` measured from the `font`, e.g. for the `ex` &
+    /// `ch` lengths of a `CssStyle` to use its real metrics.  Either is left as it is where the font
+    /// has no `x` (or `0`) glyph.
+    pub fn with_font_metrics(mut self, font: &Font) -> Self {
+        let (x_height_ratio, zero_advance_ratio) = fonts::font_ratios(font);
+        self.x_height_ratio = x_height_ratio.unwrap_or(self.x_height_ratio);
+        self.zero_advance_ratio = zero_advance_ratio.unwrap_or(self.zero_advance_ratio);
+        self
+    }
+
+    /// The line height in pixels, for `lh` units
+    #[inline]
+    pub fn computed_line_height(&self) -> f32 {
+        self.line_height.unwrap_or(self.font_size * NORMAL_LINE_HEIGHT)
+    }
+
+    /// The root line height in pixels, for `rlh` units
+    #[inline]
+    pub fn computed_root_line_height(&self) -> f32 {
+        self.root_line_height.unwrap_or(self.root_font_size * NORMAL_LINE_HEIGHT)
+    }
 }
\ No newline at end of file
@@ -69,4 +69,10 @@ impl Borrow<str> for CssString {
     fn borrow(&self) -> &str {
         self.deref()
     }
+}
+
+impl AsRef<str> for CssString {
+    fn as_ref(&self) -> &str {
+        self.deref()
+    }
 }
\ No newline at end of file
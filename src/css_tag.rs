@@ -1,27 +1,56 @@
-use bevy::prelude::{Component, debug, warn};
+use bevy::{
+    prelude::{Component, debug},
+    utils::HashMap,
+};
 use smallvec::SmallVec;
 
 /// Component used to:
 ///     a) denote that an entity should be included in CSS styling passes
-///     b) define the `id` and `classes` that will be used for said styling
+///     b) define the element `name`, `id` and `classes` that will be used for said styling
 /// An entity without a `CssTag` component will not be styled!
 /// An entity with a `CssTag` component but no `id` or `classes` could still be styled; with a
 /// wildcard (`*`) css selector for example.
+/// Only an entity with a `name` can be matched by a type selector (e.g. `button`), and only one with
+/// `attributes` by an attribute selector (e.g. `[data-state=open]`).
 #[derive(Component, Debug, Clone, Default)]
 pub struct CssTag {
+    pub(crate) name: Option<String>,
     pub(crate) id: Option<String>,
     // SmallVec is used for classes as there is often only one class specified
     pub(crate) classes: SmallVec<[String; 1]>,
+    /// By their lowercase name
+    pub(crate) attributes: HashMap<String, String>,
 }
 
 impl CssTag {
     pub fn new() -> Self {
         Self {
+            name: None,
             id: None,
             classes: SmallVec::new(),
+            attributes: HashMap::default(),
         }
     }
 
+    /// Will set the element `name` of this `CssTag` (i.e. for type selectors, e.g. `button`) to
+    /// that supplied, overwriting any existing name.
+    /// Supplying an empty string ("") will set the `name` to None
+    /// The supplied `name_string` must not contain any ASCII whitespace
+    pub fn name(mut self, name_string: String) -> Self {
+        if !name_string.is_empty() {
+            no_whitespace(name_string.as_str());
+            self.name = Some(name_string);
+        } else {
+            self.name = None;
+            debug!("Empty name string supplied for CssTag::name")
+        }
+        self
+    }
+
+    pub fn new_named(name_str: &str) -> Self {
+        Self::new().name(name_str.to_string())
+    }
+
     /// Will set the `id` of this `CssTag` to that supplied, overwriting any existing id.
     /// Supplying an empty string ("") will set the `id` to None
     /// The supplied `id_string` must not contain any ASCII whitespace
@@ -75,6 +104,62 @@ impl CssTag {
     pub fn new_class_str(classes_str: &str) -> Self {
         Self::new().class(classes_str.to_string())
     }
+
+    /// Adds the `class` (if the tag doesn't already have it), keeping the existing classes.  Changing
+    /// the classes of a spawned entity's `CssTag` restyles it.
+    /// The supplied `class` must not contain any ASCII whitespace
+    pub fn add_class(&mut self, class: &str) {
+        if class.is_empty() {
+            debug!("Empty class string supplied for CssTag::add_class");
+            return
+        }
+        no_whitespace(class);
+        if !self.has_class(class) {
+            self.classes.push(class.to_string())
+        }
+    }
+
+    /// Removes the `class`, if the tag has it
+    pub fn remove_class(&mut self, class: &str) {
+        self.classes.retain(|existing| existing != class)
+    }
+
+    /// Adds the `class` if the tag doesn't have it, otherwise removes it, e.g. to flip a `.selected`
+    /// class with `tag.toggle_class("selected")`.  Returns whether the tag now has the class.
+    /// The supplied `class` must not contain any ASCII whitespace
+    pub fn toggle_class(&mut self, class: &str) -> bool {
+        if self.has_class(class) {
+            self.remove_class(class);
+            false
+        } else {
+            self.add_class(class);
+            !class.is_empty()
+        }
+    }
+
+    /// Whether the tag has the (case-sensitive) `class`
+    #[inline]
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|existing| existing == class)
+    }
+
+    /// Will set the attribute `name` of this `CssTag` to the `value` supplied (i.e. for attribute
+    /// selectors, e.g. `[data-state=open]`), overwriting any existing value.  Attribute names are
+    /// case insensitive, like in html.
+    /// The supplied `name` must not contain any ASCII whitespace
+    /// See also: https://drafts.csswg.org/selectors/#attribute-selectors
+    pub fn attr(mut self, name: &str, value: &str) -> Self {
+        no_whitespace(name);
+        self.attributes.insert(name.to_ascii_lowercase(), value.to_string());
+        self
+    }
+
+    /// Whether the attribute `name` has been set (to any value, even an empty one), i.e. whether a
+    /// presence selector like `[data-open]` matches
+    #[inline]
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.contains_key(&name.to_ascii_lowercase())
+    }
 }
 
 impl From<&str> for CssTag {
@@ -90,8 +175,8 @@ impl From<String> for CssTag {
     /// '#' indicates the following string slice is an ID.  Only the last id given is used.
     /// '.' indicates the following string slice is a class.
     ///
-    /// If the given string does not start with '#'/'.', a warning is produced, but the starting
-    /// slice is otherwise ignored.
+    /// If the given string does not start with '#'/'.', the starting slice is the element name
+    /// (e.g. "button.primary").
     ///
     /// Any ASCII whitespace in id/class string slices will panic! with an assert error.
     ///
@@ -100,14 +185,14 @@ impl From<String> for CssTag {
     ///
     /// Example: "#id.class1.class2"
     fn from(selectors: String) -> Self {
+        let mut name = String::new();
         let mut id = String::new();
         let mut classes = String::new();
         let (mut is_id, mut is_class) = (false, false);
-        let mut warned = false;
         if selectors.is_empty() {
             debug!("Empty selectors string supplied for CssTag::from")
         }
-        for char in selectors.chars() {
+        for char in selectors.trim().chars() {
             match char {
                 '#' => {
                     is_id = true;
@@ -121,15 +206,10 @@ impl From<String> for CssTag {
                 },
                 c if is_id => id.push(c),
                 c if is_class => classes.push(c),
-                _ => {
-                    if !warned {
-                        warn!("Selectors string does not start with '#' (id) or '.' (class)")
-                    }
-                    warned = true;
-                },
+                c => name.push(c),
             }
         }
-        Self::new().id(id).class(classes)
+        Self::new().name(name).id(id).class(classes)
     }
 }
 
@@ -138,6 +218,50 @@ fn no_whitespace(str: &str) {
     // Ref: comment on https://stackoverflow.com/a/64361042
     assert!(
         !str.as_bytes().iter().any(u8::is_ascii_whitespace),
-        "A CSS id/class cannot contain any ASCII whitespace"
+        "A CSS name/id/class/attribute name cannot contain any ASCII whitespace"
     );
+}
+
+/// Marker component for an entity that is disabled, i.e. that matches the `:disabled` (rather than
+/// the `:enabled`) pseudo-class.
+/// See also: https://drafts.csswg.org/selectors/#enableddisabled
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct CssDisabled;
+
+/// Marker component for an entity that is checked (e.g. a checkbox or toggle), i.e. that matches the
+/// `:checked` pseudo-class.
+/// See also: https://drafts.csswg.org/selectors/#checked
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct CssChecked;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_mutation() {
+        let mut tag = CssTag::from("button.primary");
+        tag.add_class("selected");
+        tag.add_class("selected");
+        assert_eq!(tag.classes.as_slice(), ["primary", "selected"]);
+        assert!(tag.has_class("selected"));
+        assert!(!tag.has_class("Selected"));
+
+        assert!(!tag.toggle_class("selected"));
+        assert!(!tag.has_class("selected"));
+        assert!(tag.toggle_class("selected"));
+        assert!(tag.has_class("selected"));
+
+        tag.remove_class("primary");
+        tag.remove_class("missing");
+        assert_eq!(tag.classes.as_slice(), ["selected"]);
+        tag.add_class("");
+        assert_eq!(tag.classes.len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_class_whitespace() {
+        CssTag::new().add_class("a class");
+    }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
-use std::fmt;
+use std::{cell::RefCell, fmt, sync::Arc};
+use bevy::prelude::warn;
 use cssparser::{BasicParseErrorKind, CowRcStr, ParseError, ParseErrorKind, Token};
 use selectors::parser::SelectorParseErrorKind;
 
@@ -17,6 +18,11 @@ pub enum BevyCssContextualError<'i> {
     UnsupportedProperty(&'i str, BevyCssParsingError<'i>),
     /// A value was encountered that is invalid
     InvalidValue(&'i str, BevyCssParsingError<'i>),
+    /// A selector (list) was encountered that is invalid or unsupported
+    InvalidSelector(&'i str, BevyCssParsingError<'i>),
+    /// A declaration was parsed, but won't be applied as written (e.g. its value was clamped, or
+    /// the property isn't supported by Bevy).  Unlike the other variants, nothing was skipped.
+    Warning(&'i str, BevyCssParsingError<'i>),
 }
 
 impl<'i> BevyCssContextualError<'i> {
@@ -26,16 +32,24 @@ impl<'i> BevyCssContextualError<'i> {
             Self::UnsupportedAtRule(_, ref err) |
             Self::InvalidAtRule(_, ref err) |
             Self::UnsupportedProperty(_, ref err) |
-            Self::InvalidValue(_, ref err) => err
+            Self::InvalidValue(_, ref err) |
+            Self::InvalidSelector(_, ref err) |
+            Self::Warning(_, ref err) => err
         }
     }
 
+    #[inline]
+    pub fn is_warning(&self) -> bool {
+        matches!(*self, Self::Warning(..))
+    }
+
     #[inline]
     pub fn error_string_with_location(&self) -> String {
         let location = self.parsing_error().location;
+        let outcome = if self.is_warning() { "Parsed" } else { "Failed to parse" };
         format!(
-            "Failed to parse css at (line: {}, col: {}): {}",
-            location.line, location.column, self
+            "{} css at (line: {}, col: {}): {}",
+            outcome, location.line, location.column, self
         )
     }
 }
@@ -62,10 +76,74 @@ impl<'i> fmt::Display for BevyCssContextualError<'i> {
                 write!(f,
                        "The value of a property is invalid: {}", parse_error_2_str(err)
                 ),
+            Self::InvalidSelector(selector, ref err) =>
+                write!(f,
+                       "Invalid selector ({}), {}",
+                       selector, parse_error_2_str(err)
+                ),
+            Self::Warning(css, ref err) =>
+                write!(f,
+                       "{} ({})", parse_error_2_str(err), css
+                ),
         }
     }
 }
 
+/// Receives each error found while parsing a stylesheet (e.g. an unknown property), so an app can
+/// route them to its own logging or an on-screen console.  By default, each error is logged with
+/// `warn!`.  Insert this resource before adding the `CssPlugin` to change where stylesheet assets
+/// report their errors (which are also kept in each `CssStylesheet`'s `errors`).
+#[derive(Clone)]
+pub struct CssErrorSink(Arc<dyn Fn(&BevyCssContextualError) + Send + Sync>);
+
+impl CssErrorSink {
+    pub fn new(sink: impl Fn(&BevyCssContextualError) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(sink))
+    }
+
+    #[inline]
+    pub fn report(&self, error: &BevyCssContextualError) {
+        (self.0)(error)
+    }
+}
+
+impl Default for CssErrorSink {
+    fn default() -> Self {
+        Self::new(|error| warn!("{}", error))
+    }
+}
+
+impl fmt::Debug for CssErrorSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CssErrorSink")
+    }
+}
+
+thread_local! {
+    /// The notices given while `collect_notices()` is running (`None` otherwise)
+    static NOTICES: RefCell<Option<Vec<String>>> = RefCell::new(None);
+}
+
+/// Notes that a value was parsed, but won't be used as written (e.g. it had to be clamped).  Values
+/// are parsed without knowing which declaration (or sink) they are for, so the notice is kept for
+/// `collect_notices()`, whose caller reports it as a warning.  Outside of it, the notice is
+/// dropped, so parsing never logs anything itself.
+pub(crate) fn notice(message: String) {
+    NOTICES.with(|notices| {
+        if let Some(notices) = notices.borrow_mut().as_mut() {
+            notices.push(message)
+        }
+    })
+}
+
+/// The result of `parse`, with the messages of each `notice()` given while it ran
+pub(crate) fn collect_notices<T>(parse: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let outer = NOTICES.with(|notices| notices.replace(Some(Vec::new())));
+    let result = parse();
+    let collected = NOTICES.with(|notices| notices.replace(outer));
+    (result, collected.unwrap_or_default())
+}
+
 #[derive(Debug, Clone)]
 /// Detailed errors that may be declared while parsing parts of a CSS sheet
 pub enum BevyCssParsingErrorKind<'i> {
@@ -87,18 +165,42 @@ pub enum BevyCssParsingErrorKind<'i> {
     UnexpectedFunction(CowRcStr<'i>),
     /// An @-rule was encountered that is not supported by this parser
     UnsupportedAtRule(CowRcStr<'i>),
+    /// An @-rule was encountered with an invalid prelude (e.g. the media queries of `@media`)
+    InvalidAtRule(CowRcStr<'i>),
     /// An error occurred while parsing a selector(s)
     SelectorError(SelectorParseErrorKind<'i>),
     /// A property was declared with an unknown name
     UnknownProperty(CowRcStr<'i>),
     /// A number (other than `0`) was given without a dimension (e.g. `px`) where a dimension is expected
     MissingDimension(Token<'i>),
+    /// A property was declared without any value (e.g. `width: ;`)
+    MissingValue(CowRcStr<'i>),
     /// The keyword supplied is not supported by this parsing framework
     InvalidKeyword(CowRcStr<'i>),
     /// A value was given that is invalid in its context (but is still syntactically correct)
     InvalidValue(CowRcStr<'i>, Option<Token<'i>>),
+    /// `auto` was given for a value that can't be `auto` (e.g. a non-auto length)
+    AutoNotAllowed,
     /// A function was used where it is not supported by this parsing framework
     FunctionNotSupported(CowRcStr<'i>),
+    /// A function was called without any arguments (e.g. `min()`)
+    MissingArguments(CowRcStr<'i>),
+    /// A function was called with the wrong number of arguments, with the number that it expects
+    WrongArgumentCount(CowRcStr<'i>, CowRcStr<'i>),
+    /// A selector combinator was used that is not part of the css spec (e.g. the `>>>` deep combinator)
+    InvalidCombinator(CowRcStr<'i>),
+    /// A nested selector (e.g. `&.b`) that is not valid once combined with its parent selectors
+    InvalidNestedSelector(CowRcStr<'i>),
+    /// A style rule was nested more than one level deep, which is not supported
+    NestingTooDeep,
+    /// A keyframe selector that isn't `from`, `to`, or a percentage from `0%` to `100%`
+    InvalidKeyframeSelector(CowRcStr<'i>),
+    /// A `var()` could not be substituted (e.g. its custom property isn't declared), with the
+    /// declaration & why
+    InvalidVariable(CowRcStr<'i>),
+    /// Why a declaration won't be applied as written, though it was parsed (see
+    /// `BevyCssContextualError::Warning`)
+    Notice(CowRcStr<'i>),
     /// An unspecified or undefined error occurred.  Usually signifies low level parsing errors.
     UnspecifiedError,
 }
@@ -119,6 +221,22 @@ fn parse_error_2_str(err: &BevyCssParsingError) -> String {
         Basic(QualifiedRuleInvalid) => format!("The qualified rule is invalid"),
         Basic(UnexpectedToken(ref token)) =>
             format!("An unexpected {} was found", error_token_2_str(token)),
+        Custom(BevyCssParsingErrorKind::MissingValue(ref property)) =>
+            format!("Missing value for property {}", property),
+        Custom(BevyCssParsingErrorKind::AutoNotAllowed) => format!("'auto' is not valid here"),
+        Custom(BevyCssParsingErrorKind::WrongArgumentCount(ref function, ref expected)) =>
+            format!("{}() expects {} arguments", function, expected),
+        Custom(BevyCssParsingErrorKind::InvalidCombinator(ref combinator)) =>
+            format!("The combinator `{}` is not valid css, use one of `>`, `+`, `~` or whitespace", combinator),
+        Custom(BevyCssParsingErrorKind::InvalidNestedSelector(ref selector)) =>
+            format!("The nested selector `{}` is not valid once combined with its parent selectors", selector),
+        Custom(BevyCssParsingErrorKind::NestingTooDeep) =>
+            format!("Only one level of nested style rules is supported"),
+        Custom(BevyCssParsingErrorKind::InvalidKeyframeSelector(ref selector)) =>
+            format!("The keyframe selector `{}` is not valid, use `from`, `to` or a percentage", selector),
+        Custom(BevyCssParsingErrorKind::InvalidVariable(ref reason)) =>
+            format!("The `var()` could not be substituted in {}", reason),
+        Custom(BevyCssParsingErrorKind::Notice(ref message)) => message.to_string(),
         Custom(ref bevy_css_err) => format!("{:?}", bevy_css_err)
     }
 }
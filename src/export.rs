@@ -0,0 +1,205 @@
+use bevy::ui::{self, Style};
+use crate::{
+    properties::BevyPropertyDeclaration,
+    stylesheet::ParsedInlineStyle,
+    values::{
+        LengthPercentageOrAuto, Number, Ratio, RatioOrAuto, SidedValue, Size,
+        generic::{MaybeInitial, NonNegative},
+    },
+};
+
+// Convenience types
+type SideFunc = fn(MaybeInitial<LengthPercentageOrAuto>) -> BevyPropertyDeclaration;
+type ShorthandFunc = fn(MaybeInitial<SidedValue<LengthPercentageOrAuto>>) -> BevyPropertyDeclaration;
+
+/// Writes a `Style` as an inline css declaration block, e.g. `width: 100px; flex-direction: column;`.
+/// This is roughly the inverse of `CssStyle::to_style()`, so parsing the css gives back the same
+/// `Style`.  Only the fields that differ from `Style::default()` are written, and lengths are
+/// always in `px`.
+pub fn style_to_css(style: &Style) -> String {
+    ParsedInlineStyle {
+        declarations: style_declarations(style),
+        important_declarations: Vec::new(),
+    }.to_string()
+}
+
+/// The declarations that `style_to_css()` writes.  Values css has no way of writing (e.g.
+/// `ui::Val::Undefined`, or a negative `flex-grow`) are left out.
+pub fn style_declarations(style: &Style) -> Vec<BevyPropertyDeclaration> {
+    let default = Style::default();
+    let mut declarations = Vec::new();
+
+    // Display
+    if style.display != default.display {
+        declarations.push(BevyPropertyDeclaration::Display(style.display));
+    }
+    if style.direction != default.direction {
+        declarations.push(BevyPropertyDeclaration::Direction(style.direction));
+    }
+    let sizes: [(ui::Val, ui::Val, fn(Size) -> BevyPropertyDeclaration); 6] = [
+        (style.size.width, default.size.width, BevyPropertyDeclaration::Width),
+        (style.size.height, default.size.height, BevyPropertyDeclaration::Height),
+        (style.min_size.width, default.min_size.width, BevyPropertyDeclaration::MinWidth),
+        (style.min_size.height, default.min_size.height, BevyPropertyDeclaration::MinHeight),
+        (style.max_size.width, default.max_size.width, BevyPropertyDeclaration::MaxWidth),
+        (style.max_size.height, default.max_size.height, BevyPropertyDeclaration::MaxHeight),
+    ];
+    for (val, default_val, declaration) in sizes {
+        if val != default_val {
+            if let Ok(value) = LengthPercentageOrAuto::try_from(val) {
+                declarations.push(declaration(Size::NotIntrinsic(value)));
+            }
+        }
+    }
+    if style.overflow != default.overflow {
+        declarations.push(BevyPropertyDeclaration::Overflow(style.overflow));
+    }
+
+    // Position
+    if style.position_type != default.position_type {
+        declarations.push(BevyPropertyDeclaration::Position(style.position_type));
+    }
+    push_sides(&mut declarations, style.position, default.position, BevyPropertyDeclaration::Inset, [
+        BevyPropertyDeclaration::Top,
+        BevyPropertyDeclaration::Right,
+        BevyPropertyDeclaration::Bottom,
+        BevyPropertyDeclaration::Left,
+    ]);
+
+    // Flex Box
+    if style.flex_direction != default.flex_direction {
+        declarations.push(BevyPropertyDeclaration::FlexDirection(style.flex_direction));
+    }
+    if style.flex_wrap != default.flex_wrap {
+        declarations.push(BevyPropertyDeclaration::FlexWrap(style.flex_wrap));
+    }
+    if style.flex_grow != default.flex_grow && style.flex_grow >= 0.0 {
+        declarations.push(BevyPropertyDeclaration::FlexGrow(NonNegative(Number(style.flex_grow))));
+    }
+    if style.flex_shrink != default.flex_shrink && style.flex_shrink >= 0.0 {
+        declarations.push(BevyPropertyDeclaration::FlexShrink(NonNegative(Number(style.flex_shrink))));
+    }
+    if style.flex_basis != default.flex_basis {
+        if let Ok(value) = LengthPercentageOrAuto::try_from(style.flex_basis) {
+            declarations.push(BevyPropertyDeclaration::FlexBasis(value));
+        }
+    }
+    match style.aspect_ratio {
+        _ if style.aspect_ratio == default.aspect_ratio => (),
+        Some(ratio) if ratio < 0.0 => (),
+        Some(ratio) => declarations.push(BevyPropertyDeclaration::AspectRatio(
+            RatioOrAuto::NotAuto(Ratio(NonNegative(Number(ratio)), NonNegative(Number(1.0))))
+        )),
+        None => declarations.push(BevyPropertyDeclaration::AspectRatio(RatioOrAuto::Auto)),
+    }
+
+    // Alignment
+    if style.align_items != default.align_items {
+        declarations.push(BevyPropertyDeclaration::AlignItems(style.align_items));
+    }
+    if style.align_self != default.align_self {
+        declarations.push(BevyPropertyDeclaration::AlignSelf(style.align_self));
+    }
+    if style.align_content != default.align_content {
+        declarations.push(BevyPropertyDeclaration::AlignContent(style.align_content));
+    }
+    if style.justify_content != default.justify_content {
+        declarations.push(BevyPropertyDeclaration::JustifyContent(style.justify_content));
+    }
+
+    // Margins, Padding & Borders
+    push_sides(&mut declarations, style.margin, default.margin, BevyPropertyDeclaration::Margin, [
+        BevyPropertyDeclaration::MarginTop,
+        BevyPropertyDeclaration::MarginRight,
+        BevyPropertyDeclaration::MarginBottom,
+        BevyPropertyDeclaration::MarginLeft,
+    ]);
+    push_sides(&mut declarations, style.padding, default.padding, BevyPropertyDeclaration::Padding, [
+        BevyPropertyDeclaration::PaddingTop,
+        BevyPropertyDeclaration::PaddingRight,
+        BevyPropertyDeclaration::PaddingBottom,
+        BevyPropertyDeclaration::PaddingLeft,
+    ]);
+    push_sides(&mut declarations, style.border, default.border, BevyPropertyDeclaration::BorderWidth, [
+        BevyPropertyDeclaration::BorderWidthTop,
+        BevyPropertyDeclaration::BorderWidthRight,
+        BevyPropertyDeclaration::BorderWidthBottom,
+        BevyPropertyDeclaration::BorderWidthLeft,
+    ]);
+
+    declarations
+}
+
+/// The shorthand (e.g. `margin`) when every side has a css value, otherwise the longhands (e.g.
+/// `margin-top`) of the sides that differ from the default
+fn push_sides(
+    declarations: &mut Vec<BevyPropertyDeclaration>,
+    rect: ui::UiRect<ui::Val>,
+    default: ui::UiRect<ui::Val>,
+    shorthand: ShorthandFunc,
+    longhands: [SideFunc; 4],
+) {
+    if rect == default {
+        return
+    }
+    let sides = [rect.top, rect.right, rect.bottom, rect.left];
+    let default_sides = [default.top, default.right, default.bottom, default.left];
+    let values = sides.map(|val| LengthPercentageOrAuto::try_from(val).ok());
+    if let [Some(top), Some(right), Some(bottom), Some(left)] = values {
+        declarations.push(shorthand(MaybeInitial::NotInitial(SidedValue::new_4(top, right, bottom, left))));
+        return
+    }
+    for (((value, val), default_val), longhand) in values.into_iter().zip(sides).zip(default_sides).zip(longhands) {
+        if let Some(value) = value {
+            if val != default_val {
+                declarations.push(longhand(MaybeInitial::NotInitial(value)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CssContext, CssStyle};
+
+    #[test]
+    fn test_style_to_css() {
+        let style = Style {
+            size: bevy::prelude::Size::new(ui::Val::Px(100.0), ui::Val::Percent(50.0)),
+            margin: ui::UiRect {
+                top: ui::Val::Px(10.0),
+                right: ui::Val::Px(5.0),
+                bottom: ui::Val::Px(10.0),
+                left: ui::Val::Px(5.0),
+            },
+            padding: ui::UiRect { top: ui::Val::Auto, ..Default::default() },
+            flex_direction: ui::FlexDirection::Column,
+            ..Default::default()
+        };
+        assert_eq!(
+            style_to_css(&style),
+            "width: 100px; height: 50%; flex-direction: column; margin: 10px 5px; padding-top: auto;"
+        );
+        assert_eq!(style_to_css(&Style::default()), "");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let context = CssContext::default();
+        for css in [
+            "width: 100px; height: 50%; min-width: auto; max-height: 2em",
+            "display: none; position: absolute; top: 10px; left: 25%",
+            "inset: 1px 2px 3px 4px; margin: auto; padding: 5px 10px; border-width: 1px 0px",
+            "flex-flow: row-reverse wrap; flex: 2 0 30%; aspect-ratio: 16 / 9",
+            "align-items: center; align-self: flex-end; place-content: space-between center",
+            "overflow: hidden; direction: rtl; margin-left: -5px",
+        ] {
+            let style = CssStyle(css).to_style(&context);
+            let exported = style_to_css(&style);
+            assert_eq!(CssStyle(&exported).to_style(&context), style, "{}", exported);
+            // Writing it out again doesn't change it
+            assert_eq!(style_to_css(&CssStyle(&exported).to_style(&context)), exported);
+        }
+    }
+}
\ No newline at end of file
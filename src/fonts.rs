@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+use ab_glyph::Font as _;
+use bevy::{
+    asset::Handle,
+    text::Font,
+    utils::HashMap,
+};
+use crate::values::font::{FontFamily, FontWeight};
+
+/// The fonts that `font-family` & `font-weight` choose from, registered by their family name &
+/// weight.  It is a resource, so the app registers its own fonts (e.g. once loaded from the
+/// `AssetServer`), and css is re-applied whenever it changes.
+#[derive(Debug, Clone, Default)]
+pub struct CssFonts {
+    /// The fonts of each family, by their lowercase name
+    families: HashMap<String, Vec<(FontWeight, Handle<Font>)>>,
+}
+
+impl CssFonts {
+    /// Adds (or replaces) the `font` used for the `weight` of the `family`
+    pub fn register(&mut self, family: &str, weight: f32, font: Handle<Font>) {
+        let fonts = self.families.entry(family.to_ascii_lowercase()).or_default();
+        fonts.retain(|(other, _)| other.0 != weight);
+        fonts.push((FontWeight(weight), font));
+    }
+
+    /// The font for the first family with any registered fonts, with the nearest available weight
+    pub fn find(&self, family: &FontFamily, weight: FontWeight) -> Option<&Handle<Font>> {
+        family.0.iter()
+            .find_map(|name| self.families.get(&name.to_ascii_lowercase()))
+            .and_then(|fonts| fonts.iter()
+                .min_by(|(a, _), (b, _)| {
+                    weight_preference(weight, *a)
+                        .partial_cmp(&weight_preference(weight, *b))
+                        .unwrap_or(Ordering::Equal)
+                })
+            )
+            .map(|(_, font)| font)
+    }
+}
+
+/// The x-height & the advance width of the `0` glyph of the `font`, as fractions of its size (see
+/// `CssContext::x_height_ratio` & `CssContext::zero_advance_ratio`).  Either is `None` where the
+/// font has no glyph for it.
+pub(crate) fn font_ratios(font: &Font) -> (Option<f32>, Option<f32>) {
+    let font = &font.font;
+    let units_per_em = match font.units_per_em() {
+        Some(units_per_em) => units_per_em,
+        None => return (None, None),
+    };
+    // Glyph `0` is the font's "missing glyph"
+    let glyph = |c| Some(font.glyph_id(c)).filter(|id| id.0 != 0);
+    let x_height = glyph('x')
+        .and_then(|id| font.outline(id))
+        .map(|outline| outline.bounds.height() / units_per_em);
+    let zero_advance = glyph('0').map(|id| font.h_advance_unscaled(id) / units_per_em);
+    (x_height, zero_advance)
+}
+
+/// How well an `available` weight matches the `desired` one, where lower is better.  Weights are
+/// first grouped by the direction they're searched in, then by how far they are from the `desired`.
+/// See also: https://drafts.csswg.org/css-fonts/#font-style-matching
+fn weight_preference(desired: FontWeight, available: FontWeight) -> (u8, f32) {
+    let (desired, available) = (desired.0, available.0);
+    let group = if (400.0..=500.0).contains(&desired) {
+        // Up to 500, then lighter, then heavier than 500
+        if (desired..=500.0).contains(&available) { 0 } else if available < desired { 1 } else { 2 }
+    } else if desired < 400.0 {
+        // Lighter, then heavier
+        if available <= desired { 0 } else { 1 }
+    } else {
+        // Heavier, then lighter
+        if available >= desired { 0 } else { 1 }
+    };
+    (group, (available - desired).abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::HandleId;
+    use crate::values::Parse;
+    use super::*;
+
+    fn font() -> Handle<Font> {
+        Handle::weak(HandleId::random::<Font>())
+    }
+
+    #[test]
+    fn test_nearest_weight() {
+        let (regular, bold) = (font(), font());
+        let mut fonts = CssFonts::default();
+        fonts.register("Fira Sans", 400.0, regular.clone());
+        fonts.register("Fira Sans", 700.0, bold.clone());
+        let family = FontFamily::parse_str("fira sans").unwrap();
+        let find = |weight| fonts.find(&family, FontWeight(weight)).cloned();
+        assert_eq!(find(600.0), Some(bold.clone()));
+        assert_eq!(find(700.0), Some(bold.clone()));
+        assert_eq!(find(900.0), Some(bold.clone()));
+        assert_eq!(find(400.0), Some(regular.clone()));
+        assert_eq!(find(100.0), Some(regular.clone()));
+        // From 400 to 500, lighter weights are tried before heavier ones
+        assert_eq!(find(500.0), Some(regular.clone()));
+        // Above 500, heavier weights are tried before lighter ones, however close they are
+        fonts.register("Fira Sans", 550.0, font());
+        assert_eq!(fonts.find(&family, FontWeight(560.0)), Some(&bold));
+    }
+
+    #[test]
+    fn test_font_ratios() {
+        let font = Font::try_from_bytes(include_bytes!("../assets/fonts/FiraSans-Bold.ttf").to_vec()).unwrap();
+        let (x_height, zero_advance) = font_ratios(&font);
+        assert!((0.45..0.6).contains(&x_height.unwrap()), "{:?}", x_height);
+        assert!((0.45..0.7).contains(&zero_advance.unwrap()), "{:?}", zero_advance);
+    }
+
+    #[test]
+    fn test_family_fallback() {
+        let mono = font();
+        let mut fonts = CssFonts::default();
+        fonts.register("Fira Mono", 400.0, mono.clone());
+        let find = |css| fonts.find(&FontFamily::parse_str(css).unwrap(), FontWeight::NORMAL).cloned();
+        assert_eq!(find("Missing, 'Fira Mono'"), Some(mono));
+        assert_eq!(find("Missing, serif"), None);
+    }
+}
\ No newline at end of file
@@ -1,7 +1,11 @@
+pub mod animation;
 pub mod context;
 pub mod css_strings;
 pub mod css_tag;
 pub mod errors;
+pub mod export;
+pub mod fonts;
+pub mod media_queries;
 pub mod parser;
 pub mod plugin;
 pub mod properties;
@@ -12,14 +16,24 @@ pub mod values;
 
 pub mod prelude {
     pub use crate::context::CssContext;
-    pub use crate::css_tag::CssTag;
-    pub use crate::plugin::CssPlugin;
+    pub use crate::css_tag::{CssChecked, CssDisabled, CssTag};
+    pub use crate::errors::CssErrorSink;
+    pub use crate::fonts::CssFonts;
+    pub use crate::media_queries::{ColorScheme, CssMediaState};
+    pub use crate::plugin::{ApplyCssExt, CssPlugin, CssSettings, CssStylesheetOrder};
     pub use crate::stylesheet::{
-        CssStyle, CssStylesheet,
+        CssStyle, CssStylesheet, InlineCss, ParsedInlineStyle,
     };
 }
 
 pub use crate::prelude::{
-    CssPlugin, CssTag, CssStylesheet,       // For Stylesheets
-    CssContext, CssStyle,                   // For inline styles
+    CssPlugin, CssSettings, CssTag, CssStylesheet,  // For Stylesheets
+    CssStylesheetOrder,                             // For cascading multiple stylesheets
+    CssChecked, CssDisabled,                        // For `:checked`, `:disabled` & `:enabled`
+    CssContext, CssStyle, ParsedInlineStyle,        // For inline styles
+    InlineCss,                                      // For inline styles applied with stylesheets
+    ColorScheme, CssMediaState,                     // For `@media` rules
+    CssFonts,                                       // For `font-family` & `font-weight`
+    CssErrorSink,                                   // For routing stylesheet errors
+    ApplyCssExt,                                    // For stylesheets from strings, e.g. `commands.apply_css()`
 };
\ No newline at end of file
@@ -0,0 +1,343 @@
+use std::{
+    fmt,
+    sync::Arc,
+};
+use bevy::{
+    math::Vec2,
+    utils::HashMap,
+};
+use cssparser::{Parser, match_ignore_ascii_case, _cssparser_internal_to_lowercase};
+use crate::{
+    context::CssContext,
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::{Length, Parse},
+};
+
+/// Evaluates a custom media feature from its value, e.g. `Some("mobile")` for `(platform: mobile)`,
+/// or `None` for `(platform)`
+pub type MediaFeatureEvaluator = Arc<dyn Fn(Option<&str>) -> bool + Send + Sync>;
+
+/// The state of the app that `@media` queries are evaluated against.  It is a resource, so the app
+/// can change it (e.g. to switch to a dark theme), and css is re-applied whenever it changes.  The
+/// `viewport_size` is kept up to date with the primary window by the `CssPlugin`.
+#[derive(Clone)]
+pub struct CssMediaState {
+    /// For `prefers-color-scheme`
+    pub color_scheme: ColorScheme,
+    /// For `width` & `height` (inc. `min-` & `max-`), in logical pixels
+    pub viewport_size: Vec2,
+    /// The evaluators of any game specific media features (e.g. `(platform: mobile)`), by their
+    /// lowercase name.  Any other unknown features never match.
+    pub custom_features: HashMap<String, MediaFeatureEvaluator>,
+}
+
+impl CssMediaState {
+    /// Adds (or replaces) the `evaluator` for the custom media feature `name`.  The css is only
+    /// re-applied when this resource changes, so if the `evaluator`'s result can change, the
+    /// resource should be marked as changed then (e.g. with `set_changed()`).
+    pub fn register_feature(
+        &mut self,
+        name: &str,
+        evaluator: impl Fn(Option<&str>) -> bool + Send + Sync + 'static,
+    ) {
+        self.custom_features.insert(name.to_ascii_lowercase(), Arc::new(evaluator));
+    }
+}
+
+impl Default for CssMediaState {
+    fn default() -> Self {
+        Self {
+            color_scheme: ColorScheme::Light,
+            viewport_size: Vec2::ZERO,
+            custom_features: HashMap::default(),
+        }
+    }
+}
+
+impl fmt::Debug for CssMediaState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CssMediaState")
+            .field("color_scheme", &self.color_scheme)
+            .field("viewport_size", &self.viewport_size)
+            .field("custom_features", &self.custom_features.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// The values of the `prefers-color-scheme` media feature
+/// See also: https://drafts.csswg.org/mediaqueries-5/#prefers-color-scheme
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl Parse for ColorScheme {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { ident,
+            "light" => Self::Light,
+            "dark" => Self::Dark,
+            _ => return Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidValue(ident.clone(), None)
+            )),
+        })
+    }
+}
+
+/// The comma separated queries of an `@media` rule, which matches if any one of them does (or if
+/// there are none)
+/// See also: https://drafts.csswg.org/mediaqueries-4/#mq-list
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MediaQueryList(pub Vec<MediaQuery>);
+
+impl MediaQueryList {
+    pub fn matches(&self, state: &CssMediaState) -> bool {
+        self.0.is_empty() || self.0.iter().any(|query| query.matches(state))
+    }
+}
+
+impl Parse for MediaQueryList {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.is_exhausted() {
+            return Ok(Self(Vec::new()))
+        }
+        input.parse_comma_separated(MediaQuery::parse).map(Self)
+    }
+}
+
+/// One media query, e.g. `screen and (min-width: 600px)`.  Only `and` is supported between the
+/// features, and the whole query can be negated with `not`.
+/// See also: https://drafts.csswg.org/mediaqueries-4/#media
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MediaQuery {
+    pub negated: bool,
+    pub media_type: MediaType,
+    pub features: Vec<MediaFeature>,
+}
+
+impl MediaQuery {
+    pub fn matches(&self, state: &CssMediaState) -> bool {
+        let matches = self.media_type.matches() && self.features.iter().all(|feature| feature.matches(state));
+        matches != self.negated
+    }
+}
+
+impl Parse for MediaQuery {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let negated = input.try_parse(|i| i.expect_ident_matching("not")).is_ok();
+        if !negated {
+            let _ = input.try_parse(|i| i.expect_ident_matching("only"));
+        }
+        let media_type = input.try_parse(MediaType::parse).ok();
+        let mut features = Vec::new();
+        if media_type.is_none() {
+            features.push(MediaFeature::parse(input)?)
+        }
+        while input.try_parse(|i| i.expect_ident_matching("and")).is_ok() {
+            features.push(MediaFeature::parse(input)?)
+        }
+        Ok(Self {
+            negated,
+            media_type: media_type.unwrap_or(MediaType::All),
+            features,
+        })
+    }
+}
+
+/// Bevy only draws to a screen, so any other media type (e.g. `print`) never matches
+/// See also: https://drafts.csswg.org/mediaqueries-4/#media-types
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MediaType {
+    All,
+    Screen,
+    Other(String),
+}
+
+impl MediaType {
+    #[inline]
+    pub fn matches(&self) -> bool {
+        matches!(*self, Self::All | Self::Screen)
+    }
+}
+
+impl Parse for MediaType {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { ident,
+            "all" => Self::All,
+            "screen" => Self::Screen,
+            // Reserved, so they can't be mistaken for a media type
+            "not" | "only" | "and" | "or" => return Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidValue(ident.clone(), None)
+            )),
+            _ => Self::Other(ident.to_string()),
+        })
+    }
+}
+
+/// Whether a `width` or `height` media feature is exact, or a `min-` or `max-` bound
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MediaRange {
+    Exact,
+    Min,
+    Max,
+}
+
+impl MediaRange {
+    #[inline]
+    fn contains(self, value: f32, bound: f32) -> bool {
+        match self {
+            Self::Exact => value == bound,
+            Self::Min => value >= bound,
+            Self::Max => value <= bound,
+        }
+    }
+}
+
+/// A media feature between brackets, e.g. `(prefers-color-scheme: dark)`
+/// See also: https://drafts.csswg.org/mediaqueries-4/#mq-features
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MediaFeature {
+    PrefersColorScheme(ColorScheme),
+    Width(MediaRange, Length),
+    Height(MediaRange, Length),
+    /// Any other feature, with its (lowercase) name & its value (if any).  It is evaluated by the
+    /// `CssMediaState::custom_features` (e.g. `(platform: mobile)`), otherwise it never matches
+    /// (e.g. `(hover: hover)`).
+    Custom {
+        name: String,
+        value: Option<String>,
+    },
+}
+
+impl MediaFeature {
+    pub fn matches(&self, state: &CssMediaState) -> bool {
+        // Relative lengths (e.g. `em`) are relative to the initial values, not to any element
+        let context = CssContext { viewport_size: state.viewport_size, ..Default::default() };
+        match *self {
+            Self::PrefersColorScheme(color_scheme) => state.color_scheme == color_scheme,
+            Self::Width(range, length) => range.contains(state.viewport_size.x, length.to_computed_px(&context)),
+            Self::Height(range, length) => range.contains(state.viewport_size.y, length.to_computed_px(&context)),
+            Self::Custom { ref name, ref value } => match state.custom_features.get(name) {
+                Some(evaluator) => evaluator(value.as_deref()),
+                None => false,
+            },
+        }
+    }
+}
+
+impl Parse for MediaFeature {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        input.expect_parenthesis_block()?;
+        input.parse_nested_block(|input| {
+            let name = input.expect_ident()?.clone();
+            if input.try_parse(|i| i.expect_colon()).is_err() {
+                return Ok(Self::Custom { name: name.to_ascii_lowercase(), value: None })
+            }
+            Ok(match_ignore_ascii_case! { &name,
+                "prefers-color-scheme" => Self::PrefersColorScheme(ColorScheme::parse(input)?),
+                "width" => Self::Width(MediaRange::Exact, Length::parse(input)?),
+                "min-width" => Self::Width(MediaRange::Min, Length::parse(input)?),
+                "max-width" => Self::Width(MediaRange::Max, Length::parse(input)?),
+                "height" => Self::Height(MediaRange::Exact, Length::parse(input)?),
+                "min-height" => Self::Height(MediaRange::Min, Length::parse(input)?),
+                "max-height" => Self::Height(MediaRange::Max, Length::parse(input)?),
+                _ => {
+                    let start = input.position();
+                    while input.next().is_ok() {}
+                    Self::Custom {
+                        name: name.to_ascii_lowercase(),
+                        value: Some(input.slice_from(start).trim().to_string()),
+                    }
+                },
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::ParserInput;
+    use super::*;
+
+    fn parse_entirely(css: &str) -> Result<MediaQueryList, BevyCssParsingError> {
+        let mut parser_input = ParserInput::new(css);
+        let mut input = Parser::new(&mut parser_input);
+        input.parse_entirely(MediaQueryList::parse)
+    }
+
+    fn matches(css: &str, state: &CssMediaState) -> bool {
+        parse_entirely(css).unwrap().matches(state)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse_entirely("").unwrap(), MediaQueryList(Vec::new()));
+        assert_eq!(
+            parse_entirely("(prefers-color-scheme: dark)").unwrap(),
+            MediaQueryList(vec![MediaQuery {
+                negated: false,
+                media_type: MediaType::All,
+                features: vec![MediaFeature::PrefersColorScheme(ColorScheme::Dark)],
+            }])
+        );
+        assert_eq!(parse_entirely("only screen and (min-width: 600px), print").unwrap().0.len(), 2);
+        assert_eq!(
+            parse_entirely("(hover: hover)").unwrap().0[0].features,
+            vec![MediaFeature::Custom { name: "hover".to_string(), value: Some("hover".to_string()) }]
+        );
+        assert!(parse_entirely("(prefers-color-scheme: blue)").is_err());
+        assert!(parse_entirely("(min-width: wide)").is_err());
+        assert!(parse_entirely("screen (min-width: 600px)").is_err());
+        assert!(parse_entirely("and (min-width: 600px)").is_err());
+    }
+
+    #[test]
+    fn test_color_scheme() {
+        let light = CssMediaState::default();
+        let dark = CssMediaState { color_scheme: ColorScheme::Dark, ..Default::default() };
+        assert!(matches("(prefers-color-scheme: dark)", &dark));
+        assert!(!matches("(prefers-color-scheme: dark)", &light));
+        assert!(matches("(prefers-color-scheme: light)", &light));
+        assert!(matches("not all and (prefers-color-scheme: dark)", &light));
+        assert!(matches("(prefers-color-scheme: light), (prefers-color-scheme: dark)", &dark));
+    }
+
+    #[test]
+    fn test_sizes() {
+        let state = CssMediaState { viewport_size: Vec2::new(800.0, 600.0), ..Default::default() };
+        assert!(matches("(min-width: 600px)", &state));
+        assert!(!matches("(max-width: 600px)", &state));
+        assert!(matches("screen and (width: 800px) and (max-height: 600px)", &state));
+        assert!(matches("(min-height: 50vh)", &state));
+        assert!(!matches("print and (min-width: 600px)", &state));
+        assert!(!matches("(hover: hover)", &state));
+    }
+
+    #[test]
+    fn test_custom_features() {
+        let mut state = CssMediaState::default();
+        assert!(!matches("(platform: mobile)", &state));
+
+        state.register_feature("Platform", |value| value == Some("mobile"));
+        state.register_feature("touch", |value| value.is_none());
+        assert!(matches("(platform: mobile)", &state));
+        assert!(matches("(PLATFORM: mobile) and (touch)", &state));
+        assert!(!matches("(platform: desktop)", &state));
+        assert!(!matches("(platform)", &state));
+        assert!(!matches("(hover: hover)", &state));
+
+        state.register_feature("platform", |value| value == Some("desktop"));
+        assert!(!matches("(platform: mobile)", &state));
+        assert!(matches("(platform: desktop)", &state));
+    }
+}
\ No newline at end of file
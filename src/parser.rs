@@ -1,17 +1,19 @@
-use std::sync::Arc;
-use bevy::prelude::warn;
+use std::{cmp::Ordering, sync::Arc};
 use cssparser::{
-    AtRuleParser, CowRcStr, DeclarationListParser, DeclarationParser, Delimiter, ParseErrorKind,
-    Parser, ParserState, QualifiedRuleParser, RuleListParser
+    AtRuleParser, AtRuleType, CowRcStr, DeclarationListParser, DeclarationParser, Delimiter, ParseError,
+    ParseErrorKind, Parser, ParserState, QualifiedRuleParser, RuleListParser, Token,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
 };
 use crate::{
     errors::{
-        BevyCssContextualError, BevyCssParsingError, BevyCssParsingErrorKind
+        self, BevyCssContextualError, BevyCssParsingError, BevyCssParsingErrorKind, CssErrorSink,
     },
-    properties::BevyPropertyDeclaration,
+    media_queries::MediaQueryList,
+    properties::{BevyPropertyDeclaration, Importance},
     rules::{
-        BevyCssRule, BevyStyleRule
+        BevyCssRule, BevyKeyframe, BevyKeyframesRule, BevyMediaRule, BevyStyleRule
     },
+    values::{animation, Parse},
     selectors::BevySelectorList,
 };
 
@@ -20,32 +22,174 @@ pub struct BevySheetParser;
 
 impl BevySheetParser {
 
+    /// The rules, where any errors are reported to the default `CssErrorSink` (i.e. logged with
+    /// `warn!`)
     pub fn parse_with(input: &mut Parser) -> Vec<BevyCssRule> {
+        Self::parse_with_sink(input, &CssErrorSink::default())
+    }
+
+    /// The rules, with an error reported to the `sink` for each rule or declaration that was skipped
+    pub fn parse_with_sink(input: &mut Parser, sink: &CssErrorSink) -> Vec<BevyCssRule> {
+        let (rules, errors) = Self::parse_with_errors(input);
+        errors.iter().for_each(|error| sink.report(error));
+        rules
+    }
+
+    /// The rules, and an error for each rule or declaration that was skipped (in the order they
+    /// appear in the sheet).  Nothing is logged.
+    pub fn parse_with_errors<'i, 't>(
+        input: &mut Parser<'i, 't>
+    ) -> (Vec<BevyCssRule>, Vec<BevyCssContextualError<'i>>) {
         let list_parser =
-            RuleListParser::new_for_stylesheet(input, BevyTopLevelParser);
+            RuleListParser::new_for_stylesheet(input, BevyTopLevelParser::default());
+        Self::collect_rules(list_parser)
+    }
+
+    /// The rules of a (top level or nested) `RuleListParser`, where any invalid rules are skipped,
+    /// and the errors for everything skipped
+    fn collect_rules<'i, 't, 'a>(
+        mut list_parser: RuleListParser<'i, 't, 'a, BevyTopLevelParser<'i>>
+    ) -> (Vec<BevyCssRule>, Vec<BevyCssContextualError<'i>>) {
         let mut rules = Vec::new();
-        for result in list_parser {
+        while let Some(result) = list_parser.next() {
             match result {
-                Ok(rule) => rules.push(rule),
+                Ok(parsed_rules) => rules.extend(parsed_rules),
                 Err((err, bad_css)) =>
-                    BevySheetParser::handle_error(err, bad_css),
+                    BevySheetParser::handle_error(err, bad_css, &mut list_parser.parser.errors),
             }
         }
-        rules
+        (rules, list_parser.parser.errors)
     }
 
-    fn handle_error<'i>(err: BevyCssParsingError<'i>, bad_css: &'i str) {
-        warn!("{}", BevyCssContextualError::UnsupportedProperty(bad_css, err))
+    fn handle_error<'i>(
+        err: BevyCssParsingError<'i>,
+        bad_css: &'i str,
+        errors: &mut Vec<BevyCssContextualError<'i>>
+    ) {
+        let contextual_error = match err.kind {
+            ParseErrorKind::Custom(
+                BevyCssParsingErrorKind::SelectorError(_) |
+                BevyCssParsingErrorKind::InvalidCombinator(_) |
+                BevyCssParsingErrorKind::InvalidNestedSelector(_) |
+                BevyCssParsingErrorKind::NestingTooDeep |
+                BevyCssParsingErrorKind::InvalidKeyframeSelector(_)
+            ) => BevyCssContextualError::InvalidSelector(bad_css, err),
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::UnsupportedAtRule(_)) =>
+                BevyCssContextualError::UnsupportedAtRule(bad_css, err),
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::InvalidAtRule(_)) =>
+                BevyCssContextualError::InvalidAtRule(bad_css, err),
+            _ => BevyCssContextualError::UnsupportedProperty(bad_css, err),
+        };
+        errors.push(contextual_error)
     }
 }
 
 /// Top level parser that may delegates parsing to more specialised parsers based on what is
 /// encountered
-pub struct BevyTopLevelParser;
+#[derive(Default)]
+pub struct BevyTopLevelParser<'i> {
+    /// The errors for anything skipped inside the rules (e.g. invalid declarations)
+    errors: Vec<BevyCssContextualError<'i>>,
+}
+
+impl<'i> BevyTopLevelParser<'i> {
+
+    /// Parses the declarations of a style block, and any style rules nested in it (e.g. the `&.b`
+    /// in `.a { &.b { ... } }`).  The nested rules come after the rule for the block itself.
+    /// See also: https://drafts.csswg.org/css-nesting-1/#nesting
+    fn parse_style_block(&mut self, selectors: BevySelectorList, input: &mut Parser<'i, '_>) -> Vec<BevyCssRule> {
+        let (declarations, important_declarations, nested_rules) =
+            Self::parse_block_contents(input, Some(&selectors), &mut self.errors);
+        let style = BevyStyleRule {
+            selectors,
+            declarations: Arc::new(declarations),
+            important_declarations: Arc::new(important_declarations),
+        };
+        let mut rules = vec![BevyCssRule::Style(style)];
+        rules.extend(nested_rules);
+        rules
+    }
+
+    /// The normal & `!important` declarations of a block, and the rules nested in it.  Only one
+    /// level of nesting is supported, so rules can only be nested when there are `parent` selectors
+    /// (i.e. the block isn't itself nested).  Otherwise they are skipped, with an error.
+    fn parse_block_contents(
+        input: &mut Parser<'i, '_>,
+        parent: Option<&BevySelectorList>,
+        errors: &mut Vec<BevyCssContextualError<'i>>,
+    ) -> (Vec<BevyPropertyDeclaration>, Vec<BevyPropertyDeclaration>, Vec<BevyCssRule>) {
+        let mut declarations = Vec::new();
+        let mut important_declarations = Vec::new();
+        let mut nested_rules = Vec::new();
+        loop {
+            input.skip_whitespace();
+            if input.is_exhausted() {
+                break
+            }
+            if !Self::is_nested_rule(input) {
+                // One declaration at a time, so a nested rule can follow it
+                let _ = input.parse_until_after(Delimiter::Semicolon, |input| {
+                    let (normal, important) = BevyPropertyListParser::parse_with_errors(input, errors);
+                    declarations.extend(normal);
+                    important_declarations.extend(important);
+                    Ok::<_, BevyCssParsingError>(())
+                });
+                continue
+            }
+            match parent {
+                Some(parent) => match Self::parse_nested_rule(parent, input, errors) {
+                    Ok(rule) => nested_rules.push(rule),
+                    Err((err, bad_css)) => BevySheetParser::handle_error(err, bad_css, errors),
+                },
+                None => {
+                    let start = input.position();
+                    let err = input.new_custom_error(BevyCssParsingErrorKind::NestingTooDeep);
+                    let _ = input.parse_until_after(Delimiter::CurlyBracketBlock, |_| {
+                        Ok::<_, BevyCssParsingError>(())
+                    });
+                    BevySheetParser::handle_error(err, input.slice_from(start), errors)
+                },
+            }
+        }
+        (declarations, important_declarations, nested_rules)
+    }
+
+    /// Nested style rules have to start with `&`, so they can't be mistaken for declarations
+    fn is_nested_rule(input: &mut Parser) -> bool {
+        let state = input.state();
+        let is_nested = input.expect_delim('&').is_ok();
+        input.reset(&state);
+        is_nested
+    }
+
+    fn parse_nested_rule<'t>(
+        parent: &BevySelectorList,
+        input: &mut Parser<'i, 't>,
+        errors: &mut Vec<BevyCssContextualError<'i>>,
+    ) -> Result<BevyCssRule, (BevyCssParsingError<'i>, &'i str)> {
+        let start = input.position();
+        let selectors = input.parse_until_before(Delimiter::CurlyBracketBlock, |input| {
+            BevySelectorList::parse_nested(input, parent)
+        });
+        // The block is always consumed, so an invalid selector doesn't affect what follows it
+        let block = input.expect_curly_bracket_block()
+            .map_err(BevyCssParsingError::from)
+            .and_then(|_| input.parse_nested_block(|input| Ok(Self::parse_block_contents(input, None, errors))));
+        match (selectors, block) {
+            (Ok(selectors), Ok((declarations, important_declarations, _))) =>
+                Ok(BevyCssRule::Style(BevyStyleRule {
+                    selectors,
+                    declarations: Arc::new(declarations),
+                    important_declarations: Arc::new(important_declarations),
+                })),
+            (Err(err), _) | (_, Err(err)) => Err((err, input.slice_from(start))),
+        }
+    }
+}
 
-impl<'i> QualifiedRuleParser<'i> for BevyTopLevelParser {    // aka 'normal' style rule parser
+impl<'i> QualifiedRuleParser<'i> for BevyTopLevelParser<'i> {    // aka 'normal' style rule parser
     type Prelude = BevySelectorList;
-    type QualifiedRule = BevyCssRule;
+    type QualifiedRule = Vec<BevyCssRule>;
     type Error = BevyCssParsingErrorKind<'i>;
 
     fn parse_prelude<'t>(                                    // Prelude here means selector list
@@ -60,20 +204,141 @@ impl<'i> QualifiedRuleParser<'i> for BevyTopLevelParser {    // aka 'normal' sty
         _start: &ParserState,
         input: &mut Parser<'i, 't>
     ) -> Result<Self::QualifiedRule, BevyCssParsingError<'i>> {
-        let declarations = BevyPropertyListParser::parse_with(input);
-        let style = BevyStyleRule {
-            selectors: prelude,
-            declarations: Arc::new(declarations),
-        };
-        Ok(BevyCssRule::Style(style))
+        Ok(self.parse_style_block(prelude, input))
     }
 }
 
+/// The prelude of a supported @-rule, i.e. everything before its block
+pub enum BevyAtRulePrelude {
+    /// The media queries of an `@media` rule
+    Media(MediaQueryList),
+    /// The name of an `@keyframes` rule
+    Keyframes(String),
+}
+
 // @todo support @font-face
-impl<'i> AtRuleParser<'i> for BevyTopLevelParser {
+impl<'i> AtRuleParser<'i> for BevyTopLevelParser<'i> {
+    type PreludeNoBlock = ();
+    type PreludeBlock = BevyAtRulePrelude;
+    type AtRule = Vec<BevyCssRule>;
+    type Error = BevyCssParsingErrorKind<'i>;
+
+    fn parse_prelude<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>
+    ) -> Result<AtRuleType<Self::PreludeNoBlock, Self::PreludeBlock>, BevyCssParsingError<'i>> {
+        let start = input.position();
+        let prelude = match_ignore_ascii_case! { &name,
+            "media" => input.parse_entirely(MediaQueryList::parse).map(BevyAtRulePrelude::Media),
+            "keyframes" => input.parse_entirely(animation::parse_keyframes_name).map(BevyAtRulePrelude::Keyframes),
+            _ => return Err(input.new_custom_error(BevyCssParsingErrorKind::UnsupportedAtRule(name.clone())))
+        };
+        prelude
+            .map(AtRuleType::WithBlock)
+            .map_err(|err| err.location.new_custom_error(
+                BevyCssParsingErrorKind::InvalidAtRule(input.slice_from(start).trim().into())
+            ))
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: Self::PreludeBlock,
+        _start: &ParserState,
+        input: &mut Parser<'i, 't>
+    ) -> Result<Self::AtRule, BevyCssParsingError<'i>> {
+        match prelude {
+            BevyAtRulePrelude::Media(queries) => {
+                let list_parser = RuleListParser::new_for_nested_rule(input, BevyTopLevelParser::default());
+                let (rules, errors) = BevySheetParser::collect_rules(list_parser);
+                self.errors.extend(errors);
+                Ok(vec![BevyCssRule::Media(BevyMediaRule { queries, rules })])
+            },
+            BevyAtRulePrelude::Keyframes(name) => {
+                let (frames, errors) = BevyKeyframesParser::parse_keyframes(input);
+                self.errors.extend(errors);
+                Ok(vec![BevyCssRule::Keyframes(BevyKeyframesRule { name, frames })])
+            },
+        }
+    }
+}
+
+/// Parses the keyframes in the block of an `@keyframes` rule, e.g. `from { ... } 50%, to { ... }`
+/// See also: https://drafts.csswg.org/css-animations/#keyframes
+#[derive(Default)]
+pub struct BevyKeyframesParser<'i> {
+    /// The errors for anything skipped inside the keyframes (e.g. invalid declarations)
+    errors: Vec<BevyCssContextualError<'i>>,
+}
+
+impl<'i> BevyKeyframesParser<'i> {
+
+    /// The keyframes of the block (in order of their offsets), and the errors for everything skipped
+    pub fn parse_keyframes<'t>(
+        input: &mut Parser<'i, 't>
+    ) -> (Vec<BevyKeyframe>, Vec<BevyCssContextualError<'i>>) {
+        let mut list_parser = RuleListParser::new_for_nested_rule(input, Self::default());
+        let mut frames = Vec::new();
+        while let Some(result) = list_parser.next() {
+            match result {
+                Ok(parsed_frames) => frames.extend(parsed_frames),
+                Err((err, bad_css)) =>
+                    BevySheetParser::handle_error(err, bad_css, &mut list_parser.parser.errors),
+            }
+        }
+        // A stable sort, so keyframes with the same offset stay in source order (i.e. the last wins)
+        frames.sort_by(|a: &BevyKeyframe, b| a.offset.partial_cmp(&b.offset).unwrap_or(Ordering::Equal));
+        (frames, list_parser.parser.errors)
+    }
+
+    /// A keyframe selector, i.e. `from` (`0%`), `to` (`100%`) or a percentage between them, as a
+    /// fraction of the animation
+    fn parse_offset<'t>(input: &mut Parser<'i, 't>) -> Result<f32, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        match *input.next()? {
+            Token::Ident(ref ident) if ident.eq_ignore_ascii_case("from") => Ok(0.0),
+            Token::Ident(ref ident) if ident.eq_ignore_ascii_case("to") => Ok(1.0),
+            Token::Percentage { unit_value, .. } if (0.0..=1.0).contains(&unit_value) => Ok(unit_value),
+            ref token => Err(start.new_unexpected_token_error(token.clone())),
+        }
+    }
+}
+
+impl<'i> QualifiedRuleParser<'i> for BevyKeyframesParser<'i> {
+    type Prelude = Vec<f32>;
+    type QualifiedRule = Vec<BevyKeyframe>;
+    type Error = BevyCssParsingErrorKind<'i>;
+
+    fn parse_prelude<'t>(                                    // The keyframe selectors, e.g. `from, 50%`
+        &mut self, input: &mut Parser<'i, 't>
+    ) -> Result<Self::Prelude, BevyCssParsingError<'i>> {
+        let start = input.position();
+        input.parse_entirely(|input| input.parse_comma_separated(Self::parse_offset)).map_err(|err| {
+            // The whole prelude, for the error
+            while input.next().is_ok() {}
+            err.location.new_custom_error(
+                BevyCssParsingErrorKind::InvalidKeyframeSelector(input.slice_from(start).trim().into())
+            )
+        })
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: Self::Prelude,
+        _start: &ParserState,
+        input: &mut Parser<'i, 't>
+    ) -> Result<Self::QualifiedRule, BevyCssParsingError<'i>> {
+        // `!important` declarations are ignored in keyframes
+        let (declarations, _) = BevyPropertyListParser::parse_with_errors(input, &mut self.errors);
+        let declarations = Arc::new(declarations);
+        Ok(prelude.into_iter().map(|offset| BevyKeyframe { offset, declarations: declarations.clone() }).collect())
+    }
+}
+
+impl<'i> AtRuleParser<'i> for BevyKeyframesParser<'i> {                   // Required by `cssparser`
     type PreludeNoBlock = ();
     type PreludeBlock = ();
-    type AtRule = BevyCssRule;
+    type AtRule = Vec<BevyKeyframe>;
     type Error = BevyCssParsingErrorKind<'i>;
 }
 
@@ -82,35 +347,74 @@ pub struct BevyPropertyListParser;
 
 impl BevyPropertyListParser {
 
+    /// All the declarations in the order they should be applied, i.e. the `!important` ones last.
+    /// Any errors are reported to the default `CssErrorSink` (i.e. logged with `warn!`).
     pub fn parse_with(input: &mut Parser) -> Vec<BevyPropertyDeclaration> {
+        let (mut declarations, important_declarations) =
+            Self::parse_with_importance(input, &CssErrorSink::default());
+        declarations.extend(important_declarations);
+        declarations
+    }
+
+    /// The normal & `!important` declarations, separately (each in the order they were declared),
+    /// with an error reported to the `sink` for each declaration that was skipped
+    pub fn parse_with_importance(
+        input: &mut Parser,
+        sink: &CssErrorSink,
+    ) -> (Vec<BevyPropertyDeclaration>, Vec<BevyPropertyDeclaration>) {
+        let mut errors = Vec::new();
+        let declarations = Self::parse_with_errors(input, &mut errors);
+        errors.iter().for_each(|error| sink.report(error));
+        declarations
+    }
+
+    /// The same as `parse_with_importance()`, but with an error added to `errors` for each
+    /// declaration that is skipped (and a warning for each one that won't be applied as written),
+    /// instead of it being reported
+    pub fn parse_with_errors<'i, 't>(
+        input: &mut Parser<'i, 't>,
+        errors: &mut Vec<BevyCssContextualError<'i>>,
+    ) -> (Vec<BevyPropertyDeclaration>, Vec<BevyPropertyDeclaration>) {
         let list_parser =
             DeclarationListParser::new(input, BevyPropertyDeclarationParser);
         let mut declarations = Vec::new();
+        let mut important_declarations = Vec::new();
         for result in list_parser {
             match result {
-                Ok(dec) => declarations.push(dec),
+                Ok((dec, importance, warnings)) => {
+                    match importance {
+                        Importance::Normal => declarations.push(dec),
+                        Importance::Important => important_declarations.push(dec),
+                    }
+                    errors.extend(warnings)
+                },
                 Err((err, bad_css)) =>
-                    BevyPropertyListParser::handle_error(err, bad_css),
+                    BevyPropertyListParser::handle_error(err, bad_css, errors),
             }
         }
-        declarations
+        (declarations, important_declarations)
     }
 
-    fn handle_error<'i>(err: BevyCssParsingError<'i>, bad_css: &'i str) {
+    fn handle_error<'i>(
+        err: BevyCssParsingError<'i>,
+        bad_css: &'i str,
+        errors: &mut Vec<BevyCssContextualError<'i>>
+    ) {
         let contextual_error = match err.kind {
             ParseErrorKind::Custom(BevyCssParsingErrorKind::UnknownProperty(_)) =>
                 BevyCssContextualError::UnsupportedProperty(bad_css, err),
             _ => BevyCssContextualError::InvalidValue(bad_css, err),
         };
-        warn!("{}", contextual_error)
+        errors.push(contextual_error)
     }
 }
 
-/// Parses one single property declaration
+/// Parses one single property declaration, with a warning for each reason it won't be applied as
+/// written (e.g. a clamped value)
 pub struct BevyPropertyDeclarationParser;
 
 impl<'i> DeclarationParser<'i> for BevyPropertyDeclarationParser {
-    type Declaration = BevyPropertyDeclaration;
+    type Declaration = (BevyPropertyDeclaration, Importance, Vec<BevyCssContextualError<'i>>);
     type Error = BevyCssParsingErrorKind<'i>;
 
     fn parse_value<'t>(
@@ -119,27 +423,271 @@ impl<'i> DeclarationParser<'i> for BevyPropertyDeclarationParser {
         input: &mut Parser<'i, 't>
     ) -> Result<Self::Declaration, BevyCssParsingError<'i>> {
 
-        let property = input.parse_until_before(Delimiter::Bang, |input| {
+        input.skip_whitespace();
+        let start = input.position();
+        let location = input.current_source_location();
+        let (property, mut notices) = errors::collect_notices(|| input.parse_until_before(Delimiter::Bang, |input| {
+            // Whitespace & comments are skipped, so `width: /* comment */ ;` has no value either
+            if input.is_exhausted() {
+                return Err(input.new_custom_error(BevyCssParsingErrorKind::MissingValue(name.clone())))
+            }
             // `parse_input` checks that a) the name is valid, and b) if it can parse the input
-            BevyPropertyDeclaration::parse_input(name, input)
-        })?;
-
-        // Consume any `!important` rules
-        // @fixme currently !important rules aren't treated any differently
-        let _important = match input.try_parse(cssparser::parse_important) {
-            Ok(()) => true,
-            Err(_) => false,
+            BevyPropertyDeclaration::parse_input(name.clone(), input)
+        }));
+        let property = property?;
+
+        let importance = match input.try_parse(cssparser::parse_important) {
+            Ok(()) => Importance::Important,
+            Err(_) => Importance::Normal,
         };
 
         input.expect_exhausted()?;       // Roll back (i.e. return err) if there is still input left
 
-        Ok(property)
+        if property.is_unsupported() {
+            notices.push(format!("The property `{}` is not supported by Bevy, and will be ignored", name))
+        }
+
+        let css = input.slice_from(start).trim();
+        let warnings = notices.into_iter()
+            .map(|notice| BevyCssContextualError::Warning(css, ParseError {
+                kind: ParseErrorKind::Custom(BevyCssParsingErrorKind::Notice(notice.into())),
+                location,
+            }))
+            .collect();
+        Ok((property, importance, warnings))
     }
 }
 
 impl<'i> AtRuleParser<'i> for BevyPropertyDeclarationParser {             // Required by `cssparser`
     type PreludeNoBlock = ();
     type PreludeBlock = ();
-    type AtRule = BevyPropertyDeclaration;
+    type AtRule = (BevyPropertyDeclaration, Importance, Vec<BevyCssContextualError<'i>>);
     type Error = BevyCssParsingErrorKind<'i>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cssparser::ParserInput;
+
+    fn first_declaration_error(css: &str) -> ParseErrorKind<BevyCssParsingErrorKind> {
+        let mut parser_input = ParserInput::new(css);
+        let mut input = Parser::new(&mut parser_input);
+        let mut list_parser =
+            DeclarationListParser::new(&mut input, BevyPropertyDeclarationParser);
+        let (err, _) = list_parser.next().unwrap().unwrap_err();
+        err.kind
+    }
+
+    #[test]
+    fn test_empty_value() {
+        assert!(matches!(
+            first_declaration_error("width: ;"),
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::MissingValue(_))
+        ));
+        assert!(matches!(
+            first_declaration_error("width:;"),
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::MissingValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_comment_only_value() {
+        assert!(matches!(
+            first_declaration_error("width: /* comment */ ;"),
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::MissingValue(_))
+        ));
+        assert!(matches!(
+            first_declaration_error("width: /* comment */ !important;"),
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::MissingValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_importance() {
+        let css = "width: 1px !important; height: 2px; width: 3px; height: 4px ! IMPORTANT;";
+        let mut parser_input = ParserInput::new(css);
+        let mut input = Parser::new(&mut parser_input);
+        let (normal, important) = BevyPropertyListParser::parse_with_importance(&mut input, &CssErrorSink::default());
+        assert_eq!(normal.len(), 2);
+        assert_eq!(important.len(), 2);
+
+        // Without the split, the important declarations come last
+        let mut parser_input = ParserInput::new(css);
+        let mut input = Parser::new(&mut parser_input);
+        let all = BevyPropertyListParser::parse_with(&mut input);
+        assert_eq!(all, [normal, important].concat());
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        // A bad declaration is skipped up to the next `;`, without affecting the one after it
+        let declarations = |css: &str| BevyPropertyListParser::parse_with(&mut Parser::new(&mut ParserInput::new(css)));
+        for css in [
+            "width: bad; height: 20px",
+            "width: 10px 20px; height: 20px",
+            "width: 10px !important bad; height: 20px",
+            "width: (bad; still bad); height: 20px",
+            "colour: red; height: 20px",
+        ] {
+            let mut parser_input = ParserInput::new(css);
+            let mut errors = Vec::new();
+            let (normal, important) =
+                BevyPropertyListParser::parse_with_errors(&mut Parser::new(&mut parser_input), &mut errors);
+            assert_eq!(normal, declarations("height: 20px"), "{}", css);
+            assert!(important.is_empty(), "{}", css);
+            assert_eq!(errors.len(), 1, "{}", css);
+        }
+        let mut parser_input = ParserInput::new("width: bad; height: 20px");
+        let mut errors = Vec::new();
+        BevyPropertyListParser::parse_with_errors(&mut Parser::new(&mut parser_input), &mut errors);
+        assert!(matches!(errors[0], BevyCssContextualError::InvalidValue(bad_css, _) if bad_css.starts_with("width")));
+
+        // The same within a rule's block, where the error is kept with the sheet's errors
+        let mut parser_input = ParserInput::new(".a { width: bad; height: 20px } .b { width: 1px; }");
+        let (rules, errors) = BevySheetParser::parse_with_errors(&mut Parser::new(&mut parser_input));
+        let rules: Vec<&BevyStyleRule> = rules.iter().flat_map(BevyCssRule::style_rules).collect();
+        assert_eq!(*rules[0].declarations, declarations("height: 20px"));
+        assert_eq!(*rules[1].declarations, declarations("width: 1px"));
+        assert!(matches!(errors[..], [BevyCssContextualError::InvalidValue(..)]));
+    }
+
+    #[test]
+    fn test_warnings() {
+        // The declarations are kept, each with a warning for why it won't be applied as written
+        let mut parser_input = ParserInput::new(
+            "opacity: 1.5; gap: 1px; overflow: hidden visible !important; place-self: center end; width: 1px"
+        );
+        let mut errors = Vec::new();
+        let (normal, important) =
+            BevyPropertyListParser::parse_with_errors(&mut Parser::new(&mut parser_input), &mut errors);
+        assert_eq!((normal.len(), important.len()), (3, 1));
+        assert_eq!(errors.len(), 4);
+        assert!(errors.iter().all(BevyCssContextualError::is_warning));
+        assert!(matches!(errors[0], BevyCssContextualError::Warning("1.5", _)));
+        assert!(errors[1].to_string().contains("`gap` is not supported"));
+        assert!(errors[2].error_string_with_location().starts_with("Parsed css at (line: 0, col: 35)"));
+        assert!(errors[3].to_string().contains("`justify-self`"));
+    }
+
+    fn parse_sheet(css: &str) -> Vec<BevyStyleRule> {
+        let mut parser_input = ParserInput::new(css);
+        let mut input = Parser::new(&mut parser_input);
+        BevySheetParser::parse_with(&mut input).iter()
+            .flat_map(BevyCssRule::style_rules)
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_nesting() {
+        let rules = parse_sheet(".a { width: 1px; &.b { width: 2px; } height: 3px; } .c { width: 4px; }");
+        let selectors: Vec<String> = rules.iter().map(|rule| rule.selectors.to_string()).collect();
+        assert_eq!(selectors, vec![".a", ".a.b", ".c"]);
+        assert_eq!(rules[0].declarations.len(), 2);
+        assert_eq!(rules[1].declarations.len(), 1);
+    }
+
+    #[test]
+    fn test_nesting_selector_lists() {
+        let rules = parse_sheet(".a, #b { & > .c, &.d { width: 1px !important; } }");
+        assert_eq!(rules[1].selectors.to_string(), ".a > .c, #b > .c, .a.d, #b.d");
+        assert_eq!(rules[1].important_declarations.len(), 1);
+    }
+
+    #[test]
+    fn test_bad_nesting() {
+        // Invalid & too deeply nested rules are skipped, without affecting the rest of the block
+        let rules = parse_sheet(".a { &..b { width: 1px; } &.c { &.d { width: 2px; } height: 3px; } width: 4px; }");
+        let selectors: Vec<String> = rules.iter().map(|rule| rule.selectors.to_string()).collect();
+        assert_eq!(selectors, vec![".a", ".a.c"]);
+        assert_eq!(rules[0].declarations.len(), 1);
+        assert_eq!(rules[1].declarations.len(), 1);
+    }
+
+    #[test]
+    fn test_nesting_complex_parent() {
+        // `.c + &` can't be written without `:is(.a .b)`, so only the rule with `&` at the start is kept
+        let rules = parse_sheet(".a .b { &.c, .c + & { width: 1px; } & > .d { width: 2px; } }");
+        let selectors: Vec<String> = rules.iter().map(|rule| rule.selectors.to_string()).collect();
+        assert_eq!(selectors, vec![".a .b", ".a .b > .d"]);
+    }
+
+    #[test]
+    fn test_media_rules() {
+        let mut parser_input = ParserInput::new(
+            ".a { width: 1px; } @media (prefers-color-scheme: dark) { .a { width: 2px; } .b { width: 3px; } } .c { }"
+        );
+        let mut input = Parser::new(&mut parser_input);
+        let rules = BevySheetParser::parse_with(&mut input);
+        assert_eq!(rules.len(), 3);
+        match &rules[1] {
+            BevyCssRule::Media(media_rule) => {
+                assert_eq!(media_rule.queries, MediaQueryList::parse_str("(prefers-color-scheme: dark)").unwrap());
+                assert_eq!(media_rule.rules.len(), 2);
+            },
+            rule => panic!("Expected an @media rule, not {:?}", rule),
+        }
+    }
+
+    #[test]
+    fn test_keyframes_rules() {
+        let mut parser_input = ParserInput::new(
+            "@keyframes pulse { to { opacity: 1; } FROM { opacity: 0; width: 10px !important; } } .a { }"
+        );
+        let mut input = Parser::new(&mut parser_input);
+        let (rules, errors) = BevySheetParser::parse_with_errors(&mut input);
+        assert!(errors.is_empty());
+        assert_eq!(rules.len(), 2);
+        let declarations = |css: &str| BevyPropertyListParser::parse_with(&mut Parser::new(&mut ParserInput::new(css)));
+        match &rules[0] {
+            BevyCssRule::Keyframes(keyframes) => {
+                assert_eq!(keyframes.name, "pulse");
+                // In order of their offsets, without the `!important` declaration
+                assert_eq!(keyframes.frames, vec![
+                    BevyKeyframe { offset: 0.0, declarations: Arc::new(declarations("opacity: 0;")) },
+                    BevyKeyframe { offset: 1.0, declarations: Arc::new(declarations("opacity: 1;")) },
+                ]);
+            },
+            rule => panic!("Expected an @keyframes rule, not {:?}", rule),
+        }
+        assert!(rules[0].style_rules().is_empty());
+    }
+
+    #[test]
+    fn test_keyframe_selectors() {
+        let mut parser_input = ParserInput::new(
+            "@keyframes \"fade in\" { 0%, 50% { opacity: 0; } 150% { opacity: 2; } to { opacity: 1; } }"
+        );
+        let mut input = Parser::new(&mut parser_input);
+        let (rules, errors) = BevySheetParser::parse_with_errors(&mut input);
+        match &rules[0] {
+            BevyCssRule::Keyframes(keyframes) => {
+                assert_eq!(keyframes.name, "fade in");
+                let offsets: Vec<f32> = keyframes.frames.iter().map(|frame| frame.offset).collect();
+                assert_eq!(offsets, vec![0.0, 0.5, 1.0]);
+            },
+            rule => panic!("Expected an @keyframes rule, not {:?}", rule),
+        }
+        // The invalid keyframe is skipped
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0].parsing_error().kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::InvalidKeyframeSelector(ref selector)) if &**selector == "150%"
+        ));
+        // `none` can't be the name
+        let mut parser_input = ParserInput::new("@keyframes none { } .a { }");
+        let (rules, errors) = BevySheetParser::parse_with_errors(&mut Parser::new(&mut parser_input));
+        assert_eq!((rules.len(), errors.len()), (1, 1));
+    }
+
+    #[test]
+    fn test_bad_at_rules() {
+        // Skipped, without affecting the rules around them
+        let rules = parse_sheet(
+            ".a { } @media (prefers-color-scheme: blue) { .b { } } @font-feature-values Font { } .c { }"
+        );
+        let selectors: Vec<String> = rules.iter().map(|rule| rule.selectors.to_string()).collect();
+        assert_eq!(selectors, vec![".a", ".c"]);
+    }
 }
\ No newline at end of file
@@ -1,9 +1,22 @@
-use bevy::prelude::*;
+use std::fmt;
+use bevy::{
+    asset::HandleId,
+    ecs::system::Command,
+    prelude::*,
+    utils::{Duration, HashMap, HashSet, Instant},
+};
 use crate::{
+    animation::{self, AnimatedValues, CssAnimation, CssTransition},
     context::CssContext,
-    css_tag::CssTag,
-    rules::{BevyCssRule, BevyStyleRule},
-    stylesheet::{CssStylesheet, CssStylesheetLoader}
+    css_tag::{CssChecked, CssDisabled, CssTag},
+    errors::CssErrorSink,
+    fonts::CssFonts,
+    media_queries::CssMediaState,
+    properties::{custom, size_conflicts, BevyPropertyDeclaration},
+    rules::{BevyCssRule, BevyKeyframesRule},
+    selectors::{BevyElement, ElementTree},
+    stylesheet::{CssStylesheet, CssStylesheetLoader, InlineCss},
+    values::{font::FontWeight, AnimationSpec, CssWideKeyword, TransitionList},
 };
 
 pub struct CssPlugin;
@@ -12,53 +25,1327 @@ impl Plugin for CssPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_asset::<CssStylesheet>()
+            // For the metrics of `ex` & `ch` lengths (also added by Bevy's `TextPlugin`)
+            .add_asset::<Font>()
+            .init_resource::<CssErrorSink>()
             .init_asset_loader::<CssStylesheetLoader>()
-            .add_system(apply_styles);
+            .init_resource::<CssSettings>()
+            .init_resource::<CssMediaState>()
+            .init_resource::<CssStylesheetOrder>()
+            .init_resource::<CssFonts>()
+            .add_system_to_stage(CoreStage::PreUpdate, update_media_viewport)
+            .add_system_to_stage(CoreStage::PreUpdate, update_stylesheet_order)
+            .add_system(apply_styles)
+            .add_system(animation::update_transitions)
+            .add_system(animation::update_animations);
+    }
+}
+
+/// Settings for the `CssPlugin`.  Insert this resource before adding the plugin to change them.
+/// Changing them later re-applies the css to every entity.
+#[derive(Debug, Clone)]
+pub struct CssSettings {
+    /// Whether to `info!` how many rules were evaluated, how many entities they matched, and how
+    /// long it took, each time the styles are applied.  Off by default, for performance debugging.
+    pub log_style_stats: bool,
+    /// The root entity, like the `<html>` element in a browser, whose font size `rem` lengths are
+    /// relative to.  That is the font size of its `Text` once its css is applied (e.g. a
+    /// `font-size: 20px`).  `None` by default.
+    pub root_entity: Option<Entity>,
+    /// The font size for `rem` lengths when there is no `root_entity` (or it has no `Text`), which is
+    /// also what `rem` lengths in the root entity's own css are relative to.  The default
+    /// `TextStyle` font size by default.
+    pub root_font_size: f32,
+    /// Whether to `warn!` where an entity's min size is larger than its max size (see
+    /// `CssContext::warn_size_conflicts`).  On by default.
+    pub warn_size_conflicts: bool,
+}
+
+impl Default for CssSettings {
+    fn default() -> Self {
+        Self {
+            log_style_stats: false,
+            root_entity: None,
+            root_font_size: CssContext::default().root_font_size,
+            warn_size_conflicts: CssContext::default().warn_size_conflicts,
+        }
     }
 }
 
+/// The order that stylesheets are cascaded in.  When declarations are equally important & specific,
+/// the one from the later stylesheet wins.  Stylesheets are added in the order they are loaded, but
+/// can be given an explicit order with `push` (e.g. straight after `asset_server.load()`).
+///
+/// Only the ids of loaded stylesheets are kept, so the app's own handles decide how long they stay
+/// loaded (as for any other asset).  Once the last handle to a stylesheet is dropped, it is unloaded
+/// and its styles are undone.  The exception is stylesheets added with `apply_css()`, which have no
+/// other owner, so are kept until they are `remove`d.
+#[derive(Debug, Clone, Default)]
+pub struct CssStylesheetOrder {
+    order: Vec<HandleId>,
+    /// Strong handles to the stylesheets added with `apply_css()`
+    owned: Vec<Handle<CssStylesheet>>,
+}
+
+impl CssStylesheetOrder {
+    /// Moves the stylesheet to the end of the order (adding it if needed), so it wins over all the
+    /// others
+    pub fn push(&mut self, handle: &Handle<CssStylesheet>) {
+        self.remove_id(handle.id);
+        self.order.push(handle.id)
+    }
+
+    /// Stops cascading the stylesheet (and unloads it, if it was added with `apply_css()`)
+    pub fn remove(&mut self, handle: &Handle<CssStylesheet>) {
+        self.remove_id(handle.id)
+    }
+
+    /// The stylesheets in cascade order, i.e. the last one wins
+    pub fn iter(&self) -> impl Iterator<Item = HandleId> + '_ {
+        self.order.iter().copied()
+    }
+
+    /// Adds a newly loaded stylesheet to the end, unless it was already given a place
+    fn add(&mut self, id: HandleId) {
+        if !self.order.contains(&id) {
+            self.order.push(id)
+        }
+    }
+
+    /// Pushes a stylesheet that the order itself keeps loaded
+    fn push_owned(&mut self, handle: Handle<CssStylesheet>) {
+        self.push(&handle);
+        self.owned.push(handle)
+    }
+
+    fn remove_id(&mut self, id: HandleId) {
+        self.order.retain(|&other| other != id);
+        self.owned.retain(|handle| handle.id != id)
+    }
+}
+
+/// Adds `apply_css()` to `Commands`
+pub trait ApplyCssExt {
+    /// Adds a stylesheet from a css string, without a file or the asset server (e.g. for generated
+    /// css, or in tests).  It is parsed & stored as a `CssStylesheet` asset when the commands are
+    /// applied, and pushed onto the end of the `CssStylesheetOrder`, so it wins over the sheets
+    /// before it.  The (weak) handle can be used to `remove` it from the order again.
+    fn apply_css(&mut self, css_string: impl Into<String>) -> Handle<CssStylesheet>;
+}
+
+impl ApplyCssExt for Commands<'_, '_> {
+    fn apply_css(&mut self, css_string: impl Into<String>) -> Handle<CssStylesheet> {
+        let handle = Handle::weak(HandleId::random::<CssStylesheet>());
+        self.add(ApplyCss { handle: handle.clone_weak(), css_string: css_string.into() });
+        handle
+    }
+}
+
+/// The command for `ApplyCssExt::apply_css()`
+struct ApplyCss {
+    handle: Handle<CssStylesheet>,
+    css_string: String,
+}
+
+impl Command for ApplyCss {
+    fn write(self, world: &mut World) {
+        // Errors are reported in the same way as for loaded stylesheets
+        let error_sink = world.get_resource::<CssErrorSink>().cloned().unwrap_or_default();
+        let stylesheet = CssStylesheet::parse_with_sink(&self.css_string, &error_sink);
+        let handle = world.resource_mut::<Assets<CssStylesheet>>().set(self.handle, stylesheet);
+        world.resource_mut::<CssStylesheetOrder>().push_owned(handle)
+    }
+}
+
+/// What one run of `apply_styles` did, for `CssSettings::log_style_stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct StyleStats {
+    rules_evaluated: usize,
+    entities_styled: usize,
+    entities_matched: usize,
+    duration: Duration,
+}
+
+impl fmt::Display for StyleStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,
+            "Applied css in {:?}: {} rules evaluated for {} entities, {} of which were matched",
+            self.duration, self.rules_evaluated, self.entities_styled, self.entities_matched
+        )
+    }
+}
+
+/// All the components that can be styled with css
+type StylesQuery<'w, 's> = Query<'w, 's, (
+    Entity,
+    Option<&'static UnstyledComponents>,
+    Option<&'static mut Style>,
+    Option<&'static mut UiColor>,
+    Option<&'static mut Text>,
+    Option<&'static mut Visibility>,
+    Option<&'static mut Transform>,
+    Option<&'static CssTransition>,
+    Option<&'static CssAnimation>,
+), With<CssTag>>;
+
+/// Changes to the hierarchy (or tags, disabling, etc.) can change which selectors match any other
+/// entity (e.g. `.parent .child`, `.a + .b` or `.row:disabled .label`), so all entities are
+/// restyled.  Only tagged entities are watched, so the rest of the app's hierarchy doesn't cause
+/// restyles.
+type HierarchyChangedQuery<'w, 's> = Query<'w, 's, (), (With<CssTag>, Or<(
+    Changed<CssTag>,
+    Changed<Parent>,
+    Changed<Children>,
+    Changed<CssDisabled>,
+    Changed<CssChecked>,
+)>)>;
+
+/// Hovering changes often, so only the entities it can affect are restyled (see
+/// `push_interaction_affected()`), rather than all of them
+type InteractionChangedQuery<'w, 's> = Query<'w, 's, Entity, (With<CssTag>, Changed<Interaction>)>;
+
+/// The inline css & parent of each tagged entity, which are read before its components are styled
+type InlineQuery<'w, 's> = Query<'w, 's, (Option<&'static InlineCss>, Option<&'static Parent>), With<CssTag>>;
+
+/// Only the entity itself needs restyling when its inline css changes
+type InlineChangedQuery<'w, 's> = Query<'w, 's, Entity, (With<CssTag>, Changed<InlineCss>)>;
+
+/// The root entities of the hierarchy, to build an `ElementTree` from.  Untagged roots are only
+/// needed for their (possibly tagged) children, so those without any are skipped.
+type RootsQuery<'w, 's> = Query<'w, 's, Entity, (Without<Parent>, Or<(With<CssTag>, With<Children>)>)>;
+
+/// Used to build an `ElementTree` from the hierarchy
+type HierarchyQuery<'w, 's> = Query<'w, 's, (
+    Option<&'static CssTag>,
+    Option<&'static Children>,
+    Option<&'static Interaction>,
+    Option<&'static CssDisabled>,
+    Option<&'static CssChecked>,
+)>;
+
 /// System to manage stylesheet application to entities
 // @todo Only update styles when the style context changes
-// @todo Make the order of allied sheets deterministic (need to decided on cascading rules)
 // @todo Add support for Component matching/selectors
 fn apply_styles(
+    mut commands: Commands,
+    settings: Res<CssSettings>,
+    media: Res<CssMediaState>,
+    (fonts, font_assets, mut font_events): (Res<CssFonts>, Res<Assets<Font>>, EventReader<AssetEvent<Font>>),
     mut stylesheet_events: EventReader<AssetEvent<CssStylesheet>>,
     assets: Res<Assets<CssStylesheet>>,
-    mut styles_query: Query<(&CssTag, Option<&mut Style>, Option<&mut UiColor>)>,
+    stylesheet_order: Res<CssStylesheetOrder>,
+    hierarchy_changed_query: HierarchyChangedQuery,
+    removed_disabled: RemovedComponents<CssDisabled>,
+    removed_checked: RemovedComponents<CssChecked>,
+    (inline_changed_query, interaction_changed_query): (InlineChangedQuery, InteractionChangedQuery),
+    roots_query: RootsQuery,
+    hierarchy_query: HierarchyQuery,
+    inline_query: InlineQuery,
+    mut styles_query: StylesQuery,
+    sink: Res<CssErrorSink>,
 ) {
-    for event in stylesheet_events.iter() {
-        match event {
-            AssetEvent::Created { handle } | AssetEvent::Modified { handle } =>
-                apply_stylesheet(assets.get(handle).unwrap(), &mut styles_query),
-            _ => ()
+    // Any event restyles everything, inc. `Removed`, so the rules of an unloaded stylesheet are undone.
+    // `last()` reads every event, so none are left to restyle again next frame.
+    let stylesheets_changed = stylesheet_events.iter().last().is_some();
+    // A removed marker component isn't a change that a query can find
+    let restyle_all = stylesheets_changed
+        || stylesheet_order.is_changed()
+        || settings.is_changed()
+        || media.is_changed()
+        || fonts.is_changed()
+        // A loaded font may change the `ex` & `ch` lengths of any entity that uses it
+        || font_events.iter().last().is_some()
+        || !hierarchy_changed_query.is_empty()
+        || removed_disabled.iter().next().is_some()
+        || removed_checked.iter().next().is_some()
+        // The root font size may have changed, which every `rem` length is relative to
+        || settings.root_entity.map_or(false, |root|
+            inline_changed_query.contains(root) || interaction_changed_query.contains(root)
+        );
+    if !restyle_all && inline_changed_query.is_empty() && interaction_changed_query.is_empty() {
+        return
+    }
+    // Only timed when logged, so it costs nothing otherwise
+    let start = settings.log_style_stats.then(Instant::now);
+    let mut stats = StyleStats::default();
+    let stylesheets: Vec<&CssStylesheet> = stylesheet_order.iter().filter_map(|id| assets.get(id)).collect();
+    let rule_count: usize = stylesheets.iter()
+        .flat_map(|stylesheet| stylesheet.rules.iter())
+        .map(|rule| rule.style_rules().len())
+        .sum();
+    let untagged = CssTag::default();
+    let (tree, indices) = build_element_tree(&roots_query, &hierarchy_query, &untagged);
+    let mut entities: Vec<Entity> = if restyle_all {
+        styles_query.iter().map(|(entity, ..)| entity).collect()
+    } else {
+        let mut entities: HashSet<Entity> = inline_changed_query.iter().collect();
+        for entity in interaction_changed_query.iter() {
+            push_interaction_affected(entity, &inline_query, &hierarchy_query, &mut entities)
+        }
+        entities.into_iter().collect()
+    };
+    // Parents are styled before their children, so `all: inherit` copies the parent's new values
+    entities.sort_by_key(|entity| indices.get(entity).copied());
+    let root_font_size =
+        root_font_size(&settings, &media, &stylesheets, &tree, &indices, &inline_query, &styles_query, &sink);
+    for entity in entities {
+        let index = match indices.get(&entity) {
+            Some(&index) => index,
+            None => continue,
+        };
+        let (inline, parent) = match inline_query.get(entity) {
+            Ok(item) => item,
+            Err(_) => continue,
+        };
+        let declarations = custom::resolve_variables(cascade(&stylesheets, &media, &tree.element(index), inline), &sink);
+        let declarations: Vec<&BevyPropertyDeclaration> =
+            declarations.iter().map(|declaration| declaration.as_ref()).collect();
+        // Only copied when needed.  The root entity has no parent, so inherits the initial values.
+        let parent = parent
+            .filter(|_| declarations.iter().any(|property| property.inherits_from_parent()))
+            .and_then(|parent| styles_query.get(parent.get()).ok())
+            .map(|(_, _, style, color, text, visibility, transform, _, _)| UnstyledComponents::capture(
+                style, color, text, visibility, transform,
+            ));
+        let (_, unstyled, mut style, mut color, mut text, mut visibility, mut transform, _, _) =
+            match styles_query.get_mut(entity) {
+                Ok(item) => item,
+                Err(_) => continue,
+            };
+        // What is shown now (which may be part way through a transition), to transition from
+        let shown = AnimatedValues::capture(style.as_deref(), color.as_deref());
+        let was_styled = unstyled.is_some();
+        // Declarations that no longer apply (e.g. after `:hover`) must not be left behind
+        match unstyled {
+            Some(unstyled) => unstyled.restore(
+                style.as_deref_mut(), color.as_deref_mut(), text.as_deref_mut(),
+                visibility.as_deref_mut(), transform.as_deref_mut(),
+            ),
+            None => {
+                commands.entity(entity).insert(UnstyledComponents::capture(
+                    style.as_deref(), color.as_deref(), text.as_deref(), visibility.as_deref(), transform.as_deref(),
+                ));
+            },
+        }
+        stats.rules_evaluated += rule_count;
+        stats.entities_styled += 1;
+        if !declarations.is_empty() {
+            stats.entities_matched += 1
+        }
+        let context = entity_context(&settings, root_font_size, &media, text.as_deref());
+        // Measured from the font the text will have once styled, once it has loaded
+        let font = cascaded_font(&declarations, &fonts)
+            .or_else(|| text.as_deref().and_then(|text| text.sections.first()).map(|section| &section.style.font));
+        let context = match font.and_then(|font| font_assets.get(font)) {
+            Some(font) => context.with_font_metrics(font),
+            None => context,
+        };
+        let transitions = cascaded_transitions(&declarations);
+        let animation_spec = cascaded_animation(&declarations, &stylesheets);
+        apply_declarations(&declarations, context, &fonts, parent.as_ref(), style, color, text, visibility, transform);
+        // Styles are never transitioned to when an entity is first styled, as in a browser
+        if was_styled {
+            if let Ok((_, _, style, color, _, _, _, current, _)) = styles_query.get_mut(entity) {
+                animation::start_transition(&mut commands, entity, transitions, shown, style, color, current)
+            }
+        }
+        if let Ok((_, _, style, color, _, _, _, _, current)) = styles_query.get_mut(entity) {
+            animation::start_animation(&mut commands, entity, animation_spec, &context, &sink, style, color, current)
+        }
+    }
+    if let Some(start) = start {
+        stats.duration = start.elapsed();
+        info!("{}", stats)
+    }
+}
+
+/// Adds the entities whose selectors may match differently once the `Interaction` of `entity` has
+/// changed, i.e. the entity & its descendants (e.g. `.row:hover .label`), and its following siblings
+/// & theirs (e.g. `.a:hover + .b` or `.a:hover ~ .b .c`)
+fn push_interaction_affected(
+    entity: Entity,
+    inline_query: &InlineQuery,
+    hierarchy_query: &HierarchyQuery,
+    affected: &mut HashSet<Entity>,
+) {
+    let siblings = inline_query.get(entity).ok()
+        .and_then(|(_, parent)| parent)
+        .and_then(|parent| hierarchy_query.get(parent.get()).ok())
+        .and_then(|(_, children, ..)| children);
+    match siblings {
+        // The entity itself is the first of its following siblings
+        Some(siblings) => siblings.iter()
+            .skip_while(|&&sibling| sibling != entity)
+            .for_each(|&sibling| push_subtree(sibling, hierarchy_query, affected)),
+        None => push_subtree(entity, hierarchy_query, affected),
+    }
+}
+
+/// Adds the entity & all of its descendants
+fn push_subtree(entity: Entity, hierarchy_query: &HierarchyQuery, affected: &mut HashSet<Entity>) {
+    affected.insert(entity);
+    if let Ok((_, Some(children), ..)) = hierarchy_query.get(entity) {
+        for &child in children.iter() {
+            push_subtree(child, hierarchy_query, affected)
         }
     }
 }
 
-fn apply_stylesheet(
-    stylesheet: &CssStylesheet,
-    styles_query: &mut Query<(&CssTag, Option<&mut Style>, Option<&mut UiColor>)>,
+/// An `ElementTree` of every entity in the hierarchy, in the order of their `Children`.  Entities
+/// without a `CssTag` are given the `untagged` (i.e. empty) one.  Also returns the tree index of
+/// each entity.
+fn build_element_tree<'a>(
+    roots_query: &RootsQuery,
+    hierarchy_query: &'a HierarchyQuery,
+    untagged: &'a CssTag,
+) -> (ElementTree<'a>, HashMap<Entity, usize>) {
+    let mut tree = ElementTree::default();
+    let mut indices = HashMap::default();
+    for root in roots_query.iter() {
+        add_to_element_tree(root, None, hierarchy_query, untagged, &mut tree, &mut indices)
+    }
+    (tree, indices)
+}
+
+fn add_to_element_tree<'a>(
+    entity: Entity,
+    parent: Option<usize>,
+    hierarchy_query: &'a HierarchyQuery,
+    untagged: &'a CssTag,
+    tree: &mut ElementTree<'a>,
+    indices: &mut HashMap<Entity, usize>,
 ) {
-    for rule in stylesheet.rules.iter() {
+    if let Ok((tag, children, interaction, disabled, checked)) = hierarchy_query.get(entity) {
+        let index = tree.push(tag.unwrap_or(untagged), parent);
+        if let Some(&interaction) = interaction {
+            tree.set_interaction(index, interaction)
+        }
+        tree.set_disabled(index, disabled.is_some());
+        tree.set_checked(index, checked.is_some());
+        indices.insert(entity, index);
+        if let Some(children) = children {
+            for &child in children.iter() {
+                add_to_element_tree(child, Some(index), hierarchy_query, untagged, tree, indices)
+            }
+        }
+    }
+}
+
+/// The values of an entity's styled components from before any css was applied to it.  They are
+/// restored before the css is applied again, so declarations that no longer apply (e.g. `:hover`
+/// ones, once the pointer has left) are undone.  Only what css can change is kept, e.g. not the
+/// value of a `Text`, nor the `translation` of a `Transform` (which Bevy's UI layout sets).
+///
+/// The (styled) values of a parent entity are also captured like this, for its children to inherit.
+#[derive(Component, Debug, Clone)]
+struct UnstyledComponents {
+    style: Option<Style>,
+    color: Option<UiColor>,
+    /// The `font`, `font_size` & `color` of each text section
+    text: Option<Vec<(Handle<Font>, f32, Color)>>,
+    visibility: Option<Visibility>,
+    /// The `rotation` & `scale`
+    transform: Option<(Quat, Vec3)>,
+}
+
+impl UnstyledComponents {
+    fn capture(
+        style: Option<&Style>,
+        color: Option<&UiColor>,
+        text: Option<&Text>,
+        visibility: Option<&Visibility>,
+        transform: Option<&Transform>,
+    ) -> Self {
+        Self {
+            style: style.cloned(),
+            color: color.copied(),
+            text: text.map(|text| text.sections.iter()
+                .map(|section| (section.style.font.clone(), section.style.font_size, section.style.color))
+                .collect()
+            ),
+            visibility: visibility.cloned(),
+            transform: transform.map(|transform| (transform.rotation, transform.scale)),
+        }
+    }
+
+    fn restore(
+        &self,
+        style: Option<&mut Style>,
+        color: Option<&mut UiColor>,
+        text: Option<&mut Text>,
+        visibility: Option<&mut Visibility>,
+        transform: Option<&mut Transform>,
+    ) {
+        if let (Some(style), Some(unstyled)) = (style, &self.style) {
+            *style = unstyled.clone()
+        }
+        if let (Some(color), Some(unstyled)) = (color, &self.color) {
+            *color = *unstyled
+        }
+        if let (Some(text), Some(unstyled)) = (text, &self.text) {
+            for (section, (font, font_size, color)) in text.sections.iter_mut().zip(unstyled) {
+                section.style.font = font.clone();
+                section.style.font_size = *font_size;
+                section.style.color = *color;
+            }
+        }
+        if let (Some(visibility), Some(unstyled)) = (visibility, &self.visibility) {
+            *visibility = unstyled.clone()
+        }
+        if let (Some(transform), Some(&(rotation, scale))) = (transform, &self.transform) {
+            transform.rotation = rotation;
+            transform.scale = scale;
+        }
+    }
+
+    /// Copies a parent's values to its child, after `all` has reset the child's.  `inherit` copies
+    /// every value, while `unset` only copies those of the inherited properties, i.e. the text
+    /// `color`, `font-size` & font, and the `visibility`.
+    // Each styled component is passed separately, as any of them may be missing
+    #[allow(clippy::too_many_arguments)]
+    fn inherit(
+        &self,
+        keyword: CssWideKeyword,
+        context: &mut CssContext,
+        style: Option<&mut Style>,
+        color: Option<&mut UiColor>,
+        text: Option<&mut Text>,
+        visibility: Option<&mut Visibility>,
+        transform: Option<&mut Transform>,
+    ) {
+        match keyword {
+            CssWideKeyword::Initial => return,
+            CssWideKeyword::Inherit => self.restore(style, color, None, None, transform),
+            CssWideKeyword::Unset => (),
+        }
+        // A parent's text sections are all styled the same, so the first is inherited by every section
+        if let Some((font, font_size, color)) = self.text.as_ref().and_then(|sections| sections.first()) {
+            context.font_size = *font_size;
+            if let Some(text) = text {
+                for section in text.sections.iter_mut() {
+                    section.style.font = font.clone();
+                    section.style.font_size = *font_size;
+                    section.style.color = *color;
+                }
+            }
+        }
+        self.restore(None, None, None, visibility, None)
+    }
+}
+
+/// The levels of the cascade, from the lowest to the highest priority
+/// See also: https://drafts.csswg.org/css-cascade/#cascade-origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CascadeLevel {
+    SheetNormal,
+    InlineNormal,
+    SheetImportant,
+    InlineImportant,
+}
+
+/// All the declarations that apply to an entity, in the order they should be applied (i.e. the
+/// winning declarations last).  They are sorted by their `CascadeLevel`, then by the specificity of
+/// their selectors, then by the order they were declared in.
+/// See also: https://drafts.csswg.org/css-cascade/#cascade-sort
+fn cascade<'a>(
+    stylesheets: &[&'a CssStylesheet],
+    media: &CssMediaState,
+    element: &BevyElement,
+    inline: Option<&'a InlineCss>,
+) -> Vec<&'a BevyPropertyDeclaration> {
+    let mut blocks = Vec::new();
+    for &stylesheet in stylesheets {
+        push_matching_blocks(&stylesheet.rules, media, element, &mut blocks)
+    }
+    if let Some(InlineCss(inline)) = inline {
+        blocks.push((CascadeLevel::InlineNormal, 0, inline.declarations.as_slice()));
+        blocks.push((CascadeLevel::InlineImportant, 0, inline.important_declarations.as_slice()));
+    }
+    // The sort is stable, so blocks with the same level & specificity stay in source order
+    blocks.sort_by_key(|&(level, specificity, _)| (level, specificity));
+    blocks.into_iter().flat_map(|(_, _, declarations)| declarations.iter()).collect()
+}
+
+/// The declarations of a rule, with their `CascadeLevel` & the specificity of its selectors
+type DeclarationBlock<'a> = (CascadeLevel, u32, &'a [BevyPropertyDeclaration]);
+
+/// Adds the declaration blocks of the style `rules` that match the `element`, including those
+/// inside `@media` rules whose queries match the `media` state
+fn push_matching_blocks<'a>(
+    rules: &'a [BevyCssRule],
+    media: &CssMediaState,
+    element: &BevyElement,
+    blocks: &mut Vec<DeclarationBlock<'a>>,
+) {
+    for rule in rules {
         match rule {
-            BevyCssRule::Style(style_rule) => apply_style_rule(style_rule, styles_query)
+            BevyCssRule::Style(style_rule) => {
+                if let Some(specificity) = style_rule.selectors.matching_specificity(element) {
+                    blocks.push((CascadeLevel::SheetNormal, specificity, style_rule.declarations.as_slice()));
+                    blocks.push((CascadeLevel::SheetImportant, specificity, style_rule.important_declarations.as_slice()));
+                }
+            },
+            BevyCssRule::Media(media_rule) => {
+                if media_rule.queries.matches(media) {
+                    push_matching_blocks(&media_rule.rules, media, element, blocks)
+                }
+            },
+            // Only used by animations
+            BevyCssRule::Keyframes(_) => {},
+        }
+    }
+}
+
+/// Keeps the `CssMediaState` viewport size up to date with the primary window (e.g. as it is
+/// resized), for `@media` rules like `(min-width: 600px)` and the viewport lengths (e.g. `100vh`) of
+/// every entity's `CssContext`
+fn update_media_viewport(windows: Res<Windows>, mut media: ResMut<CssMediaState>) {
+    if let Some(window) = windows.get_primary() {
+        let viewport_size = Vec2::new(window.width(), window.height());
+        // Only set when it has changed, as any change restyles everything
+        if media.viewport_size != viewport_size {
+            media.viewport_size = viewport_size
+        }
+    }
+}
+
+/// Keeps the `CssStylesheetOrder` up to date as stylesheets are loaded & removed
+fn update_stylesheet_order(
+    mut stylesheet_events: EventReader<AssetEvent<CssStylesheet>>,
+    mut stylesheet_order: ResMut<CssStylesheetOrder>,
+) {
+    for event in stylesheet_events.iter() {
+        match event {
+            AssetEvent::Created { handle } => stylesheet_order.add(handle.id),
+            AssetEvent::Removed { handle } => stylesheet_order.remove_id(handle.id),
+            AssetEvent::Modified { .. } => {},
+        }
+    }
+}
+
+/// Whether the `visibility` that won the cascade is `collapse`, so the node should also be removed
+/// from the layout.  Only the last declaration counts, as a later `visibility: visible` un-collapses.
+fn is_collapsed(declarations: &[&BevyPropertyDeclaration]) -> bool {
+    declarations.iter()
+        .rev()
+        .find(|property| property.affects_visibility())
+        .map_or(false, |property| property.is_collapse())
+}
+
+/// The registered font for the `font-family` & `font-weight` that won the cascade, if any.  Without
+/// a `font-family` (or a registered font for it), the `Text` keeps its own fonts.
+fn cascaded_font<'a>(declarations: &[&BevyPropertyDeclaration], fonts: &'a CssFonts) -> Option<&'a Handle<Font>> {
+    let mut family = None;
+    let mut weight = FontWeight::default();
+    for property in declarations.iter() {
+        match property {
+            BevyPropertyDeclaration::FontFamily(value) => family = Some(value),
+            BevyPropertyDeclaration::FontWeight(value) => weight = *value,
+            BevyPropertyDeclaration::All(_) if property.affects_font() => {
+                family = None;
+                weight = FontWeight::default();
+            },
+            _ => (),
+        }
+    }
+    family.and_then(|family| fonts.find(family, weight))
+}
+
+/// The `transition` that won the cascade, if any
+fn cascaded_transitions(declarations: &[&BevyPropertyDeclaration]) -> Option<TransitionList> {
+    for property in declarations.iter().rev() {
+        match property {
+            BevyPropertyDeclaration::Transition(transitions) => return Some(transitions.clone()),
+            BevyPropertyDeclaration::All(CssWideKeyword::Initial | CssWideKeyword::Unset) => return None,
+            _ => (),
         }
     }
+    None
 }
 
-fn apply_style_rule(
-    style_rule: &BevyStyleRule,
-    query: &mut Query<(&CssTag, Option<&mut Style>, Option<&mut UiColor>)>
+/// The `animation` that won the cascade, if any, with the `@keyframes` it runs (from the last
+/// stylesheet that has them).  Without any keyframes of that name, nothing is animated.
+fn cascaded_animation<'a>(
+    declarations: &[&BevyPropertyDeclaration],
+    stylesheets: &[&'a CssStylesheet],
+) -> Option<(AnimationSpec, &'a BevyKeyframesRule)> {
+    for property in declarations.iter().rev() {
+        match property {
+            BevyPropertyDeclaration::Animation(spec) => {
+                let spec = spec.as_ref()?;
+                let keyframes = stylesheets.iter().rev().find_map(|stylesheet| stylesheet.keyframes(&spec.name))?;
+                return Some((spec.clone(), keyframes))
+            },
+            BevyPropertyDeclaration::All(CssWideKeyword::Initial | CssWideKeyword::Unset) => return None,
+            _ => (),
+        }
+    }
+    None
+}
+
+/// The font size that `rem` lengths are relative to, i.e. that of the `CssSettings::root_entity`
+/// once its css is applied, or else the `root_font_size` setting
+#[allow(clippy::too_many_arguments)]
+fn root_font_size(
+    settings: &CssSettings,
+    media: &CssMediaState,
+    stylesheets: &[&CssStylesheet],
+    tree: &ElementTree,
+    indices: &HashMap<Entity, usize>,
+    inline_query: &InlineQuery,
+    styles_query: &StylesQuery,
+    sink: &CssErrorSink,
+) -> f32 {
+    let root_font_size = || {
+        let root = settings.root_entity?;
+        let &index = indices.get(&root)?;
+        let (inline, _) = inline_query.get(root).ok()?;
+        let (_, unstyled, _, _, text, ..) = styles_query.get(root).ok()?;
+        // The font size from before any css was applied, which its `em` font sizes are relative to
+        let font_size = match unstyled.and_then(|unstyled| unstyled.text.as_ref()) {
+            Some(sections) => sections.first()?.1,
+            None => text?.sections.first()?.style.font_size,
+        };
+        let mut context = CssContext { font_size, ..entity_context(settings, settings.root_font_size, media, None) };
+        for declaration in custom::resolve_variables(cascade(stylesheets, media, &tree.element(index), inline), sink) {
+            declaration.modify_context(&mut context)
+        }
+        Some(context.font_size)
+    };
+    root_font_size().unwrap_or(settings.root_font_size)
+}
+
+/// The `CssContext` to apply an entity's declarations in, i.e. with the real viewport size (for `vw`
+/// etc.), the entity's own (unstyled) font size (for `em` etc.), and the `root_font_size` (for
+/// `rem`).  Percentages are passed on to Bevy's layout as `Val::Percent`, so they don't need the
+/// size of the entity's `Node`.
+fn entity_context(
+    settings: &CssSettings,
+    root_font_size: f32,
+    media: &CssMediaState,
+    text: Option<&Text>,
+) -> CssContext {
+    let mut context = CssContext {
+        viewport_size: media.viewport_size,
+        root_font_size,
+        warn_size_conflicts: settings.warn_size_conflicts,
+        ..Default::default()
+    };
+    if let Some(section) = text.and_then(|text| text.sections.first()) {
+        context.font_size = section.style.font_size
+    }
+    context
+}
+
+// Each styled component is passed separately, as any of them may be missing
+#[allow(clippy::too_many_arguments)]
+fn apply_declarations(
+    declarations: &[&BevyPropertyDeclaration],
+    mut context: CssContext,
+    fonts: &CssFonts,
+    parent: Option<&UnstyledComponents>,
+    mut style_opt: Option<Mut<Style>>,
+    mut color_opt: Option<Mut<UiColor>>,
+    mut text_opt: Option<Mut<Text>>,
+    mut visibility_opt: Option<Mut<Visibility>>,
+    mut transform_opt: Option<Mut<Transform>>,
 ) {
-    for (tag, mut style_opt, mut color_opt) in query.iter_mut() {
-        let CssTag { id, classes } = tag;
-        // @fixme Create a proper context, not a default
+    // Components are only borrowed mutably when a declaration will change them, so Bevy's change
+    // detection isn't triggered for nothing
+    for property in declarations.iter() {
+        if property.affects_text() {
+            if let Some(mut text) = text_opt.as_mut() { property.modify_text(&context, &mut text) }
+        }
+        property.modify_context(&mut context);
+        if property.affects_style() {
+            if let Some(mut style) = style_opt.as_mut() { property.modify_style(&context, &mut style) }
+        }
+        if property.affects_color() {
+            if let Some(mut color) = color_opt.as_mut() { property.modify_color(&mut color) }
+        }
+        if property.affects_visibility() {
+            if let Some(mut visibility) = visibility_opt.as_mut() { property.modify_visibility(&mut visibility) }
+        }
+        if property.affects_transform() {
+            if let Some(mut transform) = transform_opt.as_mut() { property.modify_transform(&context, &mut transform) }
+        }
+        if let (BevyPropertyDeclaration::All(keyword), Some(parent)) = (property, parent) {
+            parent.inherit(
+                *keyword, &mut context, style_opt.as_deref_mut(), color_opt.as_deref_mut(),
+                text_opt.as_deref_mut(), visibility_opt.as_deref_mut(), transform_opt.as_deref_mut(),
+            )
+        }
+    }
+    if let (Some(text), Some(font)) = (text_opt.as_mut(), cascaded_font(declarations, fonts)) {
+        for section in text.sections.iter_mut() {
+            section.style.font = font.clone()
+        }
+    }
+    if let Some(mut style) = style_opt.as_mut() {
+        if is_collapsed(declarations) {
+            style.display = Display::None
+        }
+        if declarations.iter().any(|property| property.affects_style()) {
+            size_conflicts::clamp_and_warn(&mut style, context.warn_size_conflicts)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::media_queries::ColorScheme;
+    use super::*;
+
+    /// The `width` of a `#id.item` entity (with the `ancestors`) styled by the `sheet` & `inline` css
+    fn cascaded_width_with_ancestors(sheet: &str, inline: &str, ancestors: &[&CssTag]) -> Val {
+        let stylesheet = CssStylesheet::from(sheet);
+        let inline = InlineCss::from(inline);
+        let tag = CssTag::from("#id.item");
         let context = CssContext::default();
-        if style_rule.selectors.matches(&id, &classes) {
-            for property in style_rule.declarations.iter() {
-                if let Some(mut style) = style_opt.as_mut() { property.modify_style(&context, &mut style) }
-                if let Some(mut color) = color_opt.as_mut() { property.modify_color(&mut color) }
+        let mut style = Style::default();
+        let (tree, index) = ElementTree::with_ancestors(&tag, ancestors);
+        for property in cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), Some(&inline)) {
+            property.modify_style(&context, &mut style)
+        }
+        style.size.width
+    }
+
+    fn cascaded_width(sheet: &str, inline: &str) -> Val {
+        cascaded_width_with_ancestors(sheet, inline, &[])
+    }
+
+    #[test]
+    fn test_normal_inline_beats_normal_sheet() {
+        assert_eq!(cascaded_width(".item { width: 10px; }", "width: 20px;"), Val::Px(20.0));
+    }
+
+    #[test]
+    fn test_important_sheet_beats_normal_inline() {
+        assert_eq!(cascaded_width(".item { width: 10px !important; }", "width: 20px;"), Val::Px(10.0));
+    }
+
+    #[test]
+    fn test_important_inline_beats_normal_sheet() {
+        assert_eq!(cascaded_width(".item { width: 10px; }", "width: 20px !important;"), Val::Px(20.0));
+    }
+
+    #[test]
+    fn test_important_inline_beats_important_sheet() {
+        assert_eq!(
+            cascaded_width(".item { width: 10px !important; }", "width: 20px !important;"),
+            Val::Px(20.0)
+        );
+    }
+
+    #[test]
+    fn test_specificity_and_source_order() {
+        // The more specific selector wins, wherever it is
+        assert_eq!(cascaded_width("#id { width: 10px; } .item { width: 20px; }", ""), Val::Px(10.0));
+        // Otherwise the last declaration wins
+        assert_eq!(cascaded_width(".item { width: 10px; } .item { width: 20px; }", ""), Val::Px(20.0));
+        // Importance beats specificity
+        assert_eq!(
+            cascaded_width("#id { width: 10px; } .item { width: 20px !important; }", ""),
+            Val::Px(20.0)
+        );
+    }
+
+    /// The `UiColor` of a `#id.cls` entity styled by the `sheet`
+    fn cascaded_color(sheet: &str) -> Color {
+        let stylesheet = CssStylesheet::from(sheet);
+        let tag = CssTag::from("#id.cls");
+        let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+        let mut ui_color = UiColor::default();
+        for property in cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None) {
+            property.modify_color(&mut ui_color)
+        }
+        ui_color.0
+    }
+
+    #[test]
+    fn test_specificity_beats_source_order_for_color() {
+        assert_eq!(cascaded_color("#id { color: red; } .cls { color: blue; }"), Color::RED);
+        assert_eq!(cascaded_color(".cls { color: blue; } #id { color: red; }"), Color::RED);
+    }
+
+    #[test]
+    fn test_importance_beats_specificity_for_color() {
+        assert_eq!(cascaded_color(".cls { color: blue !important; } #id { color: red; }"), Color::BLUE);
+        assert_eq!(cascaded_color("#id { color: red; } .cls { color: blue !important; }"), Color::BLUE);
+        // Between important declarations, specificity still decides
+        assert_eq!(
+            cascaded_color("#id { color: red !important; } .cls { color: blue !important; }"),
+            Color::RED
+        );
+    }
+
+    #[test]
+    fn test_stylesheet_order() {
+        let first = Handle::<CssStylesheet>::weak(HandleId::random::<CssStylesheet>());
+        let second = Handle::<CssStylesheet>::weak(HandleId::random::<CssStylesheet>());
+        let mut sheets = HashMap::default();
+        sheets.insert(first.id, CssStylesheet::from(".item { width: 10px; }"));
+        sheets.insert(second.id, CssStylesheet::from(".item { width: 20px; }"));
+        let tag = CssTag::from(".item");
+        let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+        let width = |order: &CssStylesheetOrder| {
+            let stylesheets: Vec<&CssStylesheet> = order.iter().filter_map(|id| sheets.get(&id)).collect();
+            let mut style = Style::default();
+            for property in cascade(&stylesheets, &CssMediaState::default(), &tree.element(index), None) {
+                property.modify_style(&CssContext::default(), &mut style)
+            }
+            style.size.width
+        };
+        let mut order = CssStylesheetOrder::default();
+        order.add(first.id);
+        order.add(second.id);
+        assert_eq!(width(&order), Val::Px(20.0));
+        // Loading again keeps the place, while pushing moves it to the end
+        order.add(first.id);
+        assert_eq!(width(&order), Val::Px(20.0));
+        order.push(&first);
+        assert_eq!(width(&order), Val::Px(10.0));
+        order.remove(&first);
+        assert_eq!(width(&order), Val::Px(20.0));
+    }
+
+    #[test]
+    fn test_cascaded_font() {
+        let (regular, bold) = (
+            Handle::<Font>::weak(HandleId::random::<Font>()),
+            Handle::<Font>::weak(HandleId::random::<Font>()),
+        );
+        let mut fonts = CssFonts::default();
+        fonts.register("Fira Sans", 400.0, regular.clone());
+        fonts.register("Fira Sans", 700.0, bold.clone());
+        let font = |sheet| {
+            let stylesheet = CssStylesheet::from(sheet);
+            let tag = CssTag::from("#id.item");
+            let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+            let declarations = cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None);
+            cascaded_font(&declarations, &fonts).cloned()
+        };
+        assert_eq!(font(".item { font-family: Fira Sans; }"), Some(regular));
+        assert_eq!(font(".item { font-family: Fira Sans; font-weight: 600; }"), Some(bold.clone()));
+        // The family & weight can come from different rules
+        assert_eq!(font(".item { font-family: Fira Sans; } #id { font-weight: bold; }"), Some(bold));
+        assert_eq!(font(".item { font-weight: bold; }"), None);
+        assert_eq!(font(".item { font-family: Fira Sans; } #id { all: initial; }"), None);
+    }
+
+    #[test]
+    fn test_cascaded_transitions() {
+        let transitions = |sheet| {
+            let stylesheet = CssStylesheet::from(sheet);
+            let tag = CssTag::from("#id.item");
+            let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+            let declarations = cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None);
+            cascaded_transitions(&declarations).map(|transitions| transitions.0.len())
+        };
+        assert_eq!(transitions(".item { transition: all 0.3s; }"), Some(1));
+        assert_eq!(transitions(".item { transition: all 0.3s; } #id { transition: width 1s, opacity 2s; }"), Some(2));
+        assert_eq!(transitions(".item { width: 10px; }"), None);
+        assert_eq!(transitions(".item { transition: all 0.3s; } #id { all: unset; }"), None);
+    }
+
+    #[test]
+    fn test_cascaded_animation() {
+        let keyframes = "@keyframes pulse { to { opacity: 0; } } @keyframes spin { to { width: 10px; } }";
+        let animation = |sheet: &str| {
+            let stylesheet = CssStylesheet::from(format!("{} {}", keyframes, sheet).as_str());
+            let tag = CssTag::from("#id.item");
+            let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+            let declarations = cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None);
+            cascaded_animation(&declarations, &[&stylesheet])
+                .map(|(spec, keyframes)| (spec.name, keyframes.name.clone()))
+        };
+        let names = |name: &str| Some((name.to_string(), name.to_string()));
+        assert_eq!(animation(".item { animation: pulse 1s; }"), names("pulse"));
+        assert_eq!(animation(".item { animation: pulse 1s; } #id { animation: spin 1s; }"), names("spin"));
+        assert_eq!(animation(".item { animation: pulse 1s; } #id { animation: none; }"), None);
+        // Without keyframes of that name, there is nothing to run
+        assert_eq!(animation(".item { animation: missing 1s; }"), None);
+    }
+
+    #[test]
+    fn test_visibility_collapse() {
+        // (`display`, `is_visible`) of an `.item`, as `apply_declarations` would set them
+        let layout = |sheet| {
+            let stylesheet = CssStylesheet::from(sheet);
+            let tag = CssTag::from(".item");
+            let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+            let declarations = cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None);
+            let (mut style, mut visibility) = (Style::default(), Visibility::default());
+            for property in declarations.iter() {
+                property.modify_style(&CssContext::default(), &mut style);
+                property.modify_visibility(&mut visibility);
+            }
+            if is_collapsed(&declarations) {
+                style.display = Display::None
             }
+            (style.display, visibility.is_visible)
+        };
+        // `hidden` keeps its space in the layout, while `collapse` doesn't
+        assert_eq!(layout(".item { visibility: hidden; }"), (Display::Flex, false));
+        assert_eq!(layout(".item { visibility: collapse; }"), (Display::None, false));
+        assert_eq!(layout(".item { visibility: collapse; } .item { visibility: visible; }"), (Display::Flex, true));
+        assert_eq!(layout(".item { visibility: collapse; } .item { all: initial; }"), (Display::Flex, true));
+    }
+
+    #[test]
+    fn test_non_matching_rules() {
+        assert_eq!(cascaded_width(".other { width: 10px !important; }", ""), Style::default().size.width);
+    }
+
+    #[test]
+    fn test_all_inherit_and_unset() {
+        let parent = UnstyledComponents::capture(
+            Some(&Style { size: Size::new(Val::Px(10.0), Val::Auto), ..Default::default() }),
+            Some(&UiColor(Color::BLUE)),
+            Some(&Text::from_section("parent", TextStyle { font_size: 30.0, color: Color::BLUE, ..Default::default() })),
+            None,
+            None,
+        );
+        let styled = |keyword| {
+            let (mut style, mut color) = (Style::default(), UiColor(Color::WHITE));
+            let mut text = Text::from_section("child", TextStyle { font_size: 10.0, color: Color::WHITE, ..Default::default() });
+            let mut context = CssContext::default();
+            let all = BevyPropertyDeclaration::All(keyword);
+            all.modify_text(&context, &mut text);
+            all.modify_context(&mut context);
+            all.modify_style(&context, &mut style);
+            all.modify_color(&mut color);
+            parent.inherit(keyword, &mut context, Some(&mut style), Some(&mut color), Some(&mut text), None, None);
+            let section = &text.sections[0].style;
+            (style.size.width, color.0, section.font_size, section.color, context.font_size)
+        };
+        assert_eq!(styled(CssWideKeyword::Inherit), (Val::Px(10.0), Color::BLUE, 30.0, Color::BLUE, 30.0));
+        // Only the text color & font size are inherited properties
+        assert_eq!(styled(CssWideKeyword::Unset), (Val::Auto, UiColor::default().0, 30.0, Color::BLUE, 30.0));
+        let TextStyle { font_size, color, .. } = TextStyle::default();
+        assert_eq!(styled(CssWideKeyword::Initial), (Val::Auto, UiColor::default().0, font_size, color, font_size));
+    }
+
+    #[test]
+    fn test_descendant_selectors() {
+        let sheet = ".item { width: 10px; } .panel .item { width: 20px; }";
+        let (panel, other) = (CssTag::from(".panel"), CssTag::from(".other"));
+        assert_eq!(cascaded_width_with_ancestors(sheet, "", &[&other, &panel]), Val::Px(20.0));
+        assert_eq!(cascaded_width_with_ancestors(sheet, "", &[&other]), Val::Px(10.0));
+        assert_eq!(cascaded_width_with_ancestors(sheet, "", &[]), Val::Px(10.0));
+    }
+
+    #[test]
+    fn test_all_important() {
+        let default_width = Style::default().size.width;
+        // Overrides normal declarations, whatever their specificity or source
+        assert_eq!(
+            cascaded_width("#id.item { width: 10px; } .item { all: unset !important; }", "width: 20px;"),
+            default_width
+        );
+        assert_eq!(
+            cascaded_width(".item { all: initial !important; width: 10px; } #id { width: 20px; }", ""),
+            default_width
+        );
+        // Without `!important`, later declarations still win
+        assert_eq!(cascaded_width(".item { all: unset; width: 10px; }", ""), Val::Px(10.0));
+        // But not more specific, or later, important declarations
+        assert_eq!(
+            cascaded_width(".item { all: unset !important; } #id { width: 10px !important; }", ""),
+            Val::Px(10.0)
+        );
+        assert_eq!(
+            cascaded_width(".item { all: unset !important; width: 10px !important; }", ""),
+            Val::Px(10.0)
+        );
+        assert_eq!(cascaded_width(".item { all: unset !important; }", "width: 20px !important;"), Val::Px(20.0));
+    }
+
+    #[test]
+    fn test_sibling_selectors() {
+        let stylesheet = CssStylesheet::from(".value { width: 10px; } .label + .value { width: 20px; }");
+        let (row, label, value) = (CssTag::from(".row"), CssTag::from(".label"), CssTag::from(".value"));
+        let mut tree = ElementTree::default();
+        let root = tree.push(&row, None);
+        let (first_value, _, second_value) =
+            (tree.push(&value, Some(root)), tree.push(&label, Some(root)), tree.push(&value, Some(root)));
+        let width = |index| {
+            let mut style = Style::default();
+            for property in cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None) {
+                property.modify_style(&CssContext::default(), &mut style)
+            }
+            style.size.width
+        };
+        assert_eq!(width(first_value), Val::Px(10.0));
+        assert_eq!(width(second_value), Val::Px(20.0));
+    }
+
+    #[test]
+    fn test_hovered_ancestor() {
+        let stylesheet = CssStylesheet::from(".item { width: 10px; } .menu:hover .item { width: 20px; }");
+        let (menu, group, item) = (CssTag::from(".menu"), CssTag::from(".group"), CssTag::from("#id.item"));
+        let mut tree = ElementTree::default();
+        let menu = tree.push(&menu, None);
+        let child = tree.push(&item, Some(menu));
+        let group = tree.push(&group, Some(menu));
+        let grandchild = tree.push(&item, Some(group));
+        let width = |tree: &ElementTree, index| {
+            let mut style = Style::default();
+            for property in cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None) {
+                property.modify_style(&CssContext::default(), &mut style)
+            }
+            style.size.width
+        };
+        assert_eq!(width(&tree, child), Val::Px(10.0));
+        assert_eq!(width(&tree, grandchild), Val::Px(10.0));
+
+        tree.set_interaction(menu, Interaction::Hovered);
+        assert_eq!(width(&tree, child), Val::Px(20.0));
+        assert_eq!(width(&tree, grandchild), Val::Px(20.0));
+        // Hovering the descendant itself isn't enough
+        tree.set_interaction(menu, Interaction::None);
+        tree.set_interaction(child, Interaction::Hovered);
+        assert_eq!(width(&tree, child), Val::Px(10.0));
+    }
+
+    #[test]
+    fn test_restore_unstyled() {
+        let stylesheet = CssStylesheet::from(".button { width: 10px; } .button:hover { width: 20px; opacity: 0.5; }");
+        let button = CssTag::from(".button");
+        let mut style = Style::default();
+        let mut color = UiColor(Color::WHITE);
+        let unstyled = UnstyledComponents::capture(Some(&style), Some(&color), None, None, None);
+        let mut restyle = |interaction| {
+            let mut tree = ElementTree::default();
+            let index = tree.push(&button, None);
+            tree.set_interaction(index, interaction);
+            unstyled.restore(Some(&mut style), Some(&mut color), None, None, None);
+            for property in cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None) {
+                property.modify_style(&CssContext::default(), &mut style);
+                property.modify_color(&mut color);
+            }
+            (style.size.width, color.0.a())
+        };
+        assert_eq!(restyle(Interaction::Hovered), (Val::Px(20.0), 0.5));
+        // Neither stuck, nor compounded
+        assert_eq!(restyle(Interaction::None), (Val::Px(10.0), 1.0));
+        assert_eq!(restyle(Interaction::Hovered), (Val::Px(20.0), 0.5));
+    }
+
+    #[test]
+    fn test_entity_context() {
+        let stylesheet = CssStylesheet::from(".item { width: 50vw; height: 2em; min-width: 2rem; }");
+        let tag = CssTag::from(".item");
+        let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+        let media = CssMediaState { viewport_size: Vec2::new(800.0, 600.0), ..Default::default() };
+        let text = Text {
+            sections: vec![TextSection { value: "".to_string(), style: TextStyle { font_size: 30.0, ..Default::default() } }],
+            ..Default::default()
+        };
+        let settings = CssSettings::default();
+        let context = entity_context(&settings, 10.0, &media, Some(&text));
+        let mut style = Style::default();
+        for property in cascade(&[&stylesheet], &media, &tree.element(index), None) {
+            property.modify_style(&context, &mut style)
         }
+        assert_eq!(style.size.width, Val::Px(400.0));
+        assert_eq!(style.size.height, Val::Px(60.0));
+        assert_eq!(style.min_size.width, Val::Px(20.0));
+        // Without any text, the default font size is used
+        assert_eq!(entity_context(&settings, 10.0, &media, None).font_size, CssContext::default().font_size);
+        assert!(context.warn_size_conflicts);
+        let settings = CssSettings { warn_size_conflicts: false, ..Default::default() };
+        assert!(!entity_context(&settings, 10.0, &media, None).warn_size_conflicts);
+    }
+
+    #[test]
+    fn test_viewport_resize() {
+        let stylesheet = CssStylesheet::from(".full { height: 100vh; }");
+        let tag = CssTag::from(".full");
+        let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+        let mut media = CssMediaState::default();
+        let height = |media: &CssMediaState| {
+            let settings = CssSettings::default();
+            let context = entity_context(&settings, settings.root_font_size, media, None);
+            let mut style = Style::default();
+            for property in cascade(&[&stylesheet], media, &tree.element(index), None) {
+                property.modify_style(&context, &mut style)
+            }
+            style.size.height
+        };
+        // Before the window is known, viewport lengths resolve to 0
+        assert_eq!(height(&media), Val::Px(0.0));
+        media.viewport_size = Vec2::new(800.0, 600.0);
+        assert_eq!(height(&media), Val::Px(600.0));
+        media.viewport_size = Vec2::new(1024.0, 768.0);
+        assert_eq!(height(&media), Val::Px(768.0));
+    }
+
+    #[test]
+    fn test_interaction_restyles_affected() {
+        let mut app = test_app();
+        let mut spawn = |tag: &str| app.world.spawn().insert_bundle((CssTag::from(tag), Style::default())).id();
+        let (before, hovered, after, child) = (spawn(".before"), spawn(".a"), spawn(".after"), spawn(".child"));
+        let row = spawn(".row");
+        app.world.entity_mut(hovered).insert(Interaction::None).push_children(&[child]);
+        app.world.entity_mut(row).push_children(&[before, hovered, after]);
+        let _handle = app.world.resource_mut::<Assets<CssStylesheet>>().add(CssStylesheet::from(
+            ".before { width: 1px; } .a:hover { width: 2px; } .a:hover + .after { width: 3px; } \
+             .a:hover .child { width: 4px; } .row { width: 5px; }"
+        ));
+        for _ in 0..3 {
+            app.update()
+        }
+        // Changed by the app, so only kept by entities that aren't restyled
+        for entity in [before, row] {
+            app.world.get_mut::<Style>(entity).unwrap().size.width = Val::Px(10.0)
+        }
+        *app.world.get_mut::<Interaction>(hovered).unwrap() = Interaction::Hovered;
+        app.update();
+        let width = |entity| app.world.get::<Style>(entity).unwrap().size.width;
+        assert_eq!(
+            [before, hovered, after, child, row].map(width),
+            [Val::Px(10.0), Val::Px(2.0), Val::Px(3.0), Val::Px(4.0), Val::Px(10.0)]
+        );
+    }
+
+    #[test]
+    fn test_removed_class_reverts() {
+        let stylesheet = CssStylesheet::from(".wide { width: 50%; background-color: red; }");
+        // Set in code, so kept when `.wide` no longer matches
+        let mut style = Style { flex_grow: 2.0, ..Default::default() };
+        let mut color = UiColor(Color::WHITE);
+        let unstyled = UnstyledComponents::capture(Some(&style), Some(&color), None, None, None);
+        let mut restyle = |tag: &str| {
+            let tag = CssTag::from(tag);
+            let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+            unstyled.restore(Some(&mut style), Some(&mut color), None, None, None);
+            for property in cascade(&[&stylesheet], &CssMediaState::default(), &tree.element(index), None) {
+                property.modify_style(&CssContext::default(), &mut style);
+                property.modify_color(&mut color);
+            }
+            (style.size.width, style.flex_grow, color.0)
+        };
+        assert_eq!(restyle(".item.wide"), (Val::Percent(50.0), 2.0, Color::RED));
+        assert_eq!(restyle(".item"), (Val::Auto, 2.0, Color::WHITE));
+    }
+
+    #[test]
+    fn test_style_stats_message() {
+        let stats = StyleStats {
+            rules_evaluated: 12,
+            entities_styled: 4,
+            entities_matched: 3,
+            duration: Duration::from_micros(250),
+        };
+        assert_eq!(
+            stats.to_string(),
+            "Applied css in 250µs: 12 rules evaluated for 4 entities, 3 of which were matched"
+        );
+    }
+
+    /// A headless app with the `CssPlugin`
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(bevy::asset::AssetPlugin)
+            .init_resource::<Windows>()
+            .add_plugin(CssPlugin);
+        app
+    }
+
+    #[test]
+    fn test_dropped_stylesheet_reverts() {
+        let mut app = test_app();
+        let entity = app.world.spawn().insert_bundle((CssTag::from("#id"), Style::default())).id();
+        let handle = app.world.resource_mut::<Assets<CssStylesheet>>()
+            .add(CssStylesheet::from("#id { width: 10px; }"));
+        let width = |app: &App| app.world.get::<Style>(entity).unwrap().size.width;
+        for _ in 0..3 {
+            app.update()
+        }
+        assert_eq!(width(&app), Val::Px(10.0));
+        // Once unused, the stylesheet is freed over the next few frames
+        drop(handle);
+        for _ in 0..5 {
+            app.update()
+        }
+        assert!(app.world.resource::<CssStylesheetOrder>().iter().next().is_none());
+        assert_eq!(width(&app), Val::Auto);
+    }
+
+    #[test]
+    fn test_root_entity_font_size() {
+        let mut app = test_app();
+        let root = app.world.spawn()
+            .insert_bundle((CssTag::from("#root"), Style::default(), Text::from_section("", TextStyle::default())))
+            .id();
+        let item = app.world.spawn().insert_bundle((CssTag::from(".item"), Style::default())).id();
+        app.world.entity_mut(root).push_children(&[item]);
+        app.world.insert_resource(CssSettings { root_entity: Some(root), ..Default::default() });
+        let _handle = app.world.resource_mut::<Assets<CssStylesheet>>()
+            .add(CssStylesheet::from("#root { font-size: 10px; } .item { width: 2rem; }"));
+        for _ in 0..3 {
+            app.update()
+        }
+        assert_eq!(app.world.get::<Style>(item).unwrap().size.width, Val::Px(20.0));
+        // Changing the root's font size rescales every `rem` length
+        app.world.entity_mut(root).insert(InlineCss::from("font-size: 20px"));
+        app.update();
+        assert_eq!(app.world.get::<Style>(item).unwrap().size.width, Val::Px(40.0));
+    }
+
+    #[test]
+    fn test_size_conflicts_setting() {
+        let mut app = test_app();
+        app.world.insert_resource(CssSettings { warn_size_conflicts: false, ..Default::default() });
+        let inline = InlineCss::from("min-width: 20px; max-width: 10px;");
+        let entity = app.world.spawn().insert_bundle((CssTag::from("#id"), Style::default(), inline)).id();
+        app.update();
+        // Without the warning, the conflict is still resolved the same way
+        let style = app.world.get::<Style>(entity).unwrap();
+        assert_eq!((style.min_size.width, style.max_size.width), (Val::Px(20.0), Val::Px(20.0)));
+    }
+
+    #[test]
+    fn test_apply_css() {
+        use bevy::ecs::system::CommandQueue;
+
+        let mut app = test_app();
+        let entity = app.world.spawn().insert_bundle((CssTag::from("#id"), UiColor(Color::WHITE))).id();
+        let mut queue = CommandQueue::default();
+        let handle = Commands::new(&mut queue, &app.world).apply_css("#id { color: red; }");
+        // Nothing is added until the commands are applied
+        assert!(app.world.resource::<Assets<CssStylesheet>>().get(&handle).is_none());
+        queue.apply(&mut app.world);
+
+        let stylesheet = app.world.resource::<Assets<CssStylesheet>>().get(&handle).unwrap();
+        assert_eq!(stylesheet.rules_matching_selector("#id").len(), 1);
+        let order: Vec<HandleId> = app.world.resource::<CssStylesheetOrder>().iter().collect();
+        assert_eq!(order, vec![handle.id]);
+        app.update();
+        assert_eq!(app.world.get::<UiColor>(entity).unwrap().0, Color::RED);
+    }
+
+    #[test]
+    fn test_media_color_scheme() {
+        let stylesheet = CssStylesheet::from(
+            ".item { width: 10px; } @media (prefers-color-scheme: dark) { .item { width: 20px; } }"
+        );
+        let tag = CssTag::from(".item");
+        let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+        let width = |media: &CssMediaState| {
+            let mut style = Style::default();
+            for property in cascade(&[&stylesheet], media, &tree.element(index), None) {
+                property.modify_style(&CssContext::default(), &mut style)
+            }
+            style.size.width
+        };
+        let mut media = CssMediaState::default();
+        assert_eq!(width(&media), Val::Px(10.0));
+        media.color_scheme = ColorScheme::Dark;
+        assert_eq!(width(&media), Val::Px(20.0));
+        media.color_scheme = ColorScheme::Light;
+        assert_eq!(width(&media), Val::Px(10.0));
     }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
+pub mod custom;
 pub mod declaration;
 mod property_defs;
+pub mod size_conflicts;
 
-pub use declaration::BevyPropertyDeclaration;
+pub use declaration::{BevyPropertyDeclaration, Importance};
 pub use property_defs::*;
\ No newline at end of file
@@ -0,0 +1,331 @@
+use std::{borrow::Cow, collections::HashMap};
+use cssparser::{CowRcStr, ParseError, ParseErrorKind, Parser, ParserInput, SourceLocation, Token};
+use crate::{
+    errors::{self, BevyCssContextualError, BevyCssParsingError, BevyCssParsingErrorKind, CssErrorSink},
+    properties::BevyPropertyDeclaration,
+};
+
+/// The values of custom properties, by their name (including the `--`)
+pub type CustomProperties = HashMap<String, String>;
+
+/// A custom property declaration, e.g. `--spacing: 1px 2px`.  Its value can be any tokens, so it is
+/// kept as the css it was declared with, to be substituted verbatim wherever `var(--spacing)` is
+/// used.
+/// See also: https://drafts.csswg.org/css-variables/#defining-variables
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CustomDeclaration {
+    /// The name, including the `--`.  Unlike other property names, it is case-sensitive.
+    pub name: String,
+    pub value: String,
+}
+
+impl CustomDeclaration {
+    pub(crate) fn parse<'i, 't>(
+        name: &CowRcStr<'i>,
+        input: &mut Parser<'i, 't>
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        Ok(Self { name: name.to_string(), value: parse_verbatim(input) })
+    }
+}
+
+/// A declaration whose value uses `var()`, so it can only be parsed once the custom properties are
+/// known (i.e. when it is applied)
+/// See also: https://drafts.csswg.org/css-variables/#using-variables
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnparsedDeclaration {
+    /// The (lowercase) property name
+    pub property: String,
+    pub css: String,
+}
+
+impl UnparsedDeclaration {
+    pub(crate) fn parse<'i, 't>(
+        property: &CowRcStr<'i>,
+        input: &mut Parser<'i, 't>
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        Ok(Self { property: property.to_ascii_lowercase(), css: parse_verbatim(input) })
+    }
+
+    /// The declaration, with each `var()` replaced by the value of its custom property (or by its
+    /// fallback, e.g. the `10px` of `var(--spacing, 10px)`).  `None` if that isn't possible, or the
+    /// result is not a valid value for the property, in which case the error is reported to the `sink`
+    /// (as is a warning if the result won't be applied as written, e.g. a clamped value).
+    pub fn substitute(
+        &self,
+        custom_properties: &CustomProperties,
+        sink: &CssErrorSink,
+    ) -> Option<BevyPropertyDeclaration> {
+        let css = match substitute_css(&self.css, custom_properties, &mut Vec::new()) {
+            Ok(css) => css,
+            Err(reason) => {
+                let reason = format!("`{}: {}` {}", self.property, self.css, reason);
+                let err = ParseError {
+                    kind: ParseErrorKind::Custom(BevyCssParsingErrorKind::InvalidVariable(reason.into())),
+                    location: SourceLocation { line: 0, column: 1 },
+                };
+                sink.report(&BevyCssContextualError::InvalidValue(&self.css, err));
+                return None
+            },
+        };
+        let mut parser_input = ParserInput::new(&css);
+        let mut input = Parser::new(&mut parser_input);
+        let property = CowRcStr::from(self.property.as_str());
+        let (result, notices) = errors::collect_notices(||
+            input.parse_entirely(|input| BevyPropertyDeclaration::parse_input(property, input))
+        );
+        for notice in notices {
+            let err = ParseError {
+                kind: ParseErrorKind::Custom(BevyCssParsingErrorKind::Notice(notice.into())),
+                location: SourceLocation { line: 0, column: 1 },
+            };
+            sink.report(&BevyCssContextualError::Warning(&css, err));
+        }
+        match result {
+            Ok(declaration) => Some(declaration),
+            Err(err) => {
+                sink.report(&BevyCssContextualError::InvalidValue(&css, err));
+                None
+            },
+        }
+    }
+}
+
+/// The declarations (in the order they are applied), with the `var()`s substituted from the custom
+/// properties among them.  As with any other property, the last declaration of each custom property
+/// is the one used, wherever it is.  Declarations that can't be substituted are left out, and
+/// reported to the `sink`.
+// @todo Custom properties should also be inherited from the parent entity
+pub fn resolve_variables<'a>(
+    declarations: impl IntoIterator<Item = &'a BevyPropertyDeclaration>,
+    sink: &CssErrorSink,
+) -> Vec<Cow<'a, BevyPropertyDeclaration>> {
+    let declarations: Vec<_> = declarations.into_iter().collect();
+    let custom_properties: CustomProperties = declarations.iter()
+        .filter_map(|declaration| match declaration {
+            BevyPropertyDeclaration::Custom(custom) => Some((custom.name.clone(), custom.value.clone())),
+            _ => None,
+        })
+        .collect();
+    declarations.into_iter()
+        .filter_map(|declaration| match declaration {
+            BevyPropertyDeclaration::WithVariables(unparsed) =>
+                unparsed.substitute(&custom_properties, sink).map(Cow::Owned),
+            _ => Some(Cow::Borrowed(declaration)),
+        })
+        .collect()
+}
+
+/// Whether the value uses `var()` anywhere (including inside other functions).  The input is left
+/// where it was.
+pub(crate) fn contains_var(input: &mut Parser) -> bool {
+    let state = input.state();
+    let contains = has_var_function(input);
+    input.reset(&state);
+    contains
+}
+
+fn has_var_function(input: &mut Parser) -> bool {
+    loop {
+        let is_block = match input.next() {
+            Ok(token) => match *token {
+                Token::Function(ref name) if name.eq_ignore_ascii_case("var") => return true,
+                Token::Function(_) | Token::ParenthesisBlock | Token::SquareBracketBlock | Token::CurlyBracketBlock =>
+                    true,
+                _ => false,
+            },
+            Err(_) => return false,
+        };
+        if is_block && in_nested_block(input, has_var_function).unwrap_or(false) {
+            return true
+        }
+    }
+}
+
+/// The rest of the input, exactly as it was written (other than surrounding whitespace)
+fn parse_verbatim(input: &mut Parser) -> String {
+    let start = input.position();
+    while input.next_including_whitespace_and_comments().is_ok() {}
+    input.slice_from(start).trim().to_string()
+}
+
+/// Runs `parse` on the nested block that was just started, always consuming the whole block
+fn in_nested_block<'i, 't, T>(
+    input: &mut Parser<'i, 't>,
+    parse: impl for<'tt> FnOnce(&mut Parser<'i, 'tt>) -> T,
+) -> Option<T> {
+    input.parse_nested_block(|input| {
+        let result = parse(input);
+        while input.next().is_ok() {}
+        Ok::<_, ParseError<()>>(result)
+    }).ok()
+}
+
+/// The `css` with every `var()` substituted, or why it couldn't be.  The `stack` holds the custom
+/// properties being substituted, to catch any that refer back to themselves.
+fn substitute_css(css: &str, custom_properties: &CustomProperties, stack: &mut Vec<String>) -> Result<String, String> {
+    let mut parser_input = ParserInput::new(css);
+    let mut input = Parser::new(&mut parser_input);
+    let mut substituted = String::new();
+    substitute_tokens(&mut input, custom_properties, stack, &mut substituted)?;
+    Ok(substituted)
+}
+
+fn substitute_tokens(
+    input: &mut Parser,
+    custom_properties: &CustomProperties,
+    stack: &mut Vec<String>,
+    css: &mut String,
+) -> Result<(), String> {
+    loop {
+        let start = input.position();
+        let token = match input.next_including_whitespace_and_comments() {
+            Ok(token) => token.clone(),
+            Err(_) => return Ok(()),
+        };
+        let closing = match token {
+            Token::Function(ref name) if name.eq_ignore_ascii_case("var") => {
+                in_nested_block(input, |input| substitute_var(input, custom_properties, stack, css))
+                    .unwrap_or(Ok(()))?;
+                continue
+            },
+            Token::Function(_) | Token::ParenthesisBlock => ")",
+            Token::SquareBracketBlock => "]",
+            Token::CurlyBracketBlock => "}",
+            _ => {
+                css.push_str(input.slice_from(start));
+                continue
+            },
+        };
+        // Any `var()`s inside other functions (e.g. `min()`) are substituted too
+        css.push_str(input.slice_from(start));
+        in_nested_block(input, |input| substitute_tokens(input, custom_properties, stack, css))
+            .unwrap_or(Ok(()))?;
+        css.push_str(closing);
+    }
+}
+
+/// Substitutes the arguments of a `var()`, i.e. a custom property name & an optional fallback
+fn substitute_var(
+    input: &mut Parser,
+    custom_properties: &CustomProperties,
+    stack: &mut Vec<String>,
+    css: &mut String,
+) -> Result<(), String> {
+    let name = match input.expect_ident() {
+        Ok(name) if name.starts_with("--") => name.to_string(),
+        _ => return Err("as `var()` needs the name of a custom property (e.g. `var(--name)`)".to_string()),
+    };
+    if stack.contains(&name) {
+        return Err(format!("as `{}` refers back to itself", name))
+    }
+    match custom_properties.get(&name) {
+        Some(value) => {
+            // The value may use other custom properties itself
+            stack.push(name);
+            let value = substitute_css(value, custom_properties, stack)?;
+            stack.pop();
+            css.push_str(&value);
+        },
+        None => {
+            if input.expect_comma().is_err() {
+                return Err(format!("as `{}` is not declared (and there is no fallback)", name))
+            }
+            input.skip_whitespace();
+            substitute_tokens(input, custom_properties, stack, css)?;
+        },
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ui;
+    use crate::{
+        context::CssContext,
+        stylesheet::CssStyle,
+    };
+    use super::*;
+
+    fn substitute(css: &str, custom_properties: &[(&str, &str)]) -> Result<String, String> {
+        let custom_properties = custom_properties.iter()
+            .map(|&(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        substitute_css(css, &custom_properties, &mut Vec::new())
+    }
+
+    #[test]
+    fn test_contains_var() {
+        let contains = |css| {
+            let mut parser_input = ParserInput::new(css);
+            let mut input = Parser::new(&mut parser_input);
+            let contains = contains_var(&mut input);
+            // The input is left where it was
+            assert!(input.next().is_ok());
+            contains
+        };
+        assert!(contains("var(--a)"));
+        assert!(contains("1px VAR(--a)"));
+        assert!(contains("min(10px, var(--a))"));
+        assert!(!contains("1px 2px"));
+        assert!(!contains("\"var(--a)\""));
+    }
+
+    #[test]
+    fn test_substitute() {
+        let custom = [("--a", "1px  2px"), ("--b", "var(--a) 3px"), ("--c", "var(--c)")];
+        assert_eq!(substitute("var(--a)", &custom).unwrap(), "1px  2px");
+        assert_eq!(substitute("var(--b) 4px", &custom).unwrap(), "1px  2px 3px 4px");
+        assert_eq!(substitute("min(var(--x, 5px), 10%)", &custom).unwrap(), "min(5px, 10%)");
+        assert_eq!(substitute("var(--x, var(--a))", &custom).unwrap(), "1px  2px");
+        assert!(substitute("var(--x)", &custom).is_err());
+        assert!(substitute("var(--c)", &custom).is_err());
+        assert!(substitute("var(a)", &custom).is_err());
+    }
+
+    #[test]
+    fn test_multi_token_value() {
+        // Bevy 0.8 has no grid layout (so no `grid-template-columns`), but shorthands like `margin`
+        // take several tokens in the same way
+        let style = CssStyle("--spacing: 1px 2px  3px; margin: var(--spacing); padding: var(--missing);");
+        let parsed = style.parse();
+        assert_eq!(
+            parsed.declarations[0],
+            BevyPropertyDeclaration::Custom(CustomDeclaration {
+                name: "--spacing".to_string(),
+                value: "1px 2px  3px".to_string(),
+            })
+        );
+        let style = style.to_style(&CssContext::default());
+        assert_eq!(style.margin.top, ui::Val::Px(1.0));
+        assert_eq!(style.margin.right, ui::Val::Px(2.0));
+        assert_eq!(style.margin.bottom, ui::Val::Px(3.0));
+        assert_eq!(style.margin.left, ui::Val::Px(2.0));
+        // The missing variable makes the declaration invalid, so it is ignored
+        assert_eq!(style.padding, ui::Style::default().padding);
+    }
+
+    #[test]
+    fn test_last_custom_property_wins() {
+        let style = CssStyle("width: var(--w); --w: 10px; --w: 20px !important; --w: 30px;");
+        assert_eq!(style.to_style(&CssContext::default()).size.width, ui::Val::Px(20.0));
+    }
+
+    #[test]
+    fn test_errors_reported_to_sink() {
+        use std::sync::{Arc, Mutex};
+        let collected = Arc::new(Mutex::new(Vec::new()));
+        let sink = {
+            let collected = collected.clone();
+            CssErrorSink::new(move |error| collected.lock().unwrap().push(error.to_string()))
+        };
+        let parsed = CssStyle("--w: red; width: var(--w); height: var(--h); margin: var(--h, 5px);").parse();
+        let resolved = resolve_variables(parsed.iter(), &sink);
+        // Only `margin` is left, with its fallback
+        assert_eq!(resolved.len(), 2);
+        let collected = collected.lock().unwrap();
+        assert_eq!(collected.len(), 2);
+        assert!(collected[1].contains("--h"), "{}", collected[1]);
+    }
+}
\ No newline at end of file
@@ -1,76 +1,166 @@
 use bevy::{
-    prelude::Color,
+    prelude::{Transform, Visibility},
+    text::{Text, TextStyle},
     ui,
 };
-use cssparser::{CowRcStr, Parser};
+use std::fmt;
+use cssparser::{CowRcStr, Parser, ToCss};
 use crate::{
     context::CssContext,
     errors::{BevyCssParsingError, BevyCssParsingErrorKind},
-    properties::{self, Property},
+    properties::{
+        self,
+        custom::{self, CustomDeclaration, UnparsedDeclaration},
+        Property,
+    },
     values::{
+        self,
+        absolute_length::AbsoluteLength,
+        alignment::PlaceContent,
         bevy_converters::ContextualInto,
-        LengthPercentageOrAuto, NonNegativeNumber, RatioOrAuto, SidedValue
+        bevy_impl::CssKeyword,
+        box_shadow::BoxShadow,
+        content::Content,
+        flex::{Flex, FlexFlow},
+        font::{FontFamily, FontWeight},
+        generic::{CommaSeparated, MaybeAuto, MaybeInitial, NonNegative, Numeric},
+        length::NoCalcLength,
+        AnimationSpec, ColorOrCurrentColor, CssWideKeyword, Integer, Length, LengthPercentageOrAuto, LineHeight, NonNegativeLength,
+        NonNegativeLengthPercentage, NonNegativeNumber, Number, PairedValue, RatioOrAuto, SidedValue, Size,
+        TransformList, TransitionList,
     },
 };
 
 /// Corresponds to `bevy::ui::Style`
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BevyPropertyDeclaration {
     // Display
     Display(ui::Display),
     Direction(ui::Direction),
-    Width(LengthPercentageOrAuto),
-    Height(LengthPercentageOrAuto),
-    MinWidth(LengthPercentageOrAuto),
-    MinHeight(LengthPercentageOrAuto),
-    MaxWidth(LengthPercentageOrAuto),
-    MaxHeight(LengthPercentageOrAuto),
+    Width(Size),
+    Height(Size),
+    MinWidth(Size),
+    MinHeight(Size),
+    MaxWidth(Size),
+    MaxHeight(Size),
     Overflow(ui::Overflow),
 
     // Position
     Position(ui::PositionType),
-    Top(LengthPercentageOrAuto),
-    Right(LengthPercentageOrAuto),
-    Bottom(LengthPercentageOrAuto),
-    Left(LengthPercentageOrAuto),
+    Top(MaybeInitial<LengthPercentageOrAuto>),
+    Right(MaybeInitial<LengthPercentageOrAuto>),
+    Bottom(MaybeInitial<LengthPercentageOrAuto>),
+    Left(MaybeInitial<LengthPercentageOrAuto>),
+    Inset(MaybeInitial<SidedValue<LengthPercentageOrAuto>>),
 
     // Flex Box
     FlexDirection(ui::FlexDirection),
     FlexWrap(ui::FlexWrap),
+    FlexFlow(FlexFlow),
     FlexGrow(NonNegativeNumber),
     FlexShrink(NonNegativeNumber),
     FlexBasis(LengthPercentageOrAuto),
     AspectRatio(RatioOrAuto),
+    Order(Integer),
+    Flex(Flex),
 
     // Alignment
     AlignItems(ui::AlignItems),
     AlignSelf(ui::AlignSelf),
     AlignContent(ui::AlignContent),
     JustifyContent(ui::JustifyContent),
+    PlaceContent(PlaceContent),
 
     // Margins
-    Margin(SidedValue<LengthPercentageOrAuto>),
-    MarginTop(LengthPercentageOrAuto),
-    MarginRight(LengthPercentageOrAuto),
-    MarginBottom(LengthPercentageOrAuto),
-    MarginLeft(LengthPercentageOrAuto),
+    Margin(MaybeInitial<SidedValue<LengthPercentageOrAuto>>),
+    MarginTop(MaybeInitial<LengthPercentageOrAuto>),
+    MarginRight(MaybeInitial<LengthPercentageOrAuto>),
+    MarginBottom(MaybeInitial<LengthPercentageOrAuto>),
+    MarginLeft(MaybeInitial<LengthPercentageOrAuto>),
 
     // Padding
-    Padding(SidedValue<LengthPercentageOrAuto>),
-    PaddingTop(LengthPercentageOrAuto),
-    PaddingRight(LengthPercentageOrAuto),
-    PaddingBottom(LengthPercentageOrAuto),
-    PaddingLeft(LengthPercentageOrAuto),
+    Padding(MaybeInitial<SidedValue<LengthPercentageOrAuto>>),
+    PaddingTop(MaybeInitial<LengthPercentageOrAuto>),
+    PaddingRight(MaybeInitial<LengthPercentageOrAuto>),
+    PaddingBottom(MaybeInitial<LengthPercentageOrAuto>),
+    PaddingLeft(MaybeInitial<LengthPercentageOrAuto>),
 
     // Borders
-    BorderWidth(SidedValue<LengthPercentageOrAuto>),
-    BorderWidthTop(LengthPercentageOrAuto),
-    BorderWidthRight(LengthPercentageOrAuto),
-    BorderWidthBottom(LengthPercentageOrAuto),
-    BorderWidthLeft(LengthPercentageOrAuto),
+    BorderWidth(MaybeInitial<SidedValue<LengthPercentageOrAuto>>),
+    BorderWidthTop(MaybeInitial<LengthPercentageOrAuto>),
+    BorderWidthRight(MaybeInitial<LengthPercentageOrAuto>),
+    BorderWidthBottom(MaybeInitial<LengthPercentageOrAuto>),
+    BorderWidthLeft(MaybeInitial<LengthPercentageOrAuto>),
+
+    // Gap
+    Gap(PairedValue<NonNegativeLengthPercentage>),
+    RowGap(NonNegativeLengthPercentage),
+    ColumnGap(NonNegativeLengthPercentage),
+
+    // Multi-column
+    ColumnCount(MaybeAuto<Integer>),
+    ColumnWidth(MaybeAuto<NonNegativeLength>),
+
+    // Visibility
+    Visibility(values::Visibility),
+
+    // Font
+    FontSize(NonNegativeLength),
+    LineHeight(LineHeight),
+    FontWeight(FontWeight),
+    FontFamily(FontFamily),
+
+    // Effects
+    BoxShadow(Option<CommaSeparated<BoxShadow>>),
+    Content(Content),
+    Opacity(f32),
+
+    // Transform
+    Transform(TransformList),
+
+    // Transition
+    Transition(TransitionList),
+
+    // Animation
+    /// `none` is `None`
+    Animation(Option<AnimationSpec>),
 
     // Color
-    Color(Color)
+    Color(ColorOrCurrentColor),
+    BackgroundColor(ColorOrCurrentColor),
+    TextColor(ColorOrCurrentColor),
+
+    // All
+    All(CssWideKeyword),
+
+    // Custom
+    /// A custom property (e.g. `--spacing: 1px 2px`), which is only used by `var()`
+    Custom(CustomDeclaration),
+    /// Any property whose value uses `var()`, which is parsed once the variables are substituted
+    /// (see `custom::resolve_variables`)
+    WithVariables(UnparsedDeclaration),
+}
+
+/// Whether a declaration was marked `!important`.  Important declarations are applied after (i.e.
+/// win over) all normal declarations, including inline ones.
+/// See also: https://drafts.csswg.org/css-cascade/#importance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Importance {
+    Normal,
+    Important,
+}
+
+/// The suffix of a serialized declaration, i.e. `!important` (or nothing for `Normal`), which the
+/// declaration parser turns back into the same `Importance`
+impl ToCss for Importance {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Normal => Ok(()),
+            Self::Important => dest.write_str("!important"),
+        }
+    }
 }
 
 // Convenience type
@@ -99,20 +189,36 @@ impl BevyPropertyDeclaration {
             Self::Right(right) => style.position.right = right.contextual_into(context),
             Self::Bottom(bottom) => style.position.bottom = bottom.contextual_into(context),
             Self::Left(left) => style.position.left = left.contextual_into(context),
+            Self::Inset(inset) => style.position = inset.contextual_into(context),
 
             // Flex Box
             Self::FlexDirection(flex_direction) => style.flex_direction = flex_direction,
             Self::FlexWrap(flex_wrap) => style.flex_wrap = flex_wrap,
+            Self::FlexFlow(flex_flow) => {
+                style.flex_direction = flex_flow.direction;
+                style.flex_wrap = flex_flow.wrap;
+            },
             Self::FlexGrow(flex_grow) => style.flex_grow = flex_grow.into(),
             Self::FlexShrink(flex_shrink) => style.flex_shrink = flex_shrink.into(),
             Self::FlexBasis(flex_basis) => style.flex_basis = flex_basis.contextual_into(context),
             Self::AspectRatio(aspect_ratio) => style.aspect_ratio = aspect_ratio.non_auto().map(|r| r.as_fraction()),
+            Self::Flex(flex) => {
+                style.flex_grow = flex.grow.into();
+                style.flex_shrink = flex.shrink.into();
+                style.flex_basis = flex.basis.contextual_into(context);
+            },
+            // @todo Bevy 0.8 `ui::Style` has no order field
+            Self::Order(_) => (),
 
             // Alignment
             Self::AlignItems(align_items) => style.align_items = align_items,
             Self::AlignSelf(align_self) => style.align_self = align_self,
             Self::AlignContent(align_content) => style.align_content = align_content,
             Self::JustifyContent(justify_content) => style.justify_content = justify_content,
+            Self::PlaceContent(place_content) => {
+                style.align_content = place_content.align;
+                style.justify_content = place_content.justify;
+            },
 
             // Margins
             Self::Margin(margin) => style.margin = margin.contextual_into(context),
@@ -135,19 +241,498 @@ impl BevyPropertyDeclaration {
             Self::BorderWidthBottom(border_width_bottom) => style.border.bottom = border_width_bottom.contextual_into(context),
             Self::BorderWidthLeft(border_width_left) => style.border.left = border_width_left.contextual_into(context),
 
+            // Gap
+            // @todo Bevy 0.8 `ui::Style` has no gap fields.  The values can still be converted to
+            //   `ui::Val` with `.contextual_into()` (`%` maps to `ui::Val::Percent`), once there is
+            //   somewhere to put them.
+            Self::Gap(_) | Self::RowGap(_) | Self::ColumnGap(_) => (),
+
+            // Multi-column
+            // @todo Bevy 0.8 has no multi-column layout
+            Self::ColumnCount(_) | Self::ColumnWidth(_) => (),
+
+            // Effects
+            // @todo Shadows are stored for a future renderer, Bevy 0.8 can't draw them
+            Self::BoxShadow(_) => (),
+            // @todo Bevy 0.8 has no pseudo-elements (e.g. `::before`) for the content to go in
+            Self::Content(_) => (),
+
+            // Transition
+            // Transitions are started by the `CssPlugin` once all the declarations have been applied
+            Self::Transition(_) => (),
+
+            // Animation
+            // Animations are started by the `CssPlugin`, once the keyframes are found
+            Self::Animation(_) => (),
+
+            // All
+            // `inherit` & `unset` also reset, then the `CssPlugin` copies what they inherit from the
+            // parent entity (see `UnstyledComponents::inherit`)
+            Self::All(_) => for property in Self::initial_style_properties() {
+                property.modify_style(context, style)
+            },
+
+            _ => (),
+        }
+    }
+
+    pub(crate) fn modify_visibility(&self, visibility: &mut Visibility) {
+        match *self {
+            // Visibility
+            Self::Visibility(value) => visibility.is_visible = value.is_visible(),
+
+            // All
+            Self::All(_) => *visibility = Visibility::default(),
+
+            _ => (),
+        }
+    }
+
+    /// A css `transform` replaces the whole of the entity's `Transform`
+    // @fixme Bevy's UI layout sets the `translation` of nodes every frame, so only rotations and
+    //   scales will currently last for UI nodes
+    pub(crate) fn modify_transform(&self, context: &CssContext, transform: &mut Transform) {
+        match *self {
+            // Transform
+            Self::Transform(ref transform_list) => *transform = transform_list.to_transform(context),
+
+            // All
+            Self::All(_) => *transform = Transform::default(),
+
+            _ => (),
+        }
+    }
+
+    /// Updates the context for any following declarations (e.g. `em` lengths after a `font-size`)
+    pub(crate) fn modify_context(&self, context: &mut CssContext) {
+        match *self {
+            // Font
+            // `em` font sizes are relative to the font size before this declaration (e.g. the parent's)
+            Self::FontSize(font_size) => context.font_size = font_size.0.to_computed_px(context),
+            // `lh` lengths are relative to the line height, which is computed with the current font size
+            Self::LineHeight(line_height) => context.line_height = line_height.to_computed_px(context),
+
+            // All
+            Self::All(_) => {
+                context.font_size = TextStyle::default().font_size;
+                context.line_height = None;
+            },
+
+            _ => (),
+        }
+    }
+
+    /// Text styles are set for every section of the `Text`
+    pub(crate) fn modify_text(&self, context: &CssContext, text: &mut Text) {
+        match *self {
+            // Font
+            Self::FontSize(font_size) => {
+                let font_size = font_size.0.to_computed_px(context);
+                for section in text.sections.iter_mut() {
+                    section.style.font_size = font_size
+                }
+            },
+            // @todo Bevy's `Text` has no line height, so `line-height` only affects `lh` lengths
+
+            // Color
+            Self::Color(color) | Self::TextColor(color) => {
+                for section in text.sections.iter_mut() {
+                    section.style.color = color.resolve(section.style.color)
+                }
+            },
+
+            // All
+            Self::All(_) => {
+                for section in text.sections.iter_mut() {
+                    section.style.color = TextStyle::default().color;
+                    section.style.font_size = TextStyle::default().font_size;
+                }
+            },
+
             _ => (),
         }
     }
 
+    /// Whether the property can be parsed, but is not (yet) supported by Bevy and so will be
+    /// ignored when styles are applied.  This is by name, so also covers a value with `var()`,
+    /// which isn't parsed until it's applied (e.g. `gap: var(--spacing)`).
+    pub fn is_unsupported(&self) -> bool {
+        Self::is_unsupported_property(self.name())
+    }
+
+    /// Whether the (lowercase) property name is one that can be parsed, but is not (yet) supported
+    /// by Bevy
+    fn is_unsupported_property(name: &str) -> bool {
+        matches!(name,
+            "order" |
+            "gap" | "row-gap" | "column-gap" |
+            "column-count" | "column-width" |
+            "box-shadow" | "content"
+        )
+    }
+
+    /// Whether applying the declaration will change a `ui::Style` (see `modify_style`)
+    pub fn affects_style(&self) -> bool {
+        matches!(*self,
+            Self::Display(_) | Self::Direction(_) | Self::Width(_) | Self::Height(_) |
+            Self::MinWidth(_) | Self::MinHeight(_) | Self::MaxWidth(_) | Self::MaxHeight(_) |
+            Self::Overflow(_) |
+            Self::Position(_) | Self::Top(_) | Self::Right(_) | Self::Bottom(_) | Self::Left(_) |
+            Self::Inset(_) |
+            Self::FlexDirection(_) | Self::FlexWrap(_) | Self::FlexFlow(_) | Self::FlexGrow(_) |
+            Self::FlexShrink(_) |
+            Self::FlexBasis(_) | Self::AspectRatio(_) | Self::Flex(_) |
+            Self::AlignItems(_) | Self::AlignSelf(_) | Self::AlignContent(_) | Self::JustifyContent(_) |
+            Self::PlaceContent(_) |
+            Self::Margin(_) | Self::MarginTop(_) | Self::MarginRight(_) | Self::MarginBottom(_) |
+            Self::MarginLeft(_) |
+            Self::Padding(_) | Self::PaddingTop(_) | Self::PaddingRight(_) | Self::PaddingBottom(_) |
+            Self::PaddingLeft(_) |
+            Self::BorderWidth(_) | Self::BorderWidthTop(_) | Self::BorderWidthRight(_) |
+            Self::BorderWidthBottom(_) | Self::BorderWidthLeft(_) |
+            Self::All(_)
+        )
+    }
+
+    /// Whether applying the declaration will change a `ui::UiColor` (see `modify_color`)
+    pub fn affects_color(&self) -> bool {
+        matches!(*self,
+            Self::Color(_) | Self::BackgroundColor(_) |
+            Self::Opacity(_) |
+            Self::All(_)
+        )
+    }
+
+    /// Whether applying the declaration will change a `Text` (see `modify_text`)
+    pub fn affects_text(&self) -> bool {
+        matches!(*self,
+            Self::FontSize(_) |
+            Self::Color(_) | Self::TextColor(_) |
+            Self::All(_)
+        )
+    }
+
+    /// Whether applying the declaration will change a `Visibility` (see `modify_visibility`)
+    pub fn affects_visibility(&self) -> bool {
+        matches!(*self,
+            Self::Visibility(_) |
+            Self::All(_)
+        )
+    }
+
+    /// Whether this is `font-family` or `font-weight`, which together choose a font from the
+    /// registered `CssFonts` (so they can't be applied one at a time like `modify_text`)
+    pub fn affects_font(&self) -> bool {
+        matches!(*self,
+            Self::FontWeight(_) | Self::FontFamily(_) |
+            Self::All(_)
+        )
+    }
+
+    /// Whether this is `visibility: collapse`, which (when it wins the cascade) also removes the node
+    /// from the layout
+    pub fn is_collapse(&self) -> bool {
+        matches!(*self, Self::Visibility(values::Visibility::Collapse))
+    }
+
+    /// Whether this is `all: inherit` or `all: unset`, which copy values from the parent entity
+    pub fn inherits_from_parent(&self) -> bool {
+        matches!(*self, Self::All(CssWideKeyword::Inherit | CssWideKeyword::Unset))
+    }
+
+    /// Whether applying the declaration will change a `Transform` (see `modify_transform`)
+    pub fn affects_transform(&self) -> bool {
+        matches!(*self,
+            Self::Transform(_) |
+            Self::All(_)
+        )
+    }
+
     pub(crate) fn modify_color(&self, ui_color: &mut ui::UiColor) {
         // Color
         match *self {
-            Self::Color(color) => ui_color.0 = color,
+            // Both currently set the node's background color
+            Self::Color(color) | Self::BackgroundColor(color) => ui_color.0 = color.resolve(ui_color.0),
+
+            // Effects
+            // Scales whichever alpha is already set, so this should be declared after any colors
+            Self::Opacity(opacity) => {
+                let alpha = ui_color.0.a() * opacity;
+                ui_color.0.set_a(alpha);
+            },
+
+            // All
+            Self::All(_) => *ui_color = ui::UiColor::default(),
 
             _ => (),
         }
     }
 
+    /// The same property, but with its initial value, i.e. the default of the field it sets (from
+    /// `ui::Style::default()`, `ui::UiColor::default()`, etc.).  Applying it resets the property.
+    ///
+    /// Bevy leaves the position, `margin`, `padding` & `border-width` as `ui::Val::Undefined` by
+    /// default, which only the `initial` keyword can express.
+    pub fn initial_value(&self) -> Self {
+        let style = ui::Style::default();
+        let auto_size = || Size::NotIntrinsic(LengthPercentageOrAuto::Auto);
+        match *self {
+            // Display
+            Self::Display(_) => Self::Display(style.display),
+            Self::Direction(_) => Self::Direction(style.direction),
+            Self::Width(_) => Self::Width(auto_size()),
+            Self::Height(_) => Self::Height(auto_size()),
+            Self::MinWidth(_) => Self::MinWidth(auto_size()),
+            Self::MinHeight(_) => Self::MinHeight(auto_size()),
+            Self::MaxWidth(_) => Self::MaxWidth(auto_size()),
+            Self::MaxHeight(_) => Self::MaxHeight(auto_size()),
+            Self::Overflow(_) => Self::Overflow(style.overflow),
+
+            // Position
+            Self::Position(_) => Self::Position(style.position_type),
+            Self::Top(_) => Self::Top(MaybeInitial::Initial),
+            Self::Right(_) => Self::Right(MaybeInitial::Initial),
+            Self::Bottom(_) => Self::Bottom(MaybeInitial::Initial),
+            Self::Left(_) => Self::Left(MaybeInitial::Initial),
+            Self::Inset(_) => Self::Inset(MaybeInitial::Initial),
+
+            // Flex Box
+            Self::FlexDirection(_) => Self::FlexDirection(style.flex_direction),
+            Self::FlexWrap(_) => Self::FlexWrap(style.flex_wrap),
+            Self::FlexFlow(_) => Self::FlexFlow(FlexFlow { direction: style.flex_direction, wrap: style.flex_wrap }),
+            Self::FlexGrow(_) => Self::FlexGrow(NonNegative(Number(style.flex_grow))),
+            Self::FlexShrink(_) => Self::FlexShrink(NonNegative(Number(style.flex_shrink))),
+            Self::FlexBasis(_) => Self::FlexBasis(LengthPercentageOrAuto::Auto),
+            Self::AspectRatio(_) => Self::AspectRatio(RatioOrAuto::Auto),
+            Self::Order(_) => Self::Order(Integer(0)),
+            Self::Flex(_) => Self::Flex(Flex {
+                grow: NonNegative(Number(style.flex_grow)),
+                shrink: NonNegative(Number(style.flex_shrink)),
+                basis: LengthPercentageOrAuto::Auto,
+            }),
+
+            // Alignment
+            Self::AlignItems(_) => Self::AlignItems(style.align_items),
+            Self::AlignSelf(_) => Self::AlignSelf(style.align_self),
+            Self::AlignContent(_) => Self::AlignContent(style.align_content),
+            Self::JustifyContent(_) => Self::JustifyContent(style.justify_content),
+            Self::PlaceContent(_) => Self::PlaceContent(PlaceContent {
+                align: style.align_content,
+                justify: style.justify_content,
+            }),
+
+            // Margins
+            Self::Margin(_) => Self::Margin(MaybeInitial::Initial),
+            Self::MarginTop(_) => Self::MarginTop(MaybeInitial::Initial),
+            Self::MarginRight(_) => Self::MarginRight(MaybeInitial::Initial),
+            Self::MarginBottom(_) => Self::MarginBottom(MaybeInitial::Initial),
+            Self::MarginLeft(_) => Self::MarginLeft(MaybeInitial::Initial),
+
+            // Padding
+            Self::Padding(_) => Self::Padding(MaybeInitial::Initial),
+            Self::PaddingTop(_) => Self::PaddingTop(MaybeInitial::Initial),
+            Self::PaddingRight(_) => Self::PaddingRight(MaybeInitial::Initial),
+            Self::PaddingBottom(_) => Self::PaddingBottom(MaybeInitial::Initial),
+            Self::PaddingLeft(_) => Self::PaddingLeft(MaybeInitial::Initial),
+
+            // Borders
+            Self::BorderWidth(_) => Self::BorderWidth(MaybeInitial::Initial),
+            Self::BorderWidthTop(_) => Self::BorderWidthTop(MaybeInitial::Initial),
+            Self::BorderWidthRight(_) => Self::BorderWidthRight(MaybeInitial::Initial),
+            Self::BorderWidthBottom(_) => Self::BorderWidthBottom(MaybeInitial::Initial),
+            Self::BorderWidthLeft(_) => Self::BorderWidthLeft(MaybeInitial::Initial),
+
+            // Gap
+            Self::Gap(_) => Self::Gap(PairedValue::new_1(NonNegativeLengthPercentage::zero())),
+            Self::RowGap(_) => Self::RowGap(NonNegativeLengthPercentage::zero()),
+            Self::ColumnGap(_) => Self::ColumnGap(NonNegativeLengthPercentage::zero()),
+
+            // Multi-column
+            Self::ColumnCount(_) => Self::ColumnCount(MaybeAuto::Auto),
+            Self::ColumnWidth(_) => Self::ColumnWidth(MaybeAuto::Auto),
+
+            // Visibility
+            Self::Visibility(_) => Self::Visibility(values::Visibility::default()),
+
+            // Font
+            Self::FontSize(_) => Self::FontSize(NonNegative(Length::NoCalc(
+                NoCalcLength::Absolute(AbsoluteLength::Px(TextStyle::default().font_size))
+            ))),
+            Self::LineHeight(_) => Self::LineHeight(LineHeight::Normal),
+            Self::FontWeight(_) => Self::FontWeight(FontWeight::default()),
+            // An empty family list finds no registered font, so the text keeps its own
+            Self::FontFamily(_) => Self::FontFamily(FontFamily(Vec::new())),
+
+            // Effects
+            Self::BoxShadow(_) => Self::BoxShadow(None),
+            Self::Content(_) => Self::Content(Content::Normal),
+            Self::Opacity(_) => Self::Opacity(1.0),
+
+            // Transform
+            Self::Transform(_) => Self::Transform(TransformList(Vec::new())),
+
+            // Transition
+            Self::Transition(_) => Self::Transition(TransitionList::default()),
+
+            // Animation
+            Self::Animation(_) => Self::Animation(None),
+
+            // Color
+            Self::Color(_) => Self::Color(ColorOrCurrentColor::Color(ui::UiColor::default().0)),
+            Self::BackgroundColor(_) => Self::BackgroundColor(ColorOrCurrentColor::Color(ui::UiColor::default().0)),
+            Self::TextColor(_) => Self::TextColor(ColorOrCurrentColor::Color(TextStyle::default().color)),
+
+            // All
+            Self::All(_) => Self::All(CssWideKeyword::Initial),
+
+            // Custom
+            // @todo Custom properties initially have no value, and an unparsed declaration can only
+            //   be reset once it is known which property it is for
+            Self::Custom(_) | Self::WithVariables(_) => self.clone(),
+        }
+    }
+
+    /// The initial value of a property for every field of a `ui::Style`, which `all` resets.  The
+    /// values given here don't matter, as `initial_value()` replaces them.
+    fn initial_style_properties() -> [Self; 24] {
+        let auto_size = Size::NotIntrinsic(LengthPercentageOrAuto::Auto);
+        let zero = NonNegative(Number(0.0));
+        [
+            Self::Display(Default::default()),
+            Self::Direction(Default::default()),
+            Self::Width(auto_size),
+            Self::Height(auto_size),
+            Self::MinWidth(auto_size),
+            Self::MinHeight(auto_size),
+            Self::MaxWidth(auto_size),
+            Self::MaxHeight(auto_size),
+            Self::Overflow(Default::default()),
+            Self::Position(Default::default()),
+            Self::Inset(MaybeInitial::Initial),
+            Self::FlexDirection(Default::default()),
+            Self::FlexWrap(Default::default()),
+            Self::FlexGrow(zero),
+            Self::FlexShrink(zero),
+            Self::FlexBasis(LengthPercentageOrAuto::Auto),
+            Self::AspectRatio(RatioOrAuto::Auto),
+            Self::AlignItems(Default::default()),
+            Self::AlignSelf(Default::default()),
+            Self::AlignContent(Default::default()),
+            Self::JustifyContent(Default::default()),
+            Self::Margin(MaybeInitial::Initial),
+            Self::Padding(MaybeInitial::Initial),
+            Self::BorderWidth(MaybeInitial::Initial),
+        ].map(|property| property.initial_value())
+    }
+
+    /// The (lowercase) name of the property.  The properties that are stored as another (e.g.
+    /// `overflow-x` as `overflow`, or `place-items` as `align-items`) have that property's name.
+    pub fn name(&self) -> &str {
+        match *self {
+            // Display
+            Self::Display(_) => "display",
+            Self::Direction(_) => "direction",
+            Self::Width(_) => "width",
+            Self::Height(_) => "height",
+            Self::MinWidth(_) => "min-width",
+            Self::MinHeight(_) => "min-height",
+            Self::MaxWidth(_) => "max-width",
+            Self::MaxHeight(_) => "max-height",
+            Self::Overflow(_) => "overflow",
+
+            // Position
+            Self::Position(_) => "position",
+            Self::Top(_) => "top",
+            Self::Right(_) => "right",
+            Self::Bottom(_) => "bottom",
+            Self::Left(_) => "left",
+            Self::Inset(_) => "inset",
+
+            // Flex Box
+            Self::FlexDirection(_) => "flex-direction",
+            Self::FlexWrap(_) => "flex-wrap",
+            Self::FlexFlow(_) => "flex-flow",
+            Self::FlexGrow(_) => "flex-grow",
+            Self::FlexShrink(_) => "flex-shrink",
+            Self::FlexBasis(_) => "flex-basis",
+            Self::AspectRatio(_) => "aspect-ratio",
+            Self::Order(_) => "order",
+            Self::Flex(_) => "flex",
+
+            // Alignment
+            Self::AlignItems(_) => "align-items",
+            Self::AlignSelf(_) => "align-self",
+            Self::AlignContent(_) => "align-content",
+            Self::JustifyContent(_) => "justify-content",
+            Self::PlaceContent(_) => "place-content",
+
+            // Margins
+            Self::Margin(_) => "margin",
+            Self::MarginTop(_) => "margin-top",
+            Self::MarginRight(_) => "margin-right",
+            Self::MarginBottom(_) => "margin-bottom",
+            Self::MarginLeft(_) => "margin-left",
+
+            // Padding
+            Self::Padding(_) => "padding",
+            Self::PaddingTop(_) => "padding-top",
+            Self::PaddingRight(_) => "padding-right",
+            Self::PaddingBottom(_) => "padding-bottom",
+            Self::PaddingLeft(_) => "padding-left",
+
+            // Borders
+            Self::BorderWidth(_) => "border-width",
+            Self::BorderWidthTop(_) => "border-width-top",
+            Self::BorderWidthRight(_) => "border-width-right",
+            Self::BorderWidthBottom(_) => "border-width-bottom",
+            Self::BorderWidthLeft(_) => "border-width-left",
+
+            // Gap
+            Self::Gap(_) => "gap",
+            Self::RowGap(_) => "row-gap",
+            Self::ColumnGap(_) => "column-gap",
+
+            // Multi-column
+            Self::ColumnCount(_) => "column-count",
+            Self::ColumnWidth(_) => "column-width",
+
+            // Visibility
+            Self::Visibility(_) => "visibility",
+
+            // Font
+            Self::FontSize(_) => "font-size",
+            Self::LineHeight(_) => "line-height",
+            Self::FontWeight(_) => "font-weight",
+            Self::FontFamily(_) => "font-family",
+
+            // Effects
+            Self::BoxShadow(_) => "box-shadow",
+            Self::Content(_) => "content",
+            Self::Opacity(_) => "opacity",
+
+            // Transform
+            Self::Transform(_) => "transform",
+
+            // Transition
+            Self::Transition(_) => "transition",
+
+            // Animation
+            Self::Animation(_) => "animation",
+
+            // Color
+            Self::Color(_) => "color",
+            Self::BackgroundColor(_) => "background-color",
+            Self::TextColor(_) => "text-color",
+
+            // All
+            Self::All(_) => "all",
+
+            // Custom
+            Self::Custom(ref custom) => &custom.name,
+            Self::WithVariables(ref unparsed) => &unparsed.property,
+        }
+    }
+
     fn parsing_func_from_name(name: &CowRcStr) -> Option<ParsingFunc> {
         Some(match name.to_ascii_lowercase().as_str() {
             // Display
@@ -160,6 +745,8 @@ impl BevyPropertyDeclaration {
             "max-width"         => properties::MaxWidth::parse_declaration,
             "max-height"        => properties::MaxHeight::parse_declaration,
             "overflow"          => properties::Overflow::parse_declaration,
+            "overflow-x"        => properties::OverflowX::parse_declaration,
+            "overflow-y"        => properties::OverflowY::parse_declaration,
 
             // Position
             "position"          => properties::Position::parse_declaration,
@@ -167,20 +754,27 @@ impl BevyPropertyDeclaration {
             "right"             => properties::Right::parse_declaration,
             "bottom"            => properties::Bottom::parse_declaration,
             "left"              => properties::Left::parse_declaration,
+            "inset"             => properties::Inset::parse_declaration,
 
             // Flex Box
             "flex-direction"    => properties::FlexDirection::parse_declaration,
             "flex-wrap"         => properties::FlexWrap::parse_declaration,
+            "flex-flow"         => properties::FlexFlow::parse_declaration,
             "flex-grow"         => properties::FlexGrow::parse_declaration,
             "flex-shrink"       => properties::FlexShrink::parse_declaration,
             "flex-basis"        => properties::FlexBasis::parse_declaration,
             "aspect-ratio"      => properties::AspectRatio::parse_declaration,
+            "order"             => properties::Order::parse_declaration,
+            "flex"              => properties::Flex::parse_declaration,
 
             // Alignment
             "align-items"       => properties::AlignItems::parse_declaration,
             "align-self"        => properties::AlignSelf::parse_declaration,
             "align-content"     => properties::AlignContent::parse_declaration,
             "justify-content"   => properties::JustifyContent::parse_declaration,
+            "place-content"     => properties::PlaceContent::parse_declaration,
+            "place-items"       => properties::PlaceItems::parse_declaration,
+            "place-self"        => properties::PlaceSelf::parse_declaration,
 
             // Margins
             "margin"            => properties::Margin::parse_declaration,
@@ -203,8 +797,45 @@ impl BevyPropertyDeclaration {
             "border-width-bottom"     => properties::BorderWidthBottom::parse_declaration,
             "border-width-left"       => properties::BorderWidthLeft::parse_declaration,
 
+            // Gap
+            "gap"               => properties::Gap::parse_declaration,
+            "row-gap"           => properties::RowGap::parse_declaration,
+            "column-gap"        => properties::ColumnGap::parse_declaration,
+
+            // Multi-column
+            "column-count"      => properties::ColumnCount::parse_declaration,
+            "column-width"      => properties::ColumnWidth::parse_declaration,
+
+            // Visibility
+            "visibility"        => properties::Visibility::parse_declaration,
+
+            // Font
+            "font-size"         => properties::FontSize::parse_declaration,
+            "line-height"       => properties::LineHeight::parse_declaration,
+            "font-weight"       => properties::FontWeight::parse_declaration,
+            "font-family"       => properties::FontFamily::parse_declaration,
+
+            // Effects
+            "box-shadow"        => properties::BoxShadow::parse_declaration,
+            "content"           => properties::Content::parse_declaration,
+            "opacity"           => properties::Opacity::parse_declaration,
+
+            // Transform
+            "transform"         => properties::Transform::parse_declaration,
+
+            // Transition
+            "transition"        => properties::Transition::parse_declaration,
+
+            // Animation
+            "animation"         => properties::Animation::parse_declaration,
+
             // Color
             "color"             => properties::Color::parse_declaration,
+            "background-color"  => properties::BackgroundColor::parse_declaration,
+            "text-color"        => properties::TextColor::parse_declaration,
+
+            // All
+            "all"               => properties::All::parse_declaration,
 
             _ => return None
         })
@@ -214,7 +845,13 @@ impl BevyPropertyDeclaration {
         property_name: CowRcStr<'i>,
         input: &mut Parser<'i, 't>
     ) -> Result<Self, BevyCssParsingError<'i>> {
+        if property_name.starts_with("--") {
+            return CustomDeclaration::parse(&property_name, input).map(Self::Custom)
+        }
         match Self::parsing_func_from_name(&property_name) {
+            // The value can only be parsed once the variables are known
+            Some(_) if custom::contains_var(input) =>
+                UnparsedDeclaration::parse(&property_name, input).map(Self::WithVariables),
             Some(property_parsing_func) => property_parsing_func(input),
             None => Err(
                 input.new_custom_error(BevyCssParsingErrorKind::UnknownProperty(property_name.to_owned()))
@@ -223,19 +860,132 @@ impl BevyPropertyDeclaration {
     }
 }
 
+/// The declaration without its `;`, e.g. `margin: 10px 5px`.  Values are written in their shortest
+/// form, so they can differ from the css they were parsed from, but will parse to the same value.
+impl ToCss for BevyPropertyDeclaration {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        dest.write_str(self.name())?;
+        dest.write_str(": ")?;
+        match *self {
+            // Display
+            Self::Display(display) => dest.write_str(display.css_keyword()),
+            Self::Direction(direction) => dest.write_str(direction.css_keyword()),
+            Self::Width(ref size) | Self::Height(ref size) |
+            Self::MinWidth(ref size) | Self::MinHeight(ref size) |
+            Self::MaxWidth(ref size) | Self::MaxHeight(ref size) => size.to_css(dest),
+            Self::Overflow(overflow) => dest.write_str(overflow.css_keyword()),
+
+            // Position
+            Self::Position(position_type) => dest.write_str(position_type.css_keyword()),
+            Self::Top(ref value) | Self::Right(ref value) | Self::Bottom(ref value) | Self::Left(ref value) =>
+                value.to_css(dest),
+            Self::Inset(ref inset) => inset.to_css(dest),
+
+            // Flex Box
+            Self::FlexDirection(flex_direction) => dest.write_str(flex_direction.css_keyword()),
+            Self::FlexWrap(flex_wrap) => dest.write_str(flex_wrap.css_keyword()),
+            Self::FlexFlow(ref flex_flow) => flex_flow.to_css(dest),
+            Self::FlexGrow(ref factor) | Self::FlexShrink(ref factor) => factor.to_css(dest),
+            Self::FlexBasis(ref flex_basis) => flex_basis.to_css(dest),
+            Self::AspectRatio(ref aspect_ratio) => aspect_ratio.to_css(dest),
+            Self::Order(ref order) => order.to_css(dest),
+            Self::Flex(ref flex) => flex.to_css(dest),
+
+            // Alignment
+            Self::AlignItems(align_items) => dest.write_str(align_items.css_keyword()),
+            Self::AlignSelf(align_self) => dest.write_str(align_self.css_keyword()),
+            Self::AlignContent(align_content) => dest.write_str(align_content.css_keyword()),
+            Self::JustifyContent(justify_content) => dest.write_str(justify_content.css_keyword()),
+            Self::PlaceContent(ref place_content) => place_content.to_css(dest),
+
+            // Margins, Padding & Borders
+            Self::Margin(ref sides) | Self::Padding(ref sides) | Self::BorderWidth(ref sides) => sides.to_css(dest),
+            Self::MarginTop(ref value) | Self::MarginRight(ref value) |
+            Self::MarginBottom(ref value) | Self::MarginLeft(ref value) |
+            Self::PaddingTop(ref value) | Self::PaddingRight(ref value) |
+            Self::PaddingBottom(ref value) | Self::PaddingLeft(ref value) |
+            Self::BorderWidthTop(ref value) | Self::BorderWidthRight(ref value) |
+            Self::BorderWidthBottom(ref value) | Self::BorderWidthLeft(ref value) => value.to_css(dest),
+
+            // Gap
+            Self::Gap(ref gap) => gap.to_css(dest),
+            Self::RowGap(ref gap) | Self::ColumnGap(ref gap) => gap.to_css(dest),
+
+            // Multi-column
+            Self::ColumnCount(ref count) => count.to_css(dest),
+            Self::ColumnWidth(ref width) => width.to_css(dest),
+
+            // Visibility
+            Self::Visibility(ref visibility) => visibility.to_css(dest),
+
+            // Font
+            Self::FontSize(ref font_size) => font_size.to_css(dest),
+            Self::LineHeight(ref line_height) => line_height.to_css(dest),
+            Self::FontWeight(ref font_weight) => font_weight.to_css(dest),
+            Self::FontFamily(ref font_family) => font_family.to_css(dest),
+
+            // Effects
+            Self::BoxShadow(None) => dest.write_str("none"),
+            Self::BoxShadow(Some(ref shadows)) => shadows.to_css(dest),
+            Self::Content(ref content) => content.to_css(dest),
+            Self::Opacity(opacity) => write!(dest, "{}", opacity),
+
+            // Transform
+            Self::Transform(ref transform_list) => transform_list.to_css(dest),
+
+            // Transition
+            Self::Transition(ref transition_list) => transition_list.to_css(dest),
+
+            // Animation
+            Self::Animation(None) => dest.write_str("none"),
+            Self::Animation(Some(ref animation)) => animation.to_css(dest),
+
+            // Color
+            Self::Color(ref color) | Self::BackgroundColor(ref color) | Self::TextColor(ref color) =>
+                color.to_css(dest),
+
+            // All
+            Self::All(ref keyword) => keyword.to_css(dest),
+
+            // Custom
+            Self::Custom(ref custom) => dest.write_str(&custom.value),
+            Self::WithVariables(ref unparsed) => dest.write_str(&unparsed.css),
+        }
+    }
+}
+
+/// The declaration as it would be written in a block, e.g. `margin: 10px 5px;`
+impl fmt::Display for BevyPropertyDeclaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_css(f)?;
+        f.write_str(";")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bevy::ui;
+    use bevy::{
+        prelude::Color,
+        text::TextSection,
+        ui,
+    };
     use cssparser::{ParseErrorKind, Parser, ParserInput};
     use crate::values::{
-        generic::{NonNegative, Numeric},
+        generic::{IntrinsicSize, NonNegative, Numeric},
         absolute_length::AbsoluteLength,
-        length::{NoCalcLength},
+        animation::IterationCount,
+        content::ContentItem,
+        length::{FontRelativeLength, NoCalcLength},
+        Length,
         LengthPercentage,
         Number,
+        Parse,
+        easing::EasingFunction,
         percentage::Percentage,
-        Ratio
+        transition::TransitionProperty,
+        Ratio,
+        Time,
     };
 
     //  Helpers //
@@ -370,7 +1120,7 @@ mod tests {
     fn test_width() {
         parse_all_property_values(
             "width",
-            BevyPropertyDeclaration::Width,
+            |value| BevyPropertyDeclaration::Width(Size::from(value)),
             auto_length_percentage_vec()
         );
     }
@@ -379,7 +1129,7 @@ mod tests {
     fn test_min_width() {
         parse_all_property_values(
             "min-width",
-            BevyPropertyDeclaration::MinWidth,
+            |value| BevyPropertyDeclaration::MinWidth(Size::from(value)),
             auto_length_percentage_vec()
         );
     }
@@ -388,7 +1138,7 @@ mod tests {
     fn test_max_width() {
         parse_all_property_values(
             "max-width",
-            BevyPropertyDeclaration::MaxWidth,
+            |value| BevyPropertyDeclaration::MaxWidth(Size::from(value)),
             auto_length_percentage_vec()
         );
     }
@@ -397,7 +1147,7 @@ mod tests {
     fn test_height() {
         parse_all_property_values(
             "height",
-            BevyPropertyDeclaration::Height,
+            |value| BevyPropertyDeclaration::Height(Size::from(value)),
             auto_length_percentage_vec()
         );
     }
@@ -406,7 +1156,7 @@ mod tests {
     fn test_min_height() {
         parse_all_property_values(
             "min-height",
-            BevyPropertyDeclaration::MinHeight,
+            |value| BevyPropertyDeclaration::MinHeight(Size::from(value)),
             auto_length_percentage_vec()
         );
     }
@@ -415,11 +1165,45 @@ mod tests {
     fn test_max_height() {
         parse_all_property_values(
             "max-height",
-            BevyPropertyDeclaration::MaxHeight,
+            |value| BevyPropertyDeclaration::MaxHeight(Size::from(value)),
             auto_length_percentage_vec()
         );
     }
 
+    #[test]
+    fn test_intrinsic_sizes() {
+        for property in ["width", "height", "min-width", "min-height", "max-width", "max-height"] {
+            for (css, intrinsic) in [
+                ("min-content", IntrinsicSize::MinContent),
+                ("max-content", IntrinsicSize::MaxContent),
+                ("FIT-CONTENT", IntrinsicSize::FitContent),
+            ] {
+                let declaration = parse_property_value(property, css);
+                assert!(!declaration.is_unsupported());
+                let size = match declaration {
+                    BevyPropertyDeclaration::Width(size) |
+                    BevyPropertyDeclaration::Height(size) |
+                    BevyPropertyDeclaration::MinWidth(size) |
+                    BevyPropertyDeclaration::MinHeight(size) |
+                    BevyPropertyDeclaration::MaxWidth(size) |
+                    BevyPropertyDeclaration::MaxHeight(size) => size,
+                    _ => unreachable!(),
+                };
+                assert_eq!(size, Size::Intrinsic(intrinsic));
+            }
+        }
+
+        let mut style = ui::Style::default();
+        parse_property_value("width", "min-content").modify_style(&CssContext::default(), &mut style);
+        assert_eq!(style.size.width, ui::Val::Auto);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_intrinsic_sizes_not_margins() {
+        parse_property_value("margin-top", "min-content");
+    }
+
     #[test]
     fn test_overflow() {
         parse_all_property_values(
@@ -428,10 +1212,48 @@ mod tests {
             vec![
                 ("visible", ui::Overflow::Visible),
                 ("hidden", ui::Overflow::Hidden),
+                ("hidden hidden", ui::Overflow::Hidden),
+                ("visible visible", ui::Overflow::Visible),
+                // Bevy has no per-axis overflow, so `hidden` wins
+                ("hidden visible", ui::Overflow::Hidden),
+                ("visible hidden", ui::Overflow::Hidden),
+                ("initial", ui::Overflow::Visible),
             ]
         );
     }
 
+    #[test]
+    fn test_overflow_axes() {
+        for property in ["overflow-x", "overflow-y"] {
+            parse_all_property_values(
+                property,
+                BevyPropertyDeclaration::Overflow,
+                vec![
+                    ("visible", ui::Overflow::Visible),
+                    ("hidden", ui::Overflow::Hidden),
+                ]
+            );
+        }
+        // Each sets both axes, so the last one wins
+        let mut style = ui::Style::default();
+        for (property, value) in [("overflow-x", "hidden"), ("overflow-y", "visible")] {
+            parse_property_value(property, value).modify_style(&CssContext::default(), &mut style);
+        }
+        assert_eq!(style.overflow, ui::Overflow::Visible);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow_axis_single_value() {
+        parse_property_value("overflow-x", "hidden hidden");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow_too_many_values() {
+        parse_property_value("overflow", "hidden hidden hidden");
+    }
+
     // Position //
 
     #[test]
@@ -450,7 +1272,7 @@ mod tests {
     fn test_top() {
         parse_all_property_values(
             "top",
-            BevyPropertyDeclaration::Top,
+            |value| BevyPropertyDeclaration::Top(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -459,7 +1281,7 @@ mod tests {
     fn test_right() {
         parse_all_property_values(
             "right",
-            BevyPropertyDeclaration::Right,
+            |value| BevyPropertyDeclaration::Right(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -468,7 +1290,7 @@ mod tests {
     fn test_bottom() {
         parse_all_property_values(
             "bottom",
-            BevyPropertyDeclaration::Bottom,
+            |value| BevyPropertyDeclaration::Bottom(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -477,11 +1299,28 @@ mod tests {
     fn test_left() {
         parse_all_property_values(
             "left",
-            BevyPropertyDeclaration::Left,
+            |value| BevyPropertyDeclaration::Left(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
 
+    #[test]
+    fn test_inset() {
+        parse_all_property_values(
+            "inset",
+            |value| BevyPropertyDeclaration::Inset(MaybeInitial::NotInitial(value)),
+            sided_value_vec()
+        );
+        let mut style = ui::Style::default();
+        parse_property_value("inset", "5px").modify_style(&CssContext::default(), &mut style);
+        assert_eq!(style.position, ui::UiRect::all(ui::Val::Px(5.0)));
+        parse_property_value("inset", "1px 2px 3px").modify_style(&CssContext::default(), &mut style);
+        assert_eq!(style.position.top, ui::Val::Px(1.0));
+        assert_eq!(style.position.right, ui::Val::Px(2.0));
+        assert_eq!(style.position.bottom, ui::Val::Px(3.0));
+        assert_eq!(style.position.left, ui::Val::Px(2.0));
+    }
+
     // Flex Box //
 
     #[test]
@@ -561,6 +1400,14 @@ mod tests {
                     NonNegative(Number(1.0)),
                     NonNegative(Number(1.0))
                 ))),
+                ("16/9", RatioOrAuto::NotAuto(Ratio(
+                    NonNegative(Number(16.0)),
+                    NonNegative(Number(9.0))
+                ))),
+                ("16 / 9", RatioOrAuto::NotAuto(Ratio(
+                    NonNegative(Number(16.0)),
+                    NonNegative(Number(9.0))
+                ))),
                 ("0 / 1", RatioOrAuto::NotAuto(Ratio(
                     NonNegative(Number(0.0)),
                     NonNegative(Number(1.0))
@@ -581,12 +1428,131 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_aspect_ratio_with_constraints() {
+        let context = CssContext::default();
+        let mut style = ui::Style::default();
+        for (property, value) in [
+            ("aspect-ratio", "16 / 9"),
+            ("width", "100%"),
+            ("min-width", "200px"),
+            ("max-height", "300px"),
+        ] {
+            parse_property_value(property, value).modify_style(&context, &mut style);
+        }
+        // Every field is kept, so Bevy can resolve the ratio against the constraints
+        assert_eq!(style.aspect_ratio, Some(16.0 / 9.0));
+        assert_eq!(style.size.width, ui::Val::Percent(100.0));
+        assert_eq!(style.min_size.width, ui::Val::Px(200.0));
+        assert_eq!(style.max_size.height, ui::Val::Px(300.0));
+        assert_eq!(style.size.height, ui::Val::Auto);
+
+        // Applying them in the opposite order makes no difference
+        let mut reversed = ui::Style::default();
+        for (property, value) in [
+            ("max-height", "300px"),
+            ("min-width", "200px"),
+            ("width", "100%"),
+            ("aspect-ratio", "16 / 9"),
+        ] {
+            parse_property_value(property, value).modify_style(&context, &mut reversed);
+        }
+        assert_eq!(reversed, style);
+    }
+
+    #[test]
+    fn test_aspect_ratio_decimals() {
+        for (css, a, b) in [
+            ("1.5 / 2.5", 1.5, 2.5),
+            ("1.5/2.5", 1.5, 2.5),
+            ("16.0/9.0", 16.0, 9.0),
+            ("16.0 /9", 16.0, 9.0),
+            ("0.75", 0.75, 1.0),
+        ] {
+            let declaration = parse_property_value("aspect-ratio", css);
+            let ratio = Ratio(NonNegative(Number(a)), NonNegative(Number(b)));
+            assert_eq!(declaration, BevyPropertyDeclaration::AspectRatio(RatioOrAuto::NotAuto(ratio)), "{}", css);
+
+            let mut style = ui::Style::default();
+            declaration.modify_style(&CssContext::default(), &mut style);
+            assert_eq!(style.aspect_ratio, Some(a / b), "{}", css);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_aspect_ratio_negative() {
         parse_property_value("aspect-ratio", "-1");
     }
 
+    #[test]
+    fn test_flex_flow() {
+        let context = CssContext::default();
+        let mut style = ui::Style::default();
+        parse_property_value("flex-flow", "row wrap").modify_style(&context, &mut style);
+        assert_eq!(style.flex_direction, ui::FlexDirection::Row);
+        assert_eq!(style.flex_wrap, ui::FlexWrap::Wrap);
+
+        let mut style = ui::Style::default();
+        parse_property_value("flex-flow", "column").modify_style(&context, &mut style);
+        assert_eq!(style.flex_direction, ui::FlexDirection::Column);
+        assert_eq!(style.flex_wrap, ui::Style::default().flex_wrap);
+
+        let mut style = ui::Style::default();
+        parse_property_value("flex-flow", "wrap-reverse").modify_style(&context, &mut style);
+        assert_eq!(style.flex_direction, ui::Style::default().flex_direction);
+        assert_eq!(style.flex_wrap, ui::FlexWrap::WrapReverse);
+    }
+
+    #[test]
+    fn test_flex() {
+        let context = CssContext::default();
+        for (css, grow, shrink, basis) in [
+            ("1", 1.0, 1.0, ui::Val::Px(0.0)),
+            ("1 1 auto", 1.0, 1.0, ui::Val::Auto),
+            ("auto", 1.0, 1.0, ui::Val::Auto),
+            ("none", 0.0, 0.0, ui::Val::Auto),
+            ("2 0 50%", 2.0, 0.0, ui::Val::Percent(50.0)),
+            ("10px 3", 3.0, 1.0, ui::Val::Px(10.0)),
+        ] {
+            let declaration = parse_property_value("flex", css);
+            assert!(declaration.affects_style());
+            let mut style = ui::Style::default();
+            declaration.modify_style(&context, &mut style);
+            assert_eq!(style.flex_grow, grow, "{}", css);
+            assert_eq!(style.flex_shrink, shrink, "{}", css);
+            assert_eq!(style.flex_basis, basis, "{}", css);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_flex_too_many_values() {
+        parse_property_value("flex", "1 1 auto 2");
+    }
+
+    #[test]
+    fn test_order() {
+        parse_all_property_values(
+            "order",
+            BevyPropertyDeclaration::Order,
+            vec![
+                ("0", Integer(0)),
+                ("2", Integer(2)),
+                ("+3", Integer(3)),
+                ("-1", Integer(-1)),
+                ("-20", Integer(-20)),
+            ]
+        );
+        assert!(parse_property_value("order", "2").is_unsupported());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_order_not_integer() {
+        parse_property_value("order", "1.5");
+    }
+
     // Alignment //
 
     #[test]
@@ -652,13 +1618,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_place_content() {
+        let mut style = ui::Style::default();
+        parse_property_value("place-content", "center space-between").modify_style(&CssContext::default(), &mut style);
+        assert_eq!(style.align_content, ui::AlignContent::Center);
+        assert_eq!(style.justify_content, ui::JustifyContent::SpaceBetween);
+        parse_property_value("place-content", "flex-end").modify_style(&CssContext::default(), &mut style);
+        assert_eq!(style.align_content, ui::AlignContent::FlexEnd);
+        assert_eq!(style.justify_content, ui::JustifyContent::FlexEnd);
+    }
+
+    #[test]
+    fn test_place_items_and_self() {
+        // The `justify-` half is ignored, as Bevy doesn't support it
+        parse_all_property_values(
+            "place-items",
+            BevyPropertyDeclaration::AlignItems,
+            vec![
+                ("center", ui::AlignItems::Center),
+                ("flex-end stretch", ui::AlignItems::FlexEnd),
+                ("center legacy left", ui::AlignItems::Center),
+            ]
+        );
+        parse_all_property_values(
+            "place-self",
+            BevyPropertyDeclaration::AlignSelf,
+            vec![
+                ("auto", ui::AlignSelf::Auto),
+                ("baseline center", ui::AlignSelf::Baseline),
+                ("flex-start safe right", ui::AlignSelf::FlexStart),
+            ]
+        );
+    }
+
     // Margins //
 
     #[test]
     fn test_margin() {
         parse_all_property_values(
             "margin",
-            BevyPropertyDeclaration::Margin,
+            |value| BevyPropertyDeclaration::Margin(MaybeInitial::NotInitial(value)),
             sided_value_vec()
         );
     }
@@ -667,7 +1667,7 @@ mod tests {
     fn test_margin_top() {
         parse_all_property_values(
             "margin-top",
-            BevyPropertyDeclaration::MarginTop,
+            |value| BevyPropertyDeclaration::MarginTop(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -676,7 +1676,7 @@ mod tests {
     fn test_margin_right() {
         parse_all_property_values(
             "margin-right",
-            BevyPropertyDeclaration::MarginRight,
+            |value| BevyPropertyDeclaration::MarginRight(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -685,7 +1685,7 @@ mod tests {
     fn test_margin_bottom() {
         parse_all_property_values(
             "margin-bottom",
-            BevyPropertyDeclaration::MarginBottom,
+            |value| BevyPropertyDeclaration::MarginBottom(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -694,18 +1694,56 @@ mod tests {
     fn test_margin_left() {
         parse_all_property_values(
             "margin-left",
-            BevyPropertyDeclaration::MarginLeft,
+            |value| BevyPropertyDeclaration::MarginLeft(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
 
+    #[test]
+    fn test_sided_longhands_modify_style() {
+        let context = CssContext::default();
+        let value = ui::Val::Px(5.0);
+        let cases: [(&str, fn(&ui::Style) -> ui::Val); 12] = [
+            ("margin-top", |style| style.margin.top),
+            ("margin-right", |style| style.margin.right),
+            ("margin-bottom", |style| style.margin.bottom),
+            ("margin-left", |style| style.margin.left),
+            ("padding-top", |style| style.padding.top),
+            ("padding-right", |style| style.padding.right),
+            ("padding-bottom", |style| style.padding.bottom),
+            ("padding-left", |style| style.padding.left),
+            ("border-width-top", |style| style.border.top),
+            ("border-width-right", |style| style.border.right),
+            ("border-width-bottom", |style| style.border.bottom),
+            ("border-width-left", |style| style.border.left),
+        ];
+        for (property, field) in cases {
+            let mut style = ui::Style::default();
+            parse_property_value(property, "5px").modify_style(&context, &mut style);
+            assert_eq!(field(&style), value, "{}", property);
+            // Nothing outside the margin, padding & border should change (e.g. the position)
+            let mut expected = style.clone();
+            expected.margin = ui::Style::default().margin;
+            expected.padding = ui::Style::default().padding;
+            expected.border = ui::Style::default().border;
+            assert_eq!(expected, ui::Style::default(), "{}", property);
+        }
+
+        assert_eq!(
+            parse_property_value("margin-top", "5px"),
+            BevyPropertyDeclaration::MarginTop(MaybeInitial::NotInitial(LengthPercentageOrAuto::NotAuto(
+                LengthPercentage::Length(NoCalcLength::Absolute(AbsoluteLength::Px(5.0)))
+            )))
+        );
+    }
+
     // Padding //
 
     #[test]
     fn test_padding() {
         parse_all_property_values(
             "padding",
-            BevyPropertyDeclaration::Padding,
+            |value| BevyPropertyDeclaration::Padding(MaybeInitial::NotInitial(value)),
             sided_value_vec()
         );
     }
@@ -714,7 +1752,7 @@ mod tests {
     fn test_padding_top() {
         parse_all_property_values(
             "padding-top",
-            BevyPropertyDeclaration::PaddingTop,
+            |value| BevyPropertyDeclaration::PaddingTop(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -723,7 +1761,7 @@ mod tests {
     fn test_padding_right() {
         parse_all_property_values(
             "padding-right",
-            BevyPropertyDeclaration::PaddingRight,
+            |value| BevyPropertyDeclaration::PaddingRight(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -732,7 +1770,7 @@ mod tests {
     fn test_padding_bottom() {
         parse_all_property_values(
             "padding-bottom",
-            BevyPropertyDeclaration::PaddingBottom,
+            |value| BevyPropertyDeclaration::PaddingBottom(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -741,7 +1779,7 @@ mod tests {
     fn test_padding_left() {
         parse_all_property_values(
             "padding-left",
-            BevyPropertyDeclaration::PaddingLeft,
+            |value| BevyPropertyDeclaration::PaddingLeft(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -752,7 +1790,7 @@ mod tests {
     fn test_border_width() {
         parse_all_property_values(
             "border-width",
-            BevyPropertyDeclaration::BorderWidth,
+            |value| BevyPropertyDeclaration::BorderWidth(MaybeInitial::NotInitial(value)),
             sided_value_vec()
         );
     }
@@ -761,7 +1799,7 @@ mod tests {
     fn test_border_width_top() {
         parse_all_property_values(
             "border-width-top",
-            BevyPropertyDeclaration::BorderWidthTop,
+            |value| BevyPropertyDeclaration::BorderWidthTop(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -770,7 +1808,7 @@ mod tests {
     fn test_border_width_right() {
         parse_all_property_values(
             "border-width-right",
-            BevyPropertyDeclaration::BorderWidthRight,
+            |value| BevyPropertyDeclaration::BorderWidthRight(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -779,7 +1817,7 @@ mod tests {
     fn test_border_width_bottom() {
         parse_all_property_values(
             "border-width-bottom",
-            BevyPropertyDeclaration::BorderWidthBottom,
+            |value| BevyPropertyDeclaration::BorderWidthBottom(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
@@ -788,31 +1826,677 @@ mod tests {
     fn test_border_width_left() {
         parse_all_property_values(
             "border-width-left",
-            BevyPropertyDeclaration::BorderWidthLeft,
+            |value| BevyPropertyDeclaration::BorderWidthLeft(MaybeInitial::NotInitial(value)),
             auto_length_percentage_vec()
         );
     }
 
+    // Gap //
+
+    #[test]
+    fn test_gap() {
+        let five_pc = NonNegative(LengthPercentage::Percentage(Percentage::new(0.05)));
+        let ten_px = NonNegative(LengthPercentage::from(10.0));
+        parse_all_property_values(
+            "gap",
+            BevyPropertyDeclaration::Gap,
+            vec![
+                ("5%", PairedValue { first: five_pc, second: five_pc }),
+                ("5% 10px", PairedValue { first: five_pc, second: ten_px }),
+            ]
+        );
+        parse_all_property_values("row-gap", BevyPropertyDeclaration::RowGap, vec![("5%", five_pc)]);
+        parse_all_property_values("column-gap", BevyPropertyDeclaration::ColumnGap, vec![("10px", ten_px)]);
+    }
+
+    #[test]
+    fn test_gap_percentage() {
+        // Gap percentages are not resolved against the container, but passed through to Bevy
+        let context = CssContext::default();
+        if let BevyPropertyDeclaration::Gap(gap) = parse_property_value("gap", "5%") {
+            let row_gap: ui::Val = gap.first.contextual_into(&context);
+            assert_eq!(row_gap, ui::Val::Percent(5.0));
+        } else { unreachable!() }
+    }
+
+    #[test]
+    fn test_gap_both_axes() {
+        // Bevy 0.8 `ui::Style` has no gap fields, so the (row, column) `ui::Val`s are checked instead
+        let context = CssContext::default();
+        let gaps = |css| match parse_property_value("gap", css) {
+            BevyPropertyDeclaration::Gap(gap) => {
+                let (row_gap, column_gap): (ui::Val, ui::Val) =
+                    (gap.first.contextual_into(&context), gap.second.contextual_into(&context));
+                (row_gap, column_gap)
+            },
+            _ => unreachable!(),
+        };
+        // One value is used for both the row & column gaps
+        assert_eq!(gaps("10px"), (ui::Val::Px(10.0), ui::Val::Px(10.0)));
+        assert_eq!(gaps("10px 20px"), (ui::Val::Px(10.0), ui::Val::Px(20.0)));
+        assert_eq!(gaps("10px 5%"), (ui::Val::Px(10.0), ui::Val::Percent(5.0)));
+    }
+
+    #[test]
+    fn test_gap_unsupported() {
+        let declaration = parse_property_value("gap", "5%");
+        assert!(declaration.is_unsupported());
+        let mut style = ui::Style::default();
+        declaration.modify_style(&CssContext::default(), &mut style);
+        assert_eq!(style, ui::Style::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gap_negative() {
+        parse_property_value("gap", "-5px");
+    }
+
+    // Multi-column //
+
+    #[test]
+    fn test_columns() {
+        parse_all_property_values(
+            "column-count",
+            BevyPropertyDeclaration::ColumnCount,
+            vec![("auto", MaybeAuto::Auto), ("3", MaybeAuto::NotAuto(Integer(3)))]
+        );
+        parse_all_property_values(
+            "column-width",
+            BevyPropertyDeclaration::ColumnWidth,
+            vec![("auto", MaybeAuto::Auto), ("10px", MaybeAuto::NotAuto(NonNegative(Length::from(10.0))))]
+        );
+        assert!(parse_property_value("column-count", "2").is_unsupported());
+        assert!(parse_property_value("column-width", "10em").is_unsupported());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_column_count_zero() {
+        parse_property_value("column-count", "0");
+    }
+
+    // Initial //
+
+    #[test]
+    fn test_keyword_initial() {
+        let style = ui::Style::default();
+        let declarations = [
+            ("display", BevyPropertyDeclaration::Display(style.display)),
+            ("direction", BevyPropertyDeclaration::Direction(style.direction)),
+            ("overflow", BevyPropertyDeclaration::Overflow(style.overflow)),
+            ("position", BevyPropertyDeclaration::Position(style.position_type)),
+            ("flex-direction", BevyPropertyDeclaration::FlexDirection(ui::FlexDirection::Row)),
+            ("flex-wrap", BevyPropertyDeclaration::FlexWrap(style.flex_wrap)),
+            ("align-items", BevyPropertyDeclaration::AlignItems(style.align_items)),
+            ("align-self", BevyPropertyDeclaration::AlignSelf(style.align_self)),
+            ("align-content", BevyPropertyDeclaration::AlignContent(style.align_content)),
+            ("justify-content", BevyPropertyDeclaration::JustifyContent(style.justify_content)),
+            ("visibility", BevyPropertyDeclaration::Visibility(values::Visibility::Visible)),
+        ];
+        for (property, declaration) in declarations {
+            assert_eq!(parse_property_value(property, "initial"), declaration);
+            assert_eq!(parse_property_value(property, "INITIAL"), declaration);
+        }
+    }
+
+    #[test]
+    fn test_keyword_initial_resets() {
+        let context = CssContext::default();
+        let mut style = ui::Style::default();
+        parse_property_value("flex-direction", "column").modify_style(&context, &mut style);
+        assert_eq!(style.flex_direction, ui::FlexDirection::Column);
+        parse_property_value("flex-direction", "initial").modify_style(&context, &mut style);
+        assert_eq!(style.flex_direction, ui::FlexDirection::Row);
+    }
+
+    #[test]
+    fn test_initial_value_matches_default() {
+        let context = CssContext::default();
+        let mut style = ui::Style::default();
+        let declarations = [
+            parse_property_value("display", "none"),
+            parse_property_value("width", "10px"),
+            parse_property_value("max-height", "50%"),
+            parse_property_value("flex", "2 3 10px"),
+            parse_property_value("aspect-ratio", "16 / 9"),
+            parse_property_value("align-self", "center"),
+        ];
+        for declaration in declarations.iter() {
+            declaration.modify_style(&context, &mut style);
+        }
+        assert_ne!(style, ui::Style::default());
+        for declaration in declarations.iter() {
+            declaration.initial_value().modify_style(&context, &mut style);
+        }
+        assert_eq!(style, ui::Style::default());
+    }
+
+    #[test]
+    fn test_initial_value_is_bevy_default() {
+        let context = CssContext::default();
+        for (property, value) in [
+            ("top", "5px"), ("inset", "1px 2px"), ("margin", "auto"), ("padding-left", "5px"),
+            ("border-width", "1px"), ("display", "none"), ("flex", "2 0 10px"),
+        ] {
+            let declaration = parse_property_value(property, value);
+            let mut style = ui::Style::default();
+            declaration.modify_style(&context, &mut style);
+            declaration.initial_value().modify_style(&context, &mut style);
+            assert_eq!(style, ui::Style::default(), "{}", property);
+        }
+        // Only `initial` can reset the position to `ui::Val::Undefined`
+        assert_eq!(parse_property_value("top", "initial"), BevyPropertyDeclaration::Top(MaybeInitial::Initial));
+        let mut style = ui::Style::default();
+        parse_property_value("width", "5px").modify_style(&context, &mut style);
+        parse_property_value("all", "initial").modify_style(&context, &mut style);
+        assert_eq!(style, ui::Style::default());
+    }
+
+    #[test]
+    fn test_initial_value_keeps_property() {
+        assert_eq!(
+            parse_property_value("display", "none").initial_value(),
+            parse_property_value("display", "initial")
+        );
+        assert_eq!(
+            parse_property_value("margin-top", "10px").initial_value(),
+            parse_property_value("margin-top", "initial")
+        );
+        assert_eq!(parse_property_value("opacity", "0.5").initial_value(), BevyPropertyDeclaration::Opacity(1.0));
+        assert_eq!(
+            parse_property_value("all", "unset").initial_value(),
+            BevyPropertyDeclaration::All(CssWideKeyword::Initial)
+        );
+
+        let mut ui_color = ui::UiColor(Color::RED);
+        parse_property_value("background-color", "red").initial_value().modify_color(&mut ui_color);
+        assert_eq!(ui_color.0, ui::UiColor::default().0);
+    }
+
+    // Visibility //
+
+    #[test]
+    fn test_visibility() {
+        parse_all_property_values(
+            "visibility",
+            BevyPropertyDeclaration::Visibility,
+            vec![
+                ("visible", values::Visibility::Visible),
+                ("hidden", values::Visibility::Hidden),
+                ("collapse", values::Visibility::Collapse),
+                ("HIDDEN", values::Visibility::Hidden),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_visibility_bad_keyword() {
+        parse_property_value("visibility", "none");
+    }
+
+    #[test]
+    fn test_modify_visibility() {
+        let mut visibility = Visibility::default();
+        parse_property_value("visibility", "hidden").modify_visibility(&mut visibility);
+        assert!(!visibility.is_visible);
+        parse_property_value("width", "10px").modify_visibility(&mut visibility);
+        assert!(!visibility.is_visible);
+        parse_property_value("visibility", "visible").modify_visibility(&mut visibility);
+        assert!(visibility.is_visible);
+        parse_property_value("visibility", "collapse").modify_visibility(&mut visibility);
+        assert!(!visibility.is_visible);
+        parse_property_value("all", "initial").modify_visibility(&mut visibility);
+        assert!(visibility.is_visible);
+    }
+
+    #[test]
+    fn test_display_none_and_visibility_hidden() {
+        // `display: none` only changes the layout (`Style`), and `visibility: hidden` only the paint
+        // (`Visibility`), so neither undoes the other
+        let context = CssContext::default();
+        let display_none = parse_property_value("display", "none");
+        let visibility_hidden = parse_property_value("visibility", "hidden");
+        assert!(display_none.affects_style() && !display_none.affects_visibility());
+        assert!(visibility_hidden.affects_visibility() && !visibility_hidden.affects_style());
+
+        let mut style = ui::Style::default();
+        let mut visibility = Visibility::default();
+        for property in [&display_none, &visibility_hidden] {
+            property.modify_style(&context, &mut style);
+            property.modify_visibility(&mut visibility);
+        }
+        assert_eq!(style.display, ui::Display::None);
+        assert!(!visibility.is_visible);
+
+        parse_property_value("display", "flex").modify_style(&context, &mut style);
+        assert_eq!(style.display, ui::Display::Flex);
+        assert!(!visibility.is_visible);
+    }
+
+    // Font //
+
+    #[test]
+    fn test_font_size() {
+        parse_all_property_values(
+            "font-size",
+            BevyPropertyDeclaration::FontSize,
+            vec![
+                ("20px", NonNegative(Length::from(20.0))),
+                ("1.5em", NonNegative(Length::from(FontRelativeLength::Em(1.5)))),
+                ("0", NonNegative(Length::zero())),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_font_size_negative() {
+        parse_property_value("font-size", "-1px");
+    }
+
+    #[test]
+    fn test_font_size_text() {
+        let context = CssContext { font_size: 10.0, ..Default::default() };
+        let mut text = Text::from_sections([
+            TextSection::new("a", TextStyle::default()),
+            TextSection::new("b", TextStyle::default()),
+        ]);
+        parse_property_value("font-size", "20px").modify_text(&context, &mut text);
+        assert!(text.sections.iter().all(|section| section.style.font_size == 20.0));
+        // `em` is relative to the context's font size
+        parse_property_value("font-size", "2em").modify_text(&context, &mut text);
+        assert!(text.sections.iter().all(|section| section.style.font_size == 20.0));
+        parse_property_value("font-size", "3em").modify_text(&context, &mut text);
+        assert!(text.sections.iter().all(|section| section.style.font_size == 30.0));
+    }
+
+    #[test]
+    fn test_font_size_context() {
+        let mut context = CssContext { font_size: 10.0, root_font_size: 8.0, ..Default::default() };
+        parse_property_value("font-size", "2em").modify_context(&mut context);
+        assert_eq!(context.font_size, 20.0);
+        parse_property_value("font-size", "2em").modify_context(&mut context);
+        assert_eq!(context.font_size, 40.0);
+        parse_property_value("font-size", "2rem").modify_context(&mut context);
+        assert_eq!(context.font_size, 16.0);
+        // Other properties don't change the context
+        parse_property_value("width", "10px").modify_context(&mut context);
+        assert_eq!(context.font_size, 16.0);
+    }
+
+    #[test]
+    fn test_line_height() {
+        parse_all_property_values(
+            "line-height",
+            BevyPropertyDeclaration::LineHeight,
+            vec![
+                ("normal", LineHeight::Normal),
+                ("1.5", LineHeight::Number(NonNegative(Number(1.5)))),
+                ("20px", LineHeight::LengthPercentage(NonNegative(LengthPercentage::Length(
+                    NoCalcLength::Absolute(AbsoluteLength::Px(20.0))
+                )))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_font_weight_and_family() {
+        parse_all_property_values(
+            "font-weight",
+            BevyPropertyDeclaration::FontWeight,
+            vec![("bold", FontWeight(700.0)), ("600", FontWeight(600.0))]
+        );
+        assert_eq!(
+            parse_property_value("font-family", "\"Fira Sans\", serif"),
+            BevyPropertyDeclaration::FontFamily(FontFamily(vec!["Fira Sans".to_string(), "serif".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_line_height_context() {
+        let mut context = CssContext { font_size: 20.0, ..Default::default() };
+        let one_lh = Length::parse_str("1lh").unwrap();
+        // `normal` follows the font size
+        assert_eq!(one_lh.to_computed_px(&context), 24.0);
+        parse_property_value("line-height", "1.5").modify_context(&mut context);
+        assert_eq!(one_lh.to_computed_px(&context), 30.0);
+        parse_property_value("line-height", "25px").modify_context(&mut context);
+        assert_eq!(one_lh.to_computed_px(&context), 25.0);
+        parse_property_value("line-height", "normal").modify_context(&mut context);
+        assert_eq!(one_lh.to_computed_px(&context), 24.0);
+        // `rlh` uses the root line height
+        assert_eq!(Length::parse_str("2rlh").unwrap().to_computed_px(&context), 2.0 * 1.2 * context.root_font_size);
+    }
+
+    // Effects //
+
+    #[test]
+    fn test_box_shadow() {
+        assert_eq!(parse_property_value("box-shadow", "none"), BevyPropertyDeclaration::BoxShadow(None));
+        let declaration = parse_property_value("box-shadow", "2px 2px 4px black, inset 1px 1px red");
+        assert!(declaration.is_unsupported());
+        if let BevyPropertyDeclaration::BoxShadow(Some(shadows)) = declaration {
+            assert_eq!(shadows.0.len(), 2);
+            assert_eq!(shadows.0[0].color, Some(ColorOrCurrentColor::Color(Color::BLACK)));
+            assert!(shadows.0[1].inset);
+        } else { unreachable!() }
+        assert_eq!(
+            parse_property_value("box-shadow", "2px 2px red, -2px -2px blue"),
+            BevyPropertyDeclaration::BoxShadow(Some(CommaSeparated(vec![
+                BoxShadow::parse_str("2px 2px red").unwrap(),
+                BoxShadow::parse_str("-2px -2px blue").unwrap(),
+            ])))
+        );
+    }
+
+    #[test]
+    fn test_content() {
+        let declaration = parse_property_value("content", "attr(data-label)");
+        assert!(declaration.is_unsupported());
+        assert_eq!(
+            declaration,
+            BevyPropertyDeclaration::Content(Content::Items(vec![
+                ContentItem::Attr { name: "data-label".to_string(), fallback: None },
+            ]))
+        );
+        assert!(parse_property_value("content", "counter(item) \". \"").is_unsupported());
+    }
+
+    #[test]
+    fn test_opacity() {
+        parse_all_property_values(
+            "opacity",
+            BevyPropertyDeclaration::Opacity,
+            vec![
+                ("0", 0.0),
+                ("0.5", 0.5),
+                ("1", 1.0),
+                // Out of range values are clamped
+                ("1.5", 1.0),
+                ("-0.5", 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_opacity_not_a_number() {
+        parse_property_value("opacity", "50px");
+    }
+
+    #[test]
+    fn test_opacity_scales_alpha() {
+        let mut ui_color = ui::UiColor(Color::rgba(1.0, 0.0, 0.0, 0.5));
+        parse_property_value("opacity", "0.5").modify_color(&mut ui_color);
+        assert_eq!(ui_color.0, Color::rgba(1.0, 0.0, 0.0, 0.25));
+
+        // Declaration order matters, a following color replaces the alpha
+        let mut ui_color = ui::UiColor::default();
+        for (property, value) in [("color", "blue"), ("opacity", "0.5")] {
+            parse_property_value(property, value).modify_color(&mut ui_color);
+        }
+        assert_eq!(ui_color.0, Color::rgba(0.0, 0.0, 1.0, 0.5));
+        parse_property_value("color", "red").modify_color(&mut ui_color);
+        assert_eq!(ui_color.0, Color::RED);
+    }
+
+    // Transform //
+
+    #[test]
+    fn test_transform() {
+        assert_eq!(
+            parse_property_value("transform", "none"),
+            BevyPropertyDeclaration::Transform(TransformList::default())
+        );
+        let context = CssContext::default();
+        let mut transform = Transform::default();
+        parse_property_value("transform", "translate(10px,0)").modify_transform(&context, &mut transform);
+        assert_eq!(transform.translation.x, 10.0);
+        assert_eq!(transform.translation.y, 0.0);
+        parse_property_value("transform", "scale(2)").modify_transform(&context, &mut transform);
+        assert_eq!(transform.translation.x, 0.0);
+        assert_eq!(transform.scale.x, 2.0);
+        parse_property_value("all", "initial").modify_transform(&context, &mut transform);
+        assert_eq!(transform, Transform::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_transform_bad_function() {
+        parse_property_value("transform", "skew(10deg)");
+    }
+
+    // Transition //
+
+    #[test]
+    fn test_transition_list() {
+        let declaration = parse_property_value("transition", "width 0.2s, opacity 0.5s ease-in");
+        let transitions = match declaration {
+            BevyPropertyDeclaration::Transition(TransitionList(ref transitions)) => transitions,
+            _ => panic!("`transition` parsed as {:?}", declaration),
+        };
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].property, TransitionProperty::Property("width".to_string()));
+        assert_eq!(transitions[0].duration, Time(0.2));
+        assert_eq!(transitions[0].timing_function, EasingFunction::EASE);
+        assert_eq!(transitions[1].property, TransitionProperty::Property("opacity".to_string()));
+        assert_eq!(transitions[1].duration, Time(0.5));
+        assert_eq!(transitions[1].timing_function, EasingFunction::EASE_IN);
+        assert!(!declaration.is_unsupported());
+    }
+
+    // Animation //
+
+    #[test]
+    fn test_animation() {
+        let declaration = parse_property_value("animation", "pulse 1s infinite");
+        assert_eq!(
+            declaration,
+            BevyPropertyDeclaration::Animation(Some(AnimationSpec {
+                duration: Time(1.0),
+                iteration_count: IterationCount::Infinite,
+                ..AnimationSpec::new("pulse")
+            }))
+        );
+        assert_eq!(declaration.to_css_string(), "animation: pulse 1s ease infinite");
+        assert_eq!(parse_property_value("animation", "None"), BevyPropertyDeclaration::Animation(None));
+        assert!(!declaration.affects_style() && !declaration.is_unsupported());
+    }
+
     // Color //
 
     #[test]
     fn test_color() {
         parse_all_property_values(
             "color",
-            BevyPropertyDeclaration::Color,
+            |color| BevyPropertyDeclaration::Color(ColorOrCurrentColor::Color(color)),
             vec![
                 ("none", Color::NONE),
                 ("transparent", Color::NONE),
                 ("rgb(10, 20, 30)", Color::rgb_u8(10, 20, 30)),
                 ("rgba(10, 20, 30, 0.5)", Color::rgba_u8(10, 20, 30, 128)),
+                ("rgb(10 20 30 / 0.5)", Color::rgba_u8(10, 20, 30, 128)),
                 // Test against rgb_u8, as all colors defined with CSS will bevy::Color::rgba
                 ("hsl(180, 60%, 70%)", Color::rgb_u8(133, 224, 224)),
                 ("hsla(180, 60%, 70%, 0.5)", Color::rgba_u8(133, 224, 224, 128)),
+                ("hsl(180 60% 70% / 50%)", Color::rgba_u8(133, 224, 224, 128)),
+                ("hwb(0 0% 0%)", Color::RED),
+                ("hwb(180 20% 40% / 0.5)", Color::rgba_u8(51, 153, 153, 128)),
                 ("#ba55d3", Color::rgb_u8(186, 85, 211)),
                 ("#abc", Color::rgb_u8(170, 187, 204)),
+                ("#11223344", Color::rgba_u8(0x11, 0x22, 0x33, 0x44)),
+                ("#1234", Color::rgba_u8(0x11, 0x22, 0x33, 0x44)),
                 ("red", Color::RED),
                 ("lightsalmon", Color::rgb_u8(255, 160, 122)),
             ]
         );
     }
+
+    #[test]
+    fn test_background_color() {
+        parse_all_property_values(
+            "background-color",
+            BevyPropertyDeclaration::BackgroundColor,
+            vec![
+                ("red", ColorOrCurrentColor::Color(Color::RED)),
+                ("rgb(10, 20, 30)", ColorOrCurrentColor::Color(Color::rgb_u8(10, 20, 30))),
+                ("currentcolor", ColorOrCurrentColor::CurrentColor),
+            ]
+        );
+        let mut ui_color = ui::UiColor::default();
+        parse_property_value("background-color", "blue").modify_color(&mut ui_color);
+        assert_eq!(ui_color.0, Color::BLUE);
+    }
+
+    #[test]
+    fn test_text_color() {
+        parse_all_property_values(
+            "text-color",
+            BevyPropertyDeclaration::TextColor,
+            vec![
+                ("red", ColorOrCurrentColor::Color(Color::RED)),
+                ("currentcolor", ColorOrCurrentColor::CurrentColor),
+            ]
+        );
+        let mut text = Text::from_sections([
+            TextSection::new("a", TextStyle::default()),
+            TextSection::new("b", TextStyle { color: Color::BLUE, ..Default::default() }),
+        ]);
+        parse_property_value("text-color", "red").modify_text(&CssContext::default(), &mut text);
+        assert!(text.sections.iter().all(|section| section.style.color == Color::RED));
+        parse_property_value("color", "green").modify_text(&CssContext::default(), &mut text);
+        assert!(text.sections.iter().all(|section| section.style.color == Color::GREEN));
+        parse_property_value("all", "initial").modify_text(&CssContext::default(), &mut text);
+        assert!(text.sections.iter().all(|section| section.style.color == TextStyle::default().color));
+    }
+
+    #[test]
+    fn test_text_color_ignored() {
+        // `text-color` only applies to `Text`, and `background-color` never does
+        let mut ui_color = ui::UiColor::default();
+        parse_property_value("text-color", "red").modify_color(&mut ui_color);
+        assert_eq!(ui_color.0, ui::UiColor::default().0);
+        let mut text = Text::from_section("a", TextStyle::default());
+        parse_property_value("background-color", "red").modify_text(&CssContext::default(), &mut text);
+        assert_eq!(text.sections[0].style.color, TextStyle::default().color);
+    }
+
+    #[test]
+    fn test_current_color() {
+        parse_all_property_values(
+            "color",
+            BevyPropertyDeclaration::Color,
+            vec![
+                ("currentcolor", ColorOrCurrentColor::CurrentColor),
+                ("currentColor", ColorOrCurrentColor::CurrentColor),
+            ]
+        );
+    }
+
+    // All //
+
+    #[test]
+    fn test_all() {
+        parse_all_property_values(
+            "all",
+            BevyPropertyDeclaration::All,
+            vec![
+                ("initial", CssWideKeyword::Initial),
+                ("inherit", CssWideKeyword::Inherit),
+                ("unset", CssWideKeyword::Unset),
+            ]
+        );
+    }
+
+    // Affected Components //
+
+    #[test]
+    fn test_affects() {
+        let color = parse_property_value("color", "red");
+        assert!(color.affects_color());
+        assert!(color.affects_text());
+        assert!(!color.affects_style());
+        assert!(!color.affects_visibility());
+        assert!(!color.affects_transform());
+
+        let width = parse_property_value("width", "10px");
+        assert!(width.affects_style());
+        assert!(!width.affects_color());
+        assert!(!width.affects_text());
+        assert!(!width.affects_visibility());
+        assert!(!width.affects_transform());
+
+        // Unsupported declarations don't change anything
+        let gap = parse_property_value("gap", "10px");
+        assert!(!gap.affects_style());
+
+        let all = parse_property_value("all", "initial");
+        assert!(all.affects_style() && all.affects_color() && all.affects_text());
+        assert!(all.affects_visibility() && all.affects_transform());
+        // Reset like `initial`, before the parent's values are copied
+        let all = parse_property_value("all", "inherit");
+        assert!(all.affects_style() && all.affects_color() && all.affects_text());
+        assert!(all.inherits_from_parent());
+        assert!(!parse_property_value("all", "initial").inherits_from_parent());
+    }
+
+    #[test]
+    fn test_color_bad_hex_length() {
+        for hex in ["#12345", "#1234567"] {
+            let mut parser_input = ParserInput::new(hex);
+            let mut input = Parser::new(&mut parser_input);
+            let error = BevyPropertyDeclaration::parse_input("color".into(), &mut input)
+                .unwrap_err();
+            assert!(matches!(
+                error.kind,
+                ParseErrorKind::Custom(BevyCssParsingErrorKind::InvalidValue(..))
+            ))
+        }
+    }
+
+    #[test]
+    fn test_to_css() {
+        assert_eq!(parse_property_value("margin", "10px 5px").to_string(), "margin: 10px 5px;");
+        assert_eq!(parse_property_value("margin", "10px 5px 10px 5px").to_css_string(), "margin: 10px 5px");
+        assert_eq!(parse_property_value("padding", "1em 2% 3px").to_css_string(), "padding: 1em 2% 3px");
+        assert_eq!(parse_property_value("width", "AUTO").to_css_string(), "width: auto");
+        assert_eq!(parse_property_value("width", "max-content").to_css_string(), "width: max-content");
+        assert_eq!(parse_property_value("overflow-x", "hidden").to_css_string(), "overflow: hidden");
+        assert_eq!(parse_property_value("place-items", "center").to_css_string(), "align-items: center");
+        assert_eq!(parse_property_value("flex", "2").to_css_string(), "flex: 2 1 0px");
+        assert_eq!(parse_property_value("color", "red").to_css_string(), "color: #ff0000");
+        assert_eq!(parse_property_value("background-color", "rgba(0, 0, 255, 0.5)").to_css_string(), "background-color: #0000ff80");
+        assert_eq!(parse_property_value("box-shadow", "none").to_css_string(), "box-shadow: none");
+        assert_eq!(
+            parse_property_value("transition", "width 0.2s linear, opacity 1s ease-in 0.5s").to_css_string(),
+            "transition: width 0.2s linear, opacity 1s ease-in 0.5s"
+        );
+        assert_eq!(parse_property_value("transform", "scale(2) rotate(90deg)").to_css_string(), "transform: scale(2) rotate(90deg)");
+        assert_eq!(parse_property_value("font-family", "Fira Sans, serif").to_css_string(), "font-family: \"Fira Sans\", serif");
+    }
+
+    #[test]
+    fn test_to_css_round_trip() {
+        for (property, value) in [
+            ("inset", "1px auto"),
+            ("aspect-ratio", "16 / 9"),
+            ("gap", "10px 5%"),
+            ("flex-flow", "column wrap"),
+            ("place-content", "center space-between"),
+            ("line-height", "1.5"),
+            ("box-shadow", "inset 2px 2px 4px -1px currentcolor, 1px 1px #00ff00"),
+            ("content", "\"Item \" counter(item) attr(data-label)"),
+            ("transition", "all 1s steps(4, jump-start) -1s"),
+            ("transform", "translate(10px, -2em)"),
+            ("opacity", "0.25"),
+            ("all", "unset"),
+        ] {
+            let declaration = parse_property_value(property, value);
+            let css = declaration.to_css_string();
+            let (reparsed_property, reparsed_value) = css.split_once(": ").unwrap();
+            assert_eq!(parse_property_value(reparsed_property, reparsed_value), declaration, "{}", css);
+        }
+    }
 }
\ No newline at end of file
@@ -1,10 +1,7 @@
-use bevy::{
-    render::color,
-    ui,
-};
+use bevy::ui;
 use cssparser::Parser;
 use crate::{
-    errors::BevyCssParsingError,
+    errors::{notice, BevyCssParsingError, BevyCssParsingErrorKind},
     properties::BevyPropertyDeclaration,
     values::*,
 };
@@ -39,58 +36,197 @@ macro_rules! property_def {
     }
 }
 
+/// For keyword (i.e. enum) values, where `initial` can be used to reset the value to the Bevy default
+macro_rules! keyword_property_def {
+    ( $property:ident, $value_type:ty, $variant:path ) => {
+        property_def!(
+            $property,
+            generic::MaybeInitial<$value_type>,
+            |value: generic::MaybeInitial<$value_type>| initial_or(value, $variant)
+        );
+    }
+}
+
+/// The declaration for a value, or the property's `initial_value()` for `initial`
+fn initial_or<T: Default>(
+    value: generic::MaybeInitial<T>,
+    variant: fn(T) -> BevyPropertyDeclaration,
+) -> BevyPropertyDeclaration {
+    match value {
+        // Any value will do, as it is replaced
+        generic::MaybeInitial::Initial => variant(T::default()).initial_value(),
+        generic::MaybeInitial::NotInitial(value) => variant(value),
+    }
+}
+
 // Display
-property_def!(Display, ui::Display, BevyPropertyDeclaration::Display);
-property_def!(Direction, ui::Direction, BevyPropertyDeclaration::Direction);
-property_def!(Width, LengthPercentageOrAuto, BevyPropertyDeclaration::Width);
-property_def!(Height, LengthPercentageOrAuto, BevyPropertyDeclaration::Height);
-property_def!(MinWidth, LengthPercentageOrAuto, BevyPropertyDeclaration::MinWidth);
-property_def!(MinHeight, LengthPercentageOrAuto, BevyPropertyDeclaration::MinHeight);
-property_def!(MaxWidth, LengthPercentageOrAuto, BevyPropertyDeclaration::MaxWidth);
-property_def!(MaxHeight, LengthPercentageOrAuto, BevyPropertyDeclaration::MaxHeight);
-property_def!(Overflow, ui::Overflow, BevyPropertyDeclaration::Overflow);
+keyword_property_def!(Display, ui::Display, BevyPropertyDeclaration::Display);
+keyword_property_def!(Direction, ui::Direction, BevyPropertyDeclaration::Direction);
+property_def!(Width, Size, BevyPropertyDeclaration::Width);
+property_def!(Height, Size, BevyPropertyDeclaration::Height);
+property_def!(MinWidth, Size, BevyPropertyDeclaration::MinWidth);
+property_def!(MinHeight, Size, BevyPropertyDeclaration::MinHeight);
+property_def!(MaxWidth, Size, BevyPropertyDeclaration::MaxWidth);
+property_def!(MaxHeight, Size, BevyPropertyDeclaration::MaxHeight);
+// Bevy has one `ui::Overflow` for both axes, so each of these sets it for both, and the last one
+// declared wins (e.g. `overflow-x: hidden; overflow-y: visible;` is `visible`).  Unlike the two
+// values of `overflow`, they are separate declarations, so differing axes can't be combined.
+keyword_property_def!(OverflowX, ui::Overflow, BevyPropertyDeclaration::Overflow);
+keyword_property_def!(OverflowY, ui::Overflow, BevyPropertyDeclaration::Overflow);
+
+/// `overflow` can be given for each axis (x then y), but Bevy only has one `ui::Overflow` for both,
+/// so different values are combined (i.e. if either axis is `hidden`, both are)
+pub struct Overflow;
+impl Property for Overflow {
+    type ValueType = generic::MaybeInitial<PairedValue<ui::Overflow>>;
+    fn value_to_declaration(value: Self::ValueType) -> BevyPropertyDeclaration {
+        let overflow = match value {
+            generic::MaybeInitial::Initial =>
+                return BevyPropertyDeclaration::Overflow(ui::Overflow::default()).initial_value(),
+            generic::MaybeInitial::NotInitial(PairedValue { first, second }) if first == second => first,
+            generic::MaybeInitial::NotInitial(PairedValue { first, second }) => {
+                notice(format!(
+                    "Bevy does not support a different `overflow` for each axis, so `{:?} {:?}` will be `Hidden`",
+                    first, second
+                ));
+                ui::Overflow::Hidden
+            },
+        };
+        BevyPropertyDeclaration::Overflow(overflow)
+    }
+}
 
 // Position
-property_def!(Position, ui::PositionType, BevyPropertyDeclaration::Position);
-property_def!(Top, LengthPercentageOrAuto, BevyPropertyDeclaration::Top);
-property_def!(Right, LengthPercentageOrAuto, BevyPropertyDeclaration::Right);
-property_def!(Bottom, LengthPercentageOrAuto, BevyPropertyDeclaration::Bottom);
-property_def!(Left, LengthPercentageOrAuto, BevyPropertyDeclaration::Left);
+keyword_property_def!(Position, ui::PositionType, BevyPropertyDeclaration::Position);
+property_def!(Top, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::Top);
+property_def!(Right, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::Right);
+property_def!(Bottom, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::Bottom);
+property_def!(Left, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::Left);
+property_def!(Inset, generic::MaybeInitial<SidedValue<LengthPercentageOrAuto>>, BevyPropertyDeclaration::Inset);
 
 // Flex Box
-property_def!(FlexDirection, ui::FlexDirection, BevyPropertyDeclaration::FlexDirection);
-property_def!(FlexWrap, ui::FlexWrap, BevyPropertyDeclaration::FlexWrap);
+keyword_property_def!(FlexDirection, ui::FlexDirection, BevyPropertyDeclaration::FlexDirection);
+keyword_property_def!(FlexWrap, ui::FlexWrap, BevyPropertyDeclaration::FlexWrap);
+property_def!(FlexFlow, flex::FlexFlow, BevyPropertyDeclaration::FlexFlow);
 property_def!(FlexGrow, NonNegativeNumber, BevyPropertyDeclaration::FlexGrow);
 property_def!(FlexShrink, NonNegativeNumber, BevyPropertyDeclaration::FlexShrink);
 property_def!(FlexBasis, LengthPercentageOrAuto, BevyPropertyDeclaration::FlexBasis);
 property_def!(AspectRatio, RatioOrAuto, BevyPropertyDeclaration::AspectRatio);
+property_def!(Flex, flex::Flex, BevyPropertyDeclaration::Flex);
+property_def!(Order, Integer, BevyPropertyDeclaration::Order);
 
 // Alignment
-property_def!(AlignItems, ui::AlignItems, BevyPropertyDeclaration::AlignItems);
-property_def!(AlignSelf, ui::AlignSelf, BevyPropertyDeclaration::AlignSelf);
-property_def!(AlignContent, ui::AlignContent, BevyPropertyDeclaration::AlignContent);
-property_def!(JustifyContent, ui::JustifyContent, BevyPropertyDeclaration::JustifyContent);
+keyword_property_def!(AlignItems, ui::AlignItems, BevyPropertyDeclaration::AlignItems);
+keyword_property_def!(AlignSelf, ui::AlignSelf, BevyPropertyDeclaration::AlignSelf);
+keyword_property_def!(AlignContent, ui::AlignContent, BevyPropertyDeclaration::AlignContent);
+keyword_property_def!(JustifyContent, ui::JustifyContent, BevyPropertyDeclaration::JustifyContent);
+property_def!(PlaceContent, alignment::PlaceContent, BevyPropertyDeclaration::PlaceContent);
+property_def!(
+    PlaceItems,
+    alignment::PlaceItems,
+    |place_items: alignment::PlaceItems| {
+        if place_items.justify.is_some() {
+            notice("`justify-items` is not supported by Bevy, so the second `place-items` value will be ignored".into())
+        }
+        BevyPropertyDeclaration::AlignItems(place_items.align)
+    }
+);
+property_def!(
+    PlaceSelf,
+    alignment::PlaceSelf,
+    |place_self: alignment::PlaceSelf| {
+        if place_self.justify.is_some() {
+            notice("`justify-self` is not supported by Bevy, so the second `place-self` value will be ignored".into())
+        }
+        BevyPropertyDeclaration::AlignSelf(place_self.align)
+    }
+);
 
 // Margin
-property_def!(Margin, SidedValue<LengthPercentageOrAuto>, BevyPropertyDeclaration::Margin);
-property_def!(MarginTop, LengthPercentageOrAuto, BevyPropertyDeclaration::MarginTop);
-property_def!(MarginRight, LengthPercentageOrAuto, BevyPropertyDeclaration::MarginRight);
-property_def!(MarginBottom, LengthPercentageOrAuto, BevyPropertyDeclaration::MarginBottom);
-property_def!(MarginLeft, LengthPercentageOrAuto, BevyPropertyDeclaration::MarginLeft);
+property_def!(Margin, generic::MaybeInitial<SidedValue<LengthPercentageOrAuto>>, BevyPropertyDeclaration::Margin);
+property_def!(MarginTop, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::MarginTop);
+property_def!(MarginRight, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::MarginRight);
+property_def!(MarginBottom, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::MarginBottom);
+property_def!(MarginLeft, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::MarginLeft);
 
 // Padding
-property_def!(Padding, SidedValue<LengthPercentageOrAuto>, BevyPropertyDeclaration::Padding);
-property_def!(PaddingTop, LengthPercentageOrAuto, BevyPropertyDeclaration::PaddingTop);
-property_def!(PaddingRight, LengthPercentageOrAuto, BevyPropertyDeclaration::PaddingRight);
-property_def!(PaddingBottom, LengthPercentageOrAuto, BevyPropertyDeclaration::PaddingBottom);
-property_def!(PaddingLeft, LengthPercentageOrAuto, BevyPropertyDeclaration::PaddingLeft);
+property_def!(Padding, generic::MaybeInitial<SidedValue<LengthPercentageOrAuto>>, BevyPropertyDeclaration::Padding);
+property_def!(PaddingTop, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::PaddingTop);
+property_def!(PaddingRight, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::PaddingRight);
+property_def!(PaddingBottom, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::PaddingBottom);
+property_def!(PaddingLeft, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::PaddingLeft);
 
 // Borders
-property_def!(BorderWidth, SidedValue<LengthPercentageOrAuto>, BevyPropertyDeclaration::BorderWidth);
-property_def!(BorderWidthTop, LengthPercentageOrAuto, BevyPropertyDeclaration::BorderWidthTop);
-property_def!(BorderWidthRight, LengthPercentageOrAuto, BevyPropertyDeclaration::BorderWidthRight);
-property_def!(BorderWidthBottom, LengthPercentageOrAuto, BevyPropertyDeclaration::BorderWidthBottom);
-property_def!(BorderWidthLeft, LengthPercentageOrAuto, BevyPropertyDeclaration::BorderWidthLeft);
+property_def!(BorderWidth, generic::MaybeInitial<SidedValue<LengthPercentageOrAuto>>, BevyPropertyDeclaration::BorderWidth);
+property_def!(BorderWidthTop, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::BorderWidthTop);
+property_def!(BorderWidthRight, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::BorderWidthRight);
+property_def!(BorderWidthBottom, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::BorderWidthBottom);
+property_def!(BorderWidthLeft, generic::MaybeInitial<LengthPercentageOrAuto>, BevyPropertyDeclaration::BorderWidthLeft);
+
+// Gap
+property_def!(Gap, PairedValue<NonNegativeLengthPercentage>, BevyPropertyDeclaration::Gap);
+property_def!(RowGap, NonNegativeLengthPercentage, BevyPropertyDeclaration::RowGap);
+property_def!(ColumnGap, NonNegativeLengthPercentage, BevyPropertyDeclaration::ColumnGap);
+
+// Multi-column
+/// `column-count` has to be at least `1`
+pub struct ColumnCount;
+impl Property for ColumnCount {
+    type ValueType = generic::MaybeAuto<Integer>;
+
+    fn value_to_declaration(value: Self::ValueType) -> BevyPropertyDeclaration {
+        BevyPropertyDeclaration::ColumnCount(value)
+    }
+
+    fn parse_css<'i, 't>(
+        input: &mut Parser<'i, 't>
+    ) -> Result<Self::ValueType, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        match <Self::ValueType as Parse>::parse(input)? {
+            generic::MaybeAuto::NotAuto(Integer(count)) if count < 1 => Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidValue("column-count must be at least 1".into(), None)
+            )),
+            count => Ok(count),
+        }
+    }
+}
+property_def!(ColumnWidth, generic::MaybeAuto<NonNegativeLength>, BevyPropertyDeclaration::ColumnWidth);
+
+// Visibility
+property_def!(
+    Visibility,
+    generic::MaybeInitial<visibility::Visibility>,
+    |visibility: generic::MaybeInitial<visibility::Visibility>| initial_or(visibility, BevyPropertyDeclaration::Visibility)
+);
+
+// Font
+property_def!(FontSize, NonNegativeLength, BevyPropertyDeclaration::FontSize);
+property_def!(LineHeight, line_height::LineHeight, BevyPropertyDeclaration::LineHeight);
+property_def!(FontWeight, font::FontWeight, BevyPropertyDeclaration::FontWeight);
+property_def!(FontFamily, font::FontFamily, BevyPropertyDeclaration::FontFamily);
+
+// Effects
+property_def!(
+    BoxShadow,
+    Option<generic::CommaSeparated<box_shadow::BoxShadow>>,
+    BevyPropertyDeclaration::BoxShadow
+);
+property_def!(Content, content::Content, BevyPropertyDeclaration::Content);
+property_def!(Opacity, AlphaValue, |opacity: AlphaValue| BevyPropertyDeclaration::Opacity(opacity.0));
+
+// Transform
+property_def!(Transform, TransformList, BevyPropertyDeclaration::Transform);
+
+// Transition
+property_def!(Transition, TransitionList, BevyPropertyDeclaration::Transition);
+
+// Animation
+property_def!(Animation, Option<AnimationSpec>, BevyPropertyDeclaration::Animation);
 
 // Color
-property_def!(Color, color::Color, BevyPropertyDeclaration::Color);
\ No newline at end of file
+property_def!(Color, ColorOrCurrentColor, BevyPropertyDeclaration::Color);
+property_def!(BackgroundColor, ColorOrCurrentColor, BevyPropertyDeclaration::BackgroundColor);
+property_def!(TextColor, ColorOrCurrentColor, BevyPropertyDeclaration::TextColor);
+
+// All
+property_def!(All, CssWideKeyword, BevyPropertyDeclaration::All);
\ No newline at end of file
@@ -0,0 +1,114 @@
+use std::fmt;
+use bevy::{
+    prelude::warn,
+    ui,
+};
+
+/// A min size that is larger than the max size on the same axis of a `ui::Style`, e.g. from
+/// `min-width: 200px; max-width: 100px;`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SizeConflict {
+    /// `"width"` or `"height"`
+    pub axis: &'static str,
+    pub min: ui::Val,
+    pub max: ui::Val,
+}
+
+impl fmt::Display for SizeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,
+            "`min-{axis}` ({}) is larger than `max-{axis}` ({}), so `max-{axis}` will be ignored",
+            ValDisplay(self.min), ValDisplay(self.max), axis = self.axis
+        )
+    }
+}
+
+/// Writes a `ui::Val` the way it would be written in css
+struct ValDisplay(ui::Val);
+
+impl fmt::Display for ValDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ui::Val::Undefined => f.write_str("undefined"),
+            ui::Val::Auto => f.write_str("auto"),
+            ui::Val::Px(px) => write!(f, "{}px", px),
+            ui::Val::Percent(pc) => write!(f, "{}%", pc),
+        }
+    }
+}
+
+/// As in css, a min size wins over a smaller max size, so the max size is clamped to the min size.
+/// Only sizes with the same unit (i.e. both `Px` or both `Percent`) can be compared.  Any
+/// conflicts are returned, so they can be reported.
+pub fn clamp_min_max_sizes(style: &mut ui::Style) -> Vec<SizeConflict> {
+    let mut conflicts = Vec::new();
+    let axes = [
+        ("width", style.min_size.width, &mut style.max_size.width),
+        ("height", style.min_size.height, &mut style.max_size.height),
+    ];
+    for (axis, min, max) in axes {
+        let is_conflict = match (min, *max) {
+            (ui::Val::Px(min), ui::Val::Px(max)) => min > max,
+            (ui::Val::Percent(min), ui::Val::Percent(max)) => min > max,
+            _ => false,
+        };
+        if is_conflict {
+            conflicts.push(SizeConflict { axis, min, max: *max });
+            *max = min;
+        }
+    }
+    conflicts
+}
+
+/// `clamp_min_max_sizes()`, with a `warn!` for every conflict when `warn_conflicts` is set
+pub(crate) fn clamp_and_warn(style: &mut ui::Style, warn_conflicts: bool) {
+    for conflict in clamp_min_max_sizes(style) {
+        if warn_conflicts {
+            warn!("{}", conflict)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_conflict() {
+        let mut style = ui::Style::default();
+        assert!(clamp_min_max_sizes(&mut style).is_empty());
+
+        style.min_size.width = ui::Val::Px(100.0);
+        style.max_size.width = ui::Val::Px(200.0);
+        style.min_size.height = ui::Val::Percent(50.0);
+        style.max_size.height = ui::Val::Px(10.0);    // Can't be compared
+        let expected = style.clone();
+        assert!(clamp_min_max_sizes(&mut style).is_empty());
+        assert_eq!(style, expected);
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut style = ui::Style::default();
+        style.min_size.width = ui::Val::Px(200.0);
+        style.max_size.width = ui::Val::Px(100.0);
+        style.min_size.height = ui::Val::Percent(80.0);
+        style.max_size.height = ui::Val::Percent(50.0);
+        let conflicts = clamp_min_max_sizes(&mut style);
+        assert_eq!(conflicts, vec![
+            SizeConflict { axis: "width", min: ui::Val::Px(200.0), max: ui::Val::Px(100.0) },
+            SizeConflict { axis: "height", min: ui::Val::Percent(80.0), max: ui::Val::Percent(50.0) },
+        ]);
+        assert_eq!(style.max_size.width, ui::Val::Px(200.0));
+        assert_eq!(style.max_size.height, ui::Val::Percent(80.0));
+    }
+
+    #[test]
+    fn test_message() {
+        let conflict = SizeConflict { axis: "width", min: ui::Val::Px(200.0), max: ui::Val::Px(100.0) };
+        assert_eq!(
+            conflict.to_string(),
+            "`min-width` (200px) is larger than `max-width` (100px), so `max-width` will be ignored"
+        );
+    }
+}
\ No newline at end of file
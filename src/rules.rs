@@ -1,11 +1,13 @@
 use std::sync::Arc;
 use crate::{
+    media_queries::MediaQueryList,
     properties::BevyPropertyDeclaration,
     selectors::BevySelectorList
 };
 
 /// Does not cover all possible top level CSS rules, only the ones that make sense within Bevy
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BevyCssRule {
 
     // @font-face
@@ -14,17 +16,69 @@ pub enum BevyCssRule {
     /// Normal styles (e.g. node { margin: 2px; }
     Style(BevyStyleRule),
 
+    /// Rules that only apply when the media queries match (e.g. `@media (prefers-color-scheme: dark) { ... }`)
+    Media(BevyMediaRule),
+
+    /// Named keyframes for animations (e.g. `@keyframes pulse { from { ... } to { ... } }`)
+    Keyframes(BevyKeyframesRule),
+
+}
+
+impl BevyCssRule {
+    /// The style rules of this rule, including those inside `@media` rules (whether they match or not)
+    pub fn style_rules(&self) -> Vec<&BevyStyleRule> {
+        match self {
+            Self::Style(style_rule) => vec![style_rule],
+            Self::Media(media_rule) => media_rule.rules.iter().flat_map(Self::style_rules).collect(),
+            Self::Keyframes(_) => Vec::new(),
+        }
+    }
 }
 
 /// A rule for one style block.  I.e. one selector group, and the declarations (between the curly
 /// braces - `{ ... }`) for the selector block.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BevyStyleRule {
     /// A list of all the selectors specified in the `.css` document
     pub selectors: BevySelectorList,
 
-    /// A list of all the declarations.  I.e. everything between the `{ /* ... */ }`
+    /// A list of all the (normal) declarations.  I.e. everything between the `{ /* ... */ }`,
+    /// except for `!important` declarations
     // Want to use Rc to avoid cloning of the declarations vec for every selector in the list above
     // Use Arc instead of Rc as bevy systems can run on any/many threads
-    pub declarations: Arc<Vec<BevyPropertyDeclaration>>
+    pub declarations: Arc<Vec<BevyPropertyDeclaration>>,
+
+    /// A list of the `!important` declarations
+    pub important_declarations: Arc<Vec<BevyPropertyDeclaration>>,
+}
+
+/// An `@media` rule, with its media queries and the rules (between the curly braces) that only
+/// apply while they match
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BevyMediaRule {
+    pub queries: MediaQueryList,
+    pub rules: Vec<BevyCssRule>,
+}
+
+/// An `@keyframes` rule, with its name & keyframes
+/// See also: https://drafts.csswg.org/css-animations/#keyframes
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BevyKeyframesRule {
+    pub name: String,
+    /// In order of their offsets.  A keyframe with several offsets (e.g. `0%, 100% { ... }`) is kept
+    /// once for each of them, and where keyframes have the same offset, they stay in source order.
+    pub frames: Vec<BevyKeyframe>,
+}
+
+/// One keyframe of an `@keyframes` rule, e.g. `50% { opacity: 0.5; }`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BevyKeyframe {
+    /// How far through the animation it is, where `from` (`0%`) is `0.0` & `to` (`100%`) is `1.0`
+    pub offset: f32,
+    /// `!important` declarations are ignored in keyframes, so there are only normal ones
+    pub declarations: Arc<Vec<BevyPropertyDeclaration>>,
 }
\ No newline at end of file
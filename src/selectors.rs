@@ -1,22 +1,29 @@
 use std::fmt;
+use bevy::ui::Interaction;
 use smallvec::SmallVec;
 
 use cssparser::{
-    Parser as CssParser,
-    ToCss
+    CowRcStr, ParseError, Parser as CssParser, ParserInput as CssParserInput, SourceLocation,
+    ToCss, Token, match_ignore_ascii_case, _cssparser_internal_to_lowercase
 };
 use selectors::{
     attr::{AttrSelectorOperation, CaseSensitivity, NamespaceConstraint},
     context::{MatchingContext, MatchingMode, QuirksMode},
     matching::{matches_selector, ElementSelectorFlags},
-    parser::{NonTSPseudoClass, PseudoElement, Parser as SelectorParser, Selector, SelectorImpl},
+    parser::{
+        Component, NonTSPseudoClass, PseudoElement, Parser as SelectorParser, Selector, SelectorImpl,
+        SelectorParseErrorKind,
+    },
     SelectorList, Element, OpaqueElement
 };
 
 use crate::{
     css_strings::CssString,
+    css_tag::CssTag,
     errors::{BevyCssParsingError, BevyCssParsingErrorKind},
 };
+#[cfg(feature = "serde")]
+use crate::errors::BevyCssContextualError;
 
 /// A list of selectors that apply to a particular `BevyStyleRule`, as defined in a .css sheet
 #[derive(Clone)]
@@ -27,6 +34,7 @@ pub struct BevySelectorList(pub SmallVec<[BevyCssSelector; 1]>);
 impl BevySelectorList {
     #[inline]
     pub fn parse<'i, 't>(input: &mut CssParser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        Self::check_combinators(input)?;
         let selector_list = SelectorList::parse(
             &BevySelectorParser,
             input
@@ -35,11 +43,131 @@ impl BevySelectorList {
         Ok(Self(selectors))
     }
 
-    pub fn matches(&self, id: &Option<String>, classes: &SmallVec<[String; 1]>) -> bool {
-        self.0.iter().any(|s| s.matches(id, classes))
+    /// Parses the selectors of a style rule nested in a `parent` rule, e.g. the `&.b` of
+    /// `.a { &.b { /* ... */ } }`, combined with the `parent` selectors (i.e. `.a.b`).  Each `&` is
+    /// replaced by every one of the `parent` selectors in turn, and a selector without an `&` is a
+    /// descendant of the `parent` (i.e. `.b` is `& .b`).  A `parent` selector with a combinator (e.g.
+    /// `.a .b`) can only replace an `&` at the start, as `:is()` isn't supported to wrap it in
+    /// elsewhere (i.e. `.c + &` would otherwise become `.c + .a .b`, which matches another entity).
+    /// See also: https://drafts.csswg.org/css-nesting-1/#nest-selector
+    pub fn parse_nested<'i, 't>(
+        input: &mut CssParser<'i, 't>,
+        parent: &Self,
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        let location = input.current_source_location();
+        let start = input.position();
+        let invalid = |input: &CssParser<'i, 't>| location.new_custom_error(
+            BevyCssParsingErrorKind::InvalidNestedSelector(input.slice_from(start).trim().into())
+        );
+        let nested_selectors = input.parse_comma_separated(|input| {
+            let mut parts = Vec::new();
+            split_nested_selector(input, &mut parts)?;
+            Ok::<_, BevyCssParsingError<'i>>(parts)
+        })?;
+        let mut combined = Vec::new();
+        for mut parts in nested_selectors {
+            if !parts.contains(&NestedPart::Parent) {
+                parts.splice(0..0, [NestedPart::Parent, NestedPart::Css(" ".into())]);
+            }
+            let parent_after_start = parts.iter().skip(1).any(|part| *part == NestedPart::Parent);
+            for selector in parent.0.iter() {
+                if parent_after_start && selector.has_combinator() {
+                    return Err(invalid(input))
+                }
+                let selector = selector.to_string();
+                let nested: String = parts.iter()
+                    .map(|part| match part {
+                        NestedPart::Css(css) => css.as_str(),
+                        NestedPart::Parent => selector.as_str(),
+                    })
+                    .collect();
+                combined.push(nested.trim().to_string())
+            }
+        }
+        let combined = combined.join(", ");
+        let mut parser_input = CssParserInput::new(&combined);
+        let mut combined_input = CssParser::new(&mut parser_input);
+        combined_input.parse_entirely(Self::parse).map_err(|_| invalid(input))
+    }
+
+    pub fn matches(&self, element: &BevyElement) -> bool {
+        self.0.iter().any(|s| s.matches(element))
+    }
+
+    /// The specificity of the most specific selector that matches, or `None` if none of them do
+    pub fn matching_specificity(&self, element: &BevyElement) -> Option<u32> {
+        self.0.iter()
+            .filter(|s| s.matches(element))
+            .map(BevyCssSelector::specificity)
+            .max()
+    }
+
+    /// Rejects non-standard combinators made of repeated `>`s (e.g. the `>>>` deep combinator).
+    /// Otherwise `selectors` only reports a confusing dangling `>`.  The input is left untouched.
+    fn check_combinators<'i, 't>(input: &mut CssParser<'i, 't>) -> Result<(), BevyCssParsingError<'i>> {
+        let state = input.state();
+        let mut combinator = None;
+        let mut result = Ok(());
+        loop {
+            let position = input.position();
+            let location = input.current_source_location();
+            let token = input.next_including_whitespace().map(|token| matches!(*token, Token::Delim('>')));
+            match (token, combinator) {
+                (Ok(true), None) => combinator = Some((position, location, 1)),
+                (Ok(true), Some((start, start_location, count))) =>
+                    combinator = Some((start, start_location, count + 1)),
+                (_, Some((start, start_location, count))) if count > 1 => {
+                    result = Err(start_location.new_custom_error(
+                        BevyCssParsingErrorKind::InvalidCombinator(input.slice(start..position).into())
+                    ));
+                    break
+                },
+                (Ok(false), _) => combinator = None,
+                (Err(_), _) => break,
+            }
+        }
+        input.reset(&state);
+        result
     }
 }
 
+/// A part of a nested selector, which is either css or the nesting selector (`&`)
+#[derive(Debug, Clone, PartialEq)]
+enum NestedPart {
+    Css(String),
+    Parent,
+}
+
+/// Splits a nested selector into its parts, token by token, so an `&` inside a string (e.g.
+/// `[title="a & b"]`) or escaped in an identifier isn't taken for the nesting selector.  The tokens
+/// are written back out as css, including those in blocks (e.g. the `&` of `:not(&)`).
+fn split_nested_selector<'i, 't>(
+    input: &mut CssParser<'i, 't>,
+    parts: &mut Vec<NestedPart>,
+) -> Result<(), BevyCssParsingError<'i>> {
+    let push_css = |parts: &mut Vec<NestedPart>, css: &str| match parts.last_mut() {
+        Some(NestedPart::Css(last)) => last.push_str(css),
+        _ => parts.push(NestedPart::Css(css.to_string())),
+    };
+    while let Ok(token) = input.next_including_whitespace() {
+        let token = token.clone();
+        if token == Token::Delim('&') {
+            parts.push(NestedPart::Parent);
+            continue
+        }
+        push_css(parts, &token.to_css_string());
+        let closing = match token {
+            Token::Function(_) | Token::ParenthesisBlock => ")",
+            Token::SquareBracketBlock => "]",
+            Token::CurlyBracketBlock => "}",
+            _ => continue,
+        };
+        input.parse_nested_block(|input| split_nested_selector(input, parts))?;
+        push_css(parts, closing);
+    }
+    Ok(())
+}
+
 impl fmt::Display for BevySelectorList {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut first = true;
@@ -60,6 +188,27 @@ impl fmt::Debug for BevySelectorList {
     }
 }
 
+/// Serialized as its `Display` string (e.g. `#id.class, .other`), which is parsed again when it is
+/// deserialized
+#[cfg(feature = "serde")]
+impl serde::Serialize for BevySelectorList {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BevySelectorList {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let css = String::deserialize(deserializer)?;
+        let mut parser_input = CssParserInput::new(&css);
+        let mut input = CssParser::new(&mut parser_input);
+        input.parse_entirely(Self::parse).map_err(|err| serde::de::Error::custom(
+            BevyCssContextualError::InvalidSelector(&css, err).error_string_with_location()
+        ))
+    }
+}
+
 /// A particular selector (as defined in a .css sheet) that could match an entity with the right
 /// `id` and `classes`
 #[derive(Clone)]
@@ -67,27 +216,35 @@ pub struct BevyCssSelector(Selector<BevyCssSelectorKinds>);
 
 impl BevyCssSelector {
     #[inline]
-    pub fn matches(&self, id: &Option<String>, classes: &SmallVec<[String; 1]>) -> bool {
+    pub fn matches(&self, element: &BevyElement) -> bool {
         let mut context = MatchingContext::new(
             MatchingMode::Normal,
             None,
             None,
             QuirksMode::NoQuirks
         );
-        let element = BevyElement { id, classes };
         matches_selector(
             &self.0,
             0,
             None,
-            &element,
+            element,
             &mut context,
             &mut |_, _| {}
         )
     }
 
+    /// The specificity of the selector, packed by the `selectors` crate into 10 bits each of ids,
+    /// then classes (inc. attributes & pseudo-classes), then types, i.e. `ids << 20 | classes << 10 |
+    /// types`.  So they can be compared as is, as long as none of the counts go over 1023.
+    /// See also: https://drafts.csswg.org/selectors/#specificity-rules
     pub fn specificity(&self) -> u32 {
         self.0.specificity()
     }
+
+    /// Whether the selector is made of more than one compound selector (e.g. `.a .b` or `.a > .b`)
+    fn has_combinator(&self) -> bool {
+        self.0.iter_raw_match_order().any(Component::is_combinator)
+    }
 }
 
 impl fmt::Display for BevyCssSelector {
@@ -120,22 +277,40 @@ impl SelectorImpl for BevyCssSelectorKinds {
     type PseudoElement = BevyPseudoElement;
 }
 
-#[derive(Clone, PartialEq, Eq)]
-pub struct BevyPseudoClass;
+/// The (non tree-structural) pseudo-classes that can be matched, e.g. `:hover`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BevyPseudoClass {
+    /// While the pointer is over the entity, i.e. its `Interaction` is `Hovered` (or `Clicked`)
+    Hover,
+    /// While the entity is being pressed, i.e. its `Interaction` is `Clicked`
+    Active,
+    /// While the entity has a `CssDisabled` component
+    Disabled,
+    /// While the entity doesn't have a `CssDisabled` component
+    Enabled,
+    /// While the entity has a `CssChecked` component
+    Checked,
+}
 
 impl ToCss for BevyPseudoClass {
     fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
-        dest.write_str("")
+        match *self {
+            Self::Hover => dest.write_str(":hover"),
+            Self::Active => dest.write_str(":active"),
+            Self::Disabled => dest.write_str(":disabled"),
+            Self::Enabled => dest.write_str(":enabled"),
+            Self::Checked => dest.write_str(":checked"),
+        }
     }
 }
 
 impl NonTSPseudoClass for BevyPseudoClass {
     type Impl = BevyCssSelectorKinds;
     fn is_active_or_hover(&self) -> bool {
-        false
+        matches!(*self, Self::Hover | Self::Active)
     }
     fn is_user_action_state(&self) -> bool {
-        false
+        matches!(*self, Self::Hover | Self::Active)
     }
 }
 
@@ -157,12 +332,115 @@ pub struct BevySelectorParser;
 impl<'i> SelectorParser<'i> for BevySelectorParser {
     type Impl = BevyCssSelectorKinds;
     type Error = BevyCssParsingErrorKind<'i>;
+
+    fn parse_non_ts_pseudo_class(
+        &self,
+        location: SourceLocation,
+        name: CowRcStr<'i>,
+    ) -> Result<BevyPseudoClass, ParseError<'i, Self::Error>> {
+        match_ignore_ascii_case! { &name,
+            "hover" => Ok(BevyPseudoClass::Hover),
+            "active" => Ok(BevyPseudoClass::Active),
+            "disabled" => Ok(BevyPseudoClass::Disabled),
+            "enabled" => Ok(BevyPseudoClass::Enabled),
+            "checked" => Ok(BevyPseudoClass::Checked),
+            _ => Err(location.new_custom_error(SelectorParseErrorKind::UnsupportedPseudoClassOrElement(name.clone()))),
+        }
+    }
+}
+
+/// A transient tree of the `CssTag`s of the entities in the Bevy hierarchy, so combinators like
+/// `#parent .child` or `.a + .b` can be matched.  Entities without a `CssTag` should be given an
+/// empty one, as they still count as elements (e.g. for `>`).
+#[derive(Debug, Default)]
+pub struct ElementTree<'a> {
+    nodes: Vec<ElementNode<'a>>,
+}
+
+#[derive(Debug)]
+struct ElementNode<'a> {
+    tag: &'a CssTag,
+    parent: Option<usize>,
+    prev_sibling: Option<usize>,
+    next_sibling: Option<usize>,
+    last_child: Option<usize>,
+    interaction: Interaction,
+    disabled: bool,
+    checked: bool,
 }
 
+impl<'a> ElementTree<'a> {
+    /// Adds an element as the last child of the `parent` element (or as a root element), and
+    /// returns the element's index
+    pub fn push(&mut self, tag: &'a CssTag, parent: Option<usize>) -> usize {
+        let index = self.nodes.len();
+        let prev_sibling = parent.and_then(|parent| self.nodes[parent].last_child.replace(index));
+        if let Some(prev_sibling) = prev_sibling {
+            self.nodes[prev_sibling].next_sibling = Some(index);
+        }
+        self.nodes.push(ElementNode {
+            tag,
+            parent,
+            prev_sibling,
+            next_sibling: None,
+            last_child: None,
+            interaction: Interaction::None,
+            disabled: false,
+            checked: false,
+        });
+        index
+    }
+
+    /// Sets the pointer's interaction with the element, for `:hover` & `:active`
+    #[inline]
+    pub fn set_interaction(&mut self, index: usize, interaction: Interaction) {
+        self.nodes[index].interaction = interaction
+    }
+
+    /// Sets whether the element is disabled, for `:disabled` & `:enabled`
+    #[inline]
+    pub fn set_disabled(&mut self, index: usize, disabled: bool) {
+        self.nodes[index].disabled = disabled
+    }
+
+    /// Sets whether the element is checked, for `:checked`
+    #[inline]
+    pub fn set_checked(&mut self, index: usize, checked: bool) {
+        self.nodes[index].checked = checked
+    }
+
+    /// A tree with a single chain of elements, for the `ancestors` (from the parent up to the root)
+    /// of the `tag` element.  Returns the tree, and the index of the `tag` element.
+    pub fn with_ancestors(tag: &'a CssTag, ancestors: &[&'a CssTag]) -> (Self, usize) {
+        let mut tree = Self::default();
+        let parent = ancestors.iter().rev().fold(None, |parent, &ancestor| Some(tree.push(ancestor, parent)));
+        let index = tree.push(tag, parent);
+        (tree, index)
+    }
+
+    #[inline]
+    pub fn element(&self, index: usize) -> BevyElement<'_> {
+        BevyElement { tree: self, index }
+    }
+}
+
+/// An element of an `ElementTree`, to match selectors against
 #[derive(Copy, Clone, Debug)]
-struct BevyElement<'a> {
-    id: &'a Option<String>,
-    classes: &'a SmallVec<[String; 1]>
+pub struct BevyElement<'a> {
+    tree: &'a ElementTree<'a>,
+    index: usize,
+}
+
+impl<'a> BevyElement<'a> {
+    #[inline]
+    fn node(&self) -> &'a ElementNode<'a> {
+        &self.tree.nodes[self.index]
+    }
+
+    #[inline]
+    fn with_index(&self, index: Option<usize>) -> Option<Self> {
+        index.map(|index| Self { tree: self.tree, index })
+    }
 }
 
 impl<'a> Element for BevyElement<'a> {
@@ -170,12 +448,12 @@ impl<'a> Element for BevyElement<'a> {
 
     #[inline]
     fn opaque(&self) -> OpaqueElement {
-        OpaqueElement::new(self)
+        OpaqueElement::new(self.node())
     }
 
     #[inline]
     fn parent_element(&self) -> Option<Self> {
-        None
+        self.with_index(self.node().parent)
     }
 
     #[inline]
@@ -195,12 +473,12 @@ impl<'a> Element for BevyElement<'a> {
 
     #[inline]
     fn prev_sibling_element(&self) -> Option<Self> {
-        None
+        self.with_index(self.node().prev_sibling)
     }
 
     #[inline]
     fn next_sibling_element(&self) -> Option<Self> {
-        None
+        self.with_index(self.node().next_sibling)
     }
 
     #[inline]
@@ -209,8 +487,11 @@ impl<'a> Element for BevyElement<'a> {
     }
 
     #[inline]
-    fn has_local_name(&self, _local_name: &str) -> bool {
-        false
+    fn has_local_name(&self, local_name: &str) -> bool {
+        match self.node().tag.name {
+            Some(ref name) => name.eq_ignore_ascii_case(local_name),
+            None => false,
+        }
     }
 
     #[inline]
@@ -219,29 +500,44 @@ impl<'a> Element for BevyElement<'a> {
     }
 
     #[inline]
-    fn is_same_type(&self, _other: &Self) -> bool {
-        false
+    fn is_same_type(&self, other: &Self) -> bool {
+        match (&self.node().tag.name, &other.node().tag.name) {
+            (Some(name), Some(other_name)) => name.eq_ignore_ascii_case(other_name),
+            _ => false,
+        }
     }
 
+    /// Attributes have no namespace, so it is ignored
     #[inline]
     fn attr_matches(
         &self,
         _ns: &NamespaceConstraint<&CssString>,
-        _local_name: &CssString,
-        _operation: &AttrSelectorOperation<&CssString>
+        local_name: &CssString,
+        operation: &AttrSelectorOperation<&CssString>
     ) -> bool {
-        false
+        // `eval_str` handles the operators (e.g. `^=`) & the case sensitivity (e.g. `[attr=value i]`)
+        match self.node().tag.attributes.get(&local_name.to_ascii_lowercase()) {
+            Some(value) => operation.eval_str(value),
+            None => false,
+        }
     }
 
     #[inline]
     fn match_non_ts_pseudo_class<F>(
         &self,
-        _pc: &BevyPseudoClass,
+        pc: &BevyPseudoClass,
         _context: &mut MatchingContext<Self::Impl>,
         _flags_setter: &mut F
     ) -> bool
         where F: FnMut(&Self, ElementSelectorFlags) {
-        false
+        match *pc {
+            BevyPseudoClass::Hover =>
+                matches!(self.node().interaction, Interaction::Hovered | Interaction::Clicked),
+            BevyPseudoClass::Active => matches!(self.node().interaction, Interaction::Clicked),
+            BevyPseudoClass::Disabled => self.node().disabled,
+            BevyPseudoClass::Enabled => !self.node().disabled,
+            BevyPseudoClass::Checked => self.node().checked,
+        }
     }
 
     #[inline]
@@ -265,7 +561,7 @@ impl<'a> Element for BevyElement<'a> {
 
     #[inline]
     fn has_id(&self, id: &CssString, case_sensitivity: CaseSensitivity) -> bool {
-        match self.id {
+        match &self.node().tag.id {
             Some(id_str) => case_sensitivity.eq(id_str.as_bytes(), id.as_bytes()),
             None => false,
         }
@@ -273,7 +569,7 @@ impl<'a> Element for BevyElement<'a> {
 
     #[inline]
     fn has_class(&self, name: &CssString, case_sensitivity: CaseSensitivity) -> bool {
-        self.classes.iter().any(|class|
+        self.node().tag.classes.iter().any(|class|
             case_sensitivity.eq(class.as_bytes(), name.as_bytes())
         )
     }
@@ -288,13 +584,388 @@ impl<'a> Element for BevyElement<'a> {
         false
     }
 
+    /// Only child elements are known (i.e. not any `Text`), so an element without children is empty
     #[inline]
     fn is_empty(&self) -> bool {
-        false
+        self.node().last_child.is_none()
     }
 
     #[inline]
     fn is_root(&self) -> bool {
-        false
+        self.node().parent.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::{ParseErrorKind, ParserInput};
+    use crate::errors::BevyCssContextualError;
+    use super::*;
+
+    fn parse_selectors(css: &str) -> Result<BevySelectorList, BevyCssParsingError> {
+        let mut parser_input = ParserInput::new(css);
+        let mut input = CssParser::new(&mut parser_input);
+        BevySelectorList::parse(&mut input)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse_selectors("#id.class, .other").unwrap().0.len(), 2);
+        assert_eq!(parse_selectors("#parent > .child").unwrap().0.len(), 1);
+    }
+
+    #[test]
+    fn test_matching_specificity() {
+        let selectors = parse_selectors(".item, #id.item, #other").unwrap();
+        let (class_only, with_id) = (CssTag::from(".item"), CssTag::from("#id.item"));
+        let specificity = |tag| {
+            let (tree, index) = ElementTree::with_ancestors(tag, &[]);
+            selectors.matching_specificity(&tree.element(index))
+        };
+        assert!(specificity(&with_id).unwrap() > specificity(&class_only).unwrap());
+        assert_eq!(specificity(&CssTag::default()), None);
+    }
+
+    #[test]
+    fn test_specificity() {
+        let specificity = |css| parse_selectors(css).unwrap().0[0].specificity();
+        assert_eq!(specificity("#id"), 1 << 20);
+        assert_eq!(specificity(".class"), 1 << 10);
+        assert_eq!(specificity("button"), 1);
+        assert_eq!(specificity("button#id.a.b"), (1 << 20) | (2 << 10) | 1);
+        assert!(specificity("#id") > specificity(".class"));
+        assert!(specificity(".class") > specificity("button"));
+        assert!(specificity("#id.class") > specificity("#id"));
+        // However many classes, one id is more specific
+        assert!(specificity("#id") > specificity(".a.b.c.d.e.f.g.h"));
+        // Pseudo-classes & attributes count as classes, and combinators don't count
+        assert_eq!(specificity(".a:hover"), specificity(".a.b"));
+        assert_eq!(specificity(".a[data-state]"), specificity(".a.b"));
+        assert_eq!(specificity(".a > .b"), specificity(".a .b"));
+        assert_eq!(specificity(".a .b"), specificity(".a.b"));
+    }
+
+    #[test]
+    fn test_combinators() {
+        let (panel, list, untagged, item) =
+            (CssTag::from(".panel"), CssTag::from("#list"), CssTag::default(), CssTag::from(".item"));
+        let matches = |css, ancestors: &[&CssTag]| {
+            let (tree, index) = ElementTree::with_ancestors(&item, ancestors);
+            parse_selectors(css).unwrap().matches(&tree.element(index))
+        };
+
+        // Descendant
+        assert!(matches(".panel .item", &[&panel]));
+        assert!(matches(".panel .item", &[&list, &untagged, &panel]));
+        assert!(!matches(".panel .item", &[]));
+        assert!(!matches(".panel .item", &[&list]));
+        assert!(matches(".panel #list .item", &[&list, &panel]));
+        assert!(!matches("#list .panel .item", &[&list, &panel]));
+
+        // Child
+        assert!(matches(".panel > .item", &[&panel, &list]));
+        assert!(!matches(".panel > .item", &[&untagged, &panel]));
+        assert!(matches(".panel > * > .item", &[&untagged, &panel]));
+    }
+
+    #[test]
+    fn test_child_combinator() {
+        let (root, row, cell) = (CssTag::from("#root"), CssTag::from(".row"), CssTag::from(".cell"));
+        let selectors = parse_selectors("#root > .row").unwrap();
+        let matches = |ancestors: &[&CssTag]| {
+            let (tree, index) = ElementTree::with_ancestors(&row, ancestors);
+            selectors.matches(&tree.element(index))
+        };
+        // A direct child
+        assert!(matches(&[&root]));
+        // Nested two levels deep
+        assert!(!matches(&[&cell, &root]));
+        // The outer `.row` is a child of `#root`, but the inner one isn't
+        assert!(!matches(&[&row, &root]));
+        assert!(!matches(&[]));
+    }
+
+    #[test]
+    fn test_sibling_combinators() {
+        let (list, label, value, other) =
+            (CssTag::from("#list"), CssTag::from(".label"), CssTag::from(".value"), CssTag::from(".other"));
+        // <#list> <.value/> <.label/> <.value/> <.other/> <.value/> </#list>
+        let mut tree = ElementTree::default();
+        let root = tree.push(&list, None);
+        let children: Vec<usize> = [&value, &label, &value, &other, &value].iter()
+            .map(|&tag| tree.push(tag, Some(root)))
+            .collect();
+        let matches = |css, index: usize| parse_selectors(css).unwrap().matches(&tree.element(index));
+
+        // Adjacent
+        assert!(!matches(".label + .value", children[0]));
+        assert!(matches(".label + .value", children[2]));
+        assert!(!matches(".label + .value", children[4]));
+        // General
+        assert!(!matches(".label ~ .value", children[0]));
+        assert!(matches(".label ~ .value", children[2]));
+        assert!(matches(".label ~ .value", children[4]));
+        // Combined with the hierarchy
+        assert!(matches("#list > .label + .value", children[2]));
+        assert!(!matches(".value + .value", children[2]));
+        assert!(!matches(".label + .value", root));
+    }
+
+    #[test]
+    fn test_deep_combinator() {
+        for css in ["#parent >>> .child", "#parent>>>.child", "#parent >> .child", "#parent >>"] {
+            let err = parse_selectors(css).unwrap_err();
+            match err.kind {
+                ParseErrorKind::Custom(BevyCssParsingErrorKind::InvalidCombinator(ref combinator)) =>
+                    assert!(combinator.starts_with(">>"), "{}", combinator),
+                ref kind => panic!("Unexpected error for `{}`: {:?}", css, kind),
+            }
+            let message = BevyCssContextualError::InvalidSelector(css, err).to_string();
+            assert!(message.contains("combinator"), "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_parse_nested() {
+        let parent = parse_selectors(".a, #b").unwrap();
+        let nested = |css| {
+            let mut parser_input = CssParserInput::new(css);
+            let mut input = CssParser::new(&mut parser_input);
+            BevySelectorList::parse_nested(&mut input, &parent).map(|selectors| selectors.to_string())
+        };
+        assert_eq!(nested("&.c").unwrap(), ".a.c, #b.c");
+        assert_eq!(nested("& > .c, .d").unwrap(), ".a > .c, #b > .c, .a .d, #b .d");
+        assert_eq!(nested(".c + &").unwrap(), ".c + .a, .c + #b");
+        assert!(nested("&..c").is_err());
+        // Only the nesting selector itself is replaced
+        assert_eq!(nested(".c:not(&)").unwrap(), ".c:not(.a), .c:not(#b)");
+        assert_eq!(nested("&[title=\"a & b\"]").unwrap(), ".a[title=\"a & b\"], #b[title=\"a & b\"]");
+        assert_eq!(nested("&.c\\&d").unwrap(), ".a.c\\&d, #b.c\\&d");
+
+        // A parent with a combinator is only replaced at the start, as `.c + .a .b` isn't `.c + &`
+        let parent = parse_selectors(".a .b, #d").unwrap();
+        let mut parser_input = CssParserInput::new("&.c, .c + &");
+        let mut input = CssParser::new(&mut parser_input);
+        let err = BevySelectorList::parse_nested(&mut input, &parent).unwrap_err();
+        assert!(matches!(err.kind, ParseErrorKind::Custom(BevyCssParsingErrorKind::InvalidNestedSelector(_))));
+        let mut parser_input = CssParserInput::new("&.c, & + .d");
+        let mut input = CssParser::new(&mut parser_input);
+        let combined = BevySelectorList::parse_nested(&mut input, &parent).unwrap();
+        assert_eq!(combined.to_string(), ".a .b.c, #d.c, .a .b + .d, #d + .d");
+    }
+
+    #[test]
+    fn test_hover() {
+        let selectors = parse_selectors(".button:hover, .row:hover .label").unwrap();
+        assert_eq!(selectors.to_string(), ".button:hover, .row:hover .label");
+        let (button, row, label) = (CssTag::from(".button"), CssTag::from(".row"), CssTag::from(".label"));
+        let mut tree = ElementTree::default();
+        let button = tree.push(&button, None);
+        let row = tree.push(&row, None);
+        let label = tree.push(&label, Some(row));
+        assert!(!selectors.matches(&tree.element(button)));
+        assert!(!selectors.matches(&tree.element(label)));
+
+        tree.set_interaction(button, Interaction::Hovered);
+        tree.set_interaction(row, Interaction::Hovered);
+        assert!(selectors.matches(&tree.element(button)));
+        assert!(selectors.matches(&tree.element(label)));
+
+        assert!(parse_selectors(".button:focus").is_err());
+    }
+
+    #[test]
+    fn test_active() {
+        let hover = parse_selectors(".button:hover").unwrap();
+        let active = parse_selectors(".button:ACTIVE").unwrap();
+        assert_eq!(active.to_string(), ".button:active");
+        let button = CssTag::from(".button");
+        let mut tree = ElementTree::default();
+        let index = tree.push(&button, None);
+        let matches = |tree: &ElementTree| (hover.matches(&tree.element(index)), active.matches(&tree.element(index)));
+
+        assert_eq!(matches(&tree), (false, false));
+        tree.set_interaction(index, Interaction::Hovered);
+        assert_eq!(matches(&tree), (true, false));
+        // A pressed button is still hovered
+        tree.set_interaction(index, Interaction::Clicked);
+        assert_eq!(matches(&tree), (true, true));
+        // Released
+        tree.set_interaction(index, Interaction::Hovered);
+        assert_eq!(matches(&tree), (true, false));
+    }
+
+    #[test]
+    fn test_structural_pseudo_classes() {
+        let (list, item) = (CssTag::from(".list"), CssTag::from(".item"));
+        let mut tree = ElementTree::default();
+        let root = tree.push(&list, None);
+        let items: Vec<usize> = (0..5).map(|_| tree.push(&item, Some(root))).collect();
+        let other_list = tree.push(&list, None);
+        let matching = |css| {
+            let selectors = parse_selectors(css).unwrap();
+            items.iter().map(|&index| selectors.matches(&tree.element(index))).collect::<Vec<bool>>()
+        };
+        assert_eq!(matching(".list .item:first-child"), [true, false, false, false, false]);
+        assert_eq!(matching(".item:last-child"), [false, false, false, false, true]);
+        assert_eq!(matching(".item:nth-child(2n+1)"), [true, false, true, false, true]);
+        assert_eq!(matching(".item:nth-child(odd)"), matching(".item:nth-child(2n+1)"));
+        assert_eq!(matching(".item:nth-last-child(2)"), [false, false, false, true, false]);
+        assert_eq!(matching(".item:only-child"), [false; 5]);
+
+        let empty = parse_selectors(".list:empty").unwrap();
+        assert!(!empty.matches(&tree.element(root)));
+        assert!(empty.matches(&tree.element(other_list)));
+    }
+
+    #[test]
+    fn test_not() {
+        let selectors = parse_selectors(".item:not(.active)").unwrap();
+        assert_eq!(selectors.to_string(), ".item:not(.active)");
+        let matches = |css| {
+            let tag = CssTag::from(css);
+            let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+            selectors.matches(&tree.element(index))
+        };
+        assert!(matches(".item"));
+        assert!(matches(".item.disabled"));
+        assert!(!matches(".item.active"));
+        assert!(!matches(".other"));
+
+        // Including pseudo-classes
+        let not_hovered = parse_selectors(".item:not(:hover)").unwrap();
+        let item = CssTag::from(".item");
+        let mut tree = ElementTree::default();
+        let item = tree.push(&item, None);
+        assert!(not_hovered.matches(&tree.element(item)));
+        tree.set_interaction(item, Interaction::Hovered);
+        assert!(!not_hovered.matches(&tree.element(item)));
+    }
+
+    #[test]
+    fn test_type_selectors() {
+        let button = parse_selectors("button").unwrap();
+        let primary = parse_selectors("button.primary").unwrap();
+        let matches = |selectors: &BevySelectorList, tag: CssTag| {
+            let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+            selectors.matches(&tree.element(index))
+        };
+        assert!(matches(&button, CssTag::new_named("button")));
+        assert!(matches(&button, CssTag::from("button.primary")));
+        assert!(matches(&primary, CssTag::from("button.primary")));
+        assert!(!matches(&primary, CssTag::new_named("button")));
+        assert!(!matches(&primary, CssTag::from(".primary")));
+        assert!(!matches(&button, CssTag::new_named("label")));
+        // Ids & classes work as before
+        assert!(matches(&parse_selectors("#ok.primary").unwrap(), CssTag::from("button#ok.primary")));
+
+        // e.g. `:first-of-type` uses the names
+        let first_button = parse_selectors("button:first-of-type").unwrap();
+        let (row, label, button) = (CssTag::from(".row"), CssTag::new_named("label"), CssTag::new_named("button"));
+        let mut tree = ElementTree::default();
+        let root = tree.push(&row, None);
+        let (_, first, second) = (tree.push(&label, Some(root)), tree.push(&button, Some(root)), tree.push(&button, Some(root)));
+        assert!(first_button.matches(&tree.element(first)));
+        assert!(!first_button.matches(&tree.element(second)));
+    }
+
+    #[test]
+    fn test_of_type() {
+        let (row, label, button) = (CssTag::from(".row"), CssTag::new_named("label"), CssTag::new_named("button"));
+        let mut tree = ElementTree::default();
+        let root = tree.push(&row, None);
+        let first_label = tree.push(&label, Some(root));
+        let first_button = tree.push(&button, Some(root));
+        let middle_label = tree.push(&label, Some(root));
+        let last_button = tree.push(&button, Some(root));
+        let last_label = tree.push(&label, Some(root));
+        let lone_button = tree.push(&button, None);
+        let matches = |css, index: usize| parse_selectors(css).unwrap().matches(&tree.element(index));
+        assert!(matches("button:first-of-type", first_button));
+        assert!(!matches("button:first-of-type", last_button));
+        assert!(matches("button:last-of-type", last_button));
+        assert!(!matches("button:last-of-type", first_button));
+        assert!(matches("label:first-of-type", first_label));
+        assert!(matches("label:last-of-type", last_label));
+        assert!(!matches(":first-of-type", middle_label));
+        assert!(!matches(":last-of-type", middle_label));
+        assert!(matches("label:nth-of-type(2)", middle_label));
+        assert!(matches("button:only-of-type", lone_button));
+        assert!(!matches("button:only-of-type", first_button));
+    }
+
+    #[test]
+    fn test_attribute_selectors() {
+        let tag = CssTag::from(".panel")
+            .attr("data-state", "open")
+            .attr("data-tags", "large red")
+            .attr("lang", "en-GB");
+        let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+        let matches = |css| parse_selectors(css).unwrap().matches(&tree.element(index));
+        assert!(matches("[data-state]"));
+        assert!(matches(".panel[data-state=open]"));
+        assert!(matches("[DATA-STATE=\"open\"]"));
+        assert!(!matches("[data-state=closed]"));
+        assert!(!matches("[data-state=OPEN]"));
+        assert!(matches("[data-state=OPEN i]"));
+        assert!(matches("[data-tags~=red]"));
+        assert!(!matches("[data-tags~=re]"));
+        assert!(matches("[lang|=en]"));
+        assert!(!matches("[lang|=GB]"));
+        assert!(matches("[data-state^=op]"));
+        assert!(matches("[data-state$=en]"));
+        assert!(matches("[data-tags*=\"ge r\"]"));
+        assert!(!matches("[hidden]"));
+
+        let untagged = CssTag::from(".panel");
+        let (tree, index) = ElementTree::with_ancestors(&untagged, &[]);
+        assert!(!parse_selectors("[data-state]").unwrap().matches(&tree.element(index)));
+    }
+
+    #[test]
+    fn test_attribute_presence() {
+        for value in ["", "true", "anything at all"] {
+            let tag = CssTag::from(".panel").attr("data-open", value);
+            assert!(tag.has_attribute("data-open"));
+            assert!(tag.has_attribute("DATA-OPEN"));
+            let (tree, index) = ElementTree::with_ancestors(&tag, &[]);
+            let matches = |css| parse_selectors(css).unwrap().matches(&tree.element(index));
+            assert!(matches("[data-open]"), "{:?}", value);
+            assert!(matches(".panel[DATA-OPEN]"), "{:?}", value);
+            assert!(!matches("[data-closed]"), "{:?}", value);
+            assert!(!matches("[data]"), "{:?}", value);
+        }
+        assert!(!CssTag::from(".panel").has_attribute("data-open"));
+    }
+
+    #[test]
+    fn test_disabled_and_enabled() {
+        let disabled = parse_selectors(".btn:disabled").unwrap();
+        let enabled = parse_selectors(".btn:enabled").unwrap();
+        assert_eq!(enabled.to_string(), ".btn:enabled");
+        let (btn, other) = (CssTag::from(".btn"), CssTag::from(".other"));
+        let mut tree = ElementTree::default();
+        let (first, second, other) = (tree.push(&btn, None), tree.push(&btn, None), tree.push(&other, None));
+        tree.set_disabled(second, true);
+        tree.set_disabled(other, true);
+        let matches = |index| (disabled.matches(&tree.element(index)), enabled.matches(&tree.element(index)));
+        assert_eq!(matches(first), (false, true));
+        assert_eq!(matches(second), (true, false));
+        assert_eq!(matches(other), (false, false));
+    }
+
+    #[test]
+    fn test_checked() {
+        let checked = parse_selectors(".toggle:checked").unwrap();
+        let unchecked = parse_selectors(".toggle:not(:checked)").unwrap();
+        assert_eq!(checked.to_string(), ".toggle:checked");
+        let toggle = CssTag::from(".toggle");
+        let mut tree = ElementTree::default();
+        let (on, off) = (tree.push(&toggle, None), tree.push(&toggle, None));
+        tree.set_checked(on, true);
+        assert!(checked.matches(&tree.element(on)));
+        assert!(!checked.matches(&tree.element(off)));
+        assert!(!unchecked.matches(&tree.element(on)));
+        assert!(unchecked.matches(&tree.element(off)));
     }
 }
\ No newline at end of file
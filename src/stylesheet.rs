@@ -1,16 +1,19 @@
+use std::{collections::BTreeSet, fmt};
 use bevy::{
     asset::{AssetLoader, LoadContext, LoadedAsset},
-    prelude::Style,
+    prelude::{Component, FromWorld, Style, World},
     reflect::TypeUuid,
+    text::{Text, TextSection, TextStyle},
     utils:: BoxedFuture,
     ui::UiColor,
 };
-use cssparser::{Parser, ParserInput};
+use cssparser::{Parser, ParserInput, ToCss};
 use crate::{
     context::CssContext,
+    errors::{BevyCssContextualError, CssErrorSink},
     parser::{BevySheetParser, BevyPropertyListParser},
-    properties::BevyPropertyDeclaration,
-    rules::BevyCssRule
+    properties::{custom, size_conflicts, BevyPropertyDeclaration, Importance},
+    rules::{BevyCssRule, BevyKeyframesRule, BevyStyleRule},
 };
 
 /// This struct doesn't actually store any styles in memory.  It is just a way to create a `Style`
@@ -18,57 +21,296 @@ use crate::{
 pub struct CssStyle<'i>(pub &'i str);
 
 impl<'i> CssStyle<'i> {
+    /// All the declarations, in the order they are applied (i.e. the `!important` ones last)
     pub fn parse_inline(&self) -> Vec<BevyPropertyDeclaration> {
+        let ParsedInlineStyle { mut declarations, important_declarations } = self.parse();
+        declarations.extend(important_declarations);
+        declarations
+    }
+
+    /// Parses the CSS string, keeping the declarations so they can be used repeatedly without
+    /// re-parsing (e.g. when styling entities every frame, or when a node needs both its `Style` &
+    /// its `UiColor`).  Any declarations that are skipped are reported to the default `CssErrorSink`
+    /// (i.e. logged with `warn!`).
+    #[inline]
+    pub fn parse(&self) -> ParsedInlineStyle {
+        self.parse_with_sink(&CssErrorSink::default())
+    }
+
+    /// The same as `parse()`, but with the skipped declarations reported to the `sink`
+    pub fn parse_with_sink(&self, sink: &CssErrorSink) -> ParsedInlineStyle {
+        let mut parser_input = ParserInput::new(self.0);
+        let mut input = Parser::new(&mut parser_input);
+        let (declarations, important_declarations) =
+            BevyPropertyListParser::parse_with_importance(&mut input, sink);
+        ParsedInlineStyle { declarations, important_declarations }
+    }
+
+    /// An alias of `parse()`, which was first added under this name
+    #[inline]
+    pub fn parse_once(&self) -> ParsedInlineStyle {
+        self.parse()
+    }
+
+    /// The same as `parse()`, but fails with every error if any declaration can't be parsed (e.g.
+    /// an unknown property, or an invalid value), rather than skipping it.  Warnings (e.g. for a
+    /// clamped value) don't fail it, and aren't reported anywhere.
+    pub fn try_parse(&self) -> Result<ParsedInlineStyle, Vec<BevyCssContextualError<'i>>> {
         let mut parser_input = ParserInput::new(self.0);
         let mut input = Parser::new(&mut parser_input);
-        BevyPropertyListParser::parse_with(&mut input)
+        let mut errors = Vec::new();
+        let (declarations, important_declarations) =
+            BevyPropertyListParser::parse_with_errors(&mut input, &mut errors);
+        errors.retain(|error| !error.is_warning());
+        if errors.is_empty() {
+            Ok(ParsedInlineStyle { declarations, important_declarations })
+        } else {
+            Err(errors)
+        }
+    }
+
+    #[inline]
+    pub fn to_style(&self, context: &CssContext) -> Style {
+        self.parse().to_style(context)
+    }
+
+    /// The same as `to_style()`, but fails if anything can't be parsed (see `try_parse()`).  Each
+    /// error's `error_string_with_location()` gives where it is in the css.
+    #[inline]
+    pub fn try_to_style(&self, context: &CssContext) -> Result<Style, Vec<BevyCssContextualError<'i>>> {
+        self.try_parse().map(|parsed| parsed.to_style(context))
+    }
+
+    #[inline]
+    pub fn to_ui_color(&self) -> UiColor {
+        self.parse().to_ui_color()
+    }
+
+    #[inline]
+    pub fn to_text_style(&self, context: &CssContext, base: &TextStyle) -> TextStyle {
+        self.parse().to_text_style(context, base)
+    }
+}
+
+/// The parsed declarations of a `CssStyle`, which can be turned into components any number of times.
+/// Created with `CssStyle::parse()`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedInlineStyle {
+    pub declarations: Vec<BevyPropertyDeclaration>,
+    /// The `!important` declarations, which are applied after the normal ones
+    pub important_declarations: Vec<BevyPropertyDeclaration>,
+}
+
+impl ParsedInlineStyle {
+    /// All the declarations, in the order they are applied
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &BevyPropertyDeclaration> {
+        self.declarations.iter().chain(self.important_declarations.iter())
     }
 
     pub fn to_style(&self, context: &CssContext) -> Style {
         let mut style = Style::default();
-        let properties = self.parse_inline();
-        for property in properties.iter() {
-            property.modify_style(context, &mut style)
+        // Declarations like `font-size` change the context for the declarations that follow them
+        let mut context = *context;
+        for property in custom::resolve_variables(self.iter(), &CssErrorSink::default()) {
+            property.modify_context(&mut context);
+            property.modify_style(&context, &mut style)
         }
+        size_conflicts::clamp_and_warn(&mut style, context.warn_size_conflicts);
         style
     }
 
     pub fn to_ui_color(&self) -> UiColor {
         let mut color = UiColor::default();
-        let properties = self.parse_inline();
-        for property in properties.iter() {
+        for property in custom::resolve_variables(self.iter(), &CssErrorSink::default()) {
             property.modify_color(&mut color)
         }
         color
     }
+
+    /// The `base` text style (e.g. with the `font` to use), with any `font-size` & `color` (or
+    /// `text-color`) declarations applied to it
+    pub fn to_text_style(&self, context: &CssContext, base: &TextStyle) -> TextStyle {
+        let mut text = Text {
+            sections: vec![TextSection { value: String::new(), style: base.clone() }],
+            ..Default::default()
+        };
+        let mut context = *context;
+        for property in custom::resolve_variables(self.iter(), &CssErrorSink::default()) {
+            property.modify_text(&context, &mut text);
+            property.modify_context(&mut context)
+        }
+        text.sections.remove(0).style
+    }
+
+    /// The names of the declared properties that are parsed, but not supported by Bevy (see
+    /// `CssStylesheet::unsupported_properties()`)
+    pub fn unsupported_properties(&self) -> BTreeSet<&str> {
+        unsupported_property_names(self.iter())
+    }
+}
+
+/// The declarations as an inline style, e.g. `width: 10px; color: #ff0000 !important;`, which
+/// `CssStyle` parses back to the same declarations
+impl fmt::Display for ParsedInlineStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let declarations = self.declarations.iter().map(|declaration| (declaration, Importance::Normal));
+        let important_declarations = self.important_declarations.iter()
+            .map(|declaration| (declaration, Importance::Important));
+        for (n, (declaration, importance)) in declarations.chain(important_declarations).enumerate() {
+            if n > 0 {
+                f.write_str(" ")?;
+            }
+            declaration.to_css(f)?;
+            if importance == Importance::Important {
+                f.write_str(" ")?;
+                importance.to_css(f)?;
+            }
+            f.write_str(";")?;
+        }
+        Ok(())
+    }
+}
+
+/// Inline css for an entity (like a html `style` attribute), which is applied along with any
+/// stylesheets by the `CssPlugin`.  Inline declarations win over stylesheet declarations, unless
+/// only the stylesheet declaration is `!important`.
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct InlineCss(pub ParsedInlineStyle);
+
+impl InlineCss {
+    /// Parses the css, reporting any declarations that are skipped to the `sink` (e.g. the app's
+    /// `CssErrorSink` resource).  `InlineCss::from()` uses the default sink.
+    pub fn parse_with_sink(css_string: &str, sink: &CssErrorSink) -> Self {
+        Self(CssStyle(css_string).parse_with_sink(sink))
+    }
+}
+
+impl From<&str> for InlineCss {
+    fn from(css_string: &str) -> Self {
+        Self(CssStyle(css_string).parse())
+    }
 }
 
 /// Stored as an asset
 #[derive(Debug, TypeUuid)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[uuid = "da9c2e27-0fe0-4fca-b9d1-5012c042a882"]  // from: https://www.uuidgenerator.net/version4
 pub struct CssStylesheet {
     pub rules: Vec<BevyCssRule>,
+    /// A message (with the line & column) for each rule or declaration that was skipped when the
+    /// sheet was parsed, or that won't be applied as written (see `BevyCssContextualError::Warning`),
+    /// e.g. for an editor to show
+    pub errors: Vec<String>,
 }
 
 impl CssStylesheet {
-    pub fn parse_sheet(css_string: &str) -> Vec<BevyCssRule> {
+    /// Parses the sheet, reporting an error to the `sink` for anything that is skipped (as well as
+    /// keeping their messages in `errors`).  `CssStylesheet::from()` uses the default sink, which
+    /// logs them with `warn!`.
+    pub fn parse_with_sink(css_string: &str, sink: &CssErrorSink) -> Self {
+        let (rules, errors) = Self::parse_sheet_with_errors(css_string);
+        errors.iter().for_each(|error| sink.report(error));
+        let errors = errors.iter().map(BevyCssContextualError::error_string_with_location).collect();
+        Self { rules, errors }
+    }
+
+    /// The rules, and the messages for anything that was skipped (see `errors`), without reporting
+    /// them anywhere
+    pub fn parse_sheet(css_string: &str) -> (Vec<BevyCssRule>, Vec<String>) {
+        let (rules, errors) = Self::parse_sheet_with_errors(css_string);
+        let errors = errors.iter().map(BevyCssContextualError::error_string_with_location).collect();
+        (rules, errors)
+    }
+
+    /// The same as `parse_sheet()`, but with the errors themselves, which borrow from the css
+    pub fn parse_sheet_with_errors(css_string: &str) -> (Vec<BevyCssRule>, Vec<BevyCssContextualError>) {
         let mut parser_input = ParserInput::new(css_string);
         let mut input = Parser::new(&mut parser_input);
-        BevySheetParser::parse_with(&mut input)
+        BevySheetParser::parse_with_errors(&mut input)
+    }
+
+    /// Parses the sheet, but fails with every error if anything can't be parsed (e.g. an unknown
+    /// property, or an invalid value), rather than skipping it.  Useful for validating stylesheets
+    /// (e.g. in CI), whereas loading a stylesheet asset always skips what it can't parse.  Warnings
+    /// (e.g. for a clamped value) don't fail it, and aren't reported anywhere.
+    pub fn parse_strict(css_string: &str) -> Result<Self, Vec<BevyCssContextualError>> {
+        let (rules, mut errors) = Self::parse_sheet_with_errors(css_string);
+        errors.retain(|error| !error.is_warning());
+        if errors.is_empty() {
+            Ok(Self { rules, errors: Vec::new() })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The style rules whose selectors are written exactly as `selector` (as given by their
+    /// `Display`, e.g. `#id.class, .other`), including those inside `@media` rules.  Useful for
+    /// finding rules to list or edit.
+    pub fn rules_matching_selector(&self, selector: &str) -> Vec<&BevyStyleRule> {
+        let selector = selector.trim();
+        self.rules.iter()
+            .flat_map(BevyCssRule::style_rules)
+            .filter(|style_rule| style_rule.selectors.to_string() == selector)
+            .collect()
+    }
+
+    /// The names of the properties declared in the sheet (inc. inside `@media` & `@keyframes` rules)
+    /// that are valid css, but not supported by Bevy, e.g. `gap` or `column-count`.  They are
+    /// parsed without an error (just a warning), but won't change how anything looks, so this is a
+    /// way to find what won't render when moving css over from the web.
+    pub fn unsupported_properties(&self) -> BTreeSet<&str> {
+        let style_declarations = self.rules.iter()
+            .flat_map(BevyCssRule::style_rules)
+            .flat_map(|style_rule| style_rule.declarations.iter().chain(style_rule.important_declarations.iter()));
+        let keyframe_declarations = self.rules.iter()
+            .filter_map(|rule| match rule {
+                BevyCssRule::Keyframes(keyframes) => Some(keyframes),
+                _ => None,
+            })
+            .flat_map(|keyframes| keyframes.frames.iter())
+            .flat_map(|frame| frame.declarations.iter());
+        unsupported_property_names(style_declarations.chain(keyframe_declarations))
+    }
+
+    /// The (top level) `@keyframes` rule with the (case-sensitive) `name`.  Where there are several,
+    /// the last one is used.
+    pub fn keyframes(&self, name: &str) -> Option<&BevyKeyframesRule> {
+        self.rules.iter().rev().find_map(|rule| match rule {
+            BevyCssRule::Keyframes(keyframes) if keyframes.name == name => Some(keyframes),
+            _ => None,
+        })
     }
 }
 
 impl From<&str> for CssStylesheet {
     fn from(css_string: &str) -> Self {
+        Self::parse_with_sink(css_string, &CssErrorSink::default())
+    }
+}
+
+fn unsupported_property_names<'a>(
+    declarations: impl Iterator<Item = &'a BevyPropertyDeclaration>
+) -> BTreeSet<&'a str> {
+    declarations
+        .filter(|declaration| declaration.is_unsupported())
+        .map(BevyPropertyDeclaration::name)
+        .collect()
+}
+
+pub(crate) struct CssStylesheetLoader {
+    error_sink: CssErrorSink,
+}
+
+impl FromWorld for CssStylesheetLoader {
+    /// Uses the app's `CssErrorSink`, if it has one
+    fn from_world(world: &mut World) -> Self {
         Self {
-            rules: Self::parse_sheet(css_string)
+            error_sink: world.get_resource::<CssErrorSink>().cloned().unwrap_or_default(),
         }
     }
 }
 
-#[derive(Default)]
-pub(crate) struct CssStylesheetLoader;
-
 impl AssetLoader for CssStylesheetLoader {
     fn load<'a>(
         &'a self,
@@ -77,7 +319,7 @@ impl AssetLoader for CssStylesheetLoader {
     ) -> BoxedFuture<'a, anyhow::Result<()>> {
         Box::pin(async move {
             let css_file_string = std::str::from_utf8(bytes)?;
-            let stylesheet = CssStylesheet::from(css_file_string);
+            let stylesheet = CssStylesheet::parse_with_sink(css_file_string, &self.error_sink);
             load_context.set_default_asset(LoadedAsset::new(stylesheet));
             Ok(())
         })
@@ -93,6 +335,7 @@ mod tests {
     use super::*;
     use bevy::{
         ui,
+        math::Vec2,
         prelude::{
             Size, Color,
         },
@@ -114,6 +357,91 @@ mod tests {
         assert_eq!(style.to_style(&context), res)
     }
 
+    #[test]
+    fn test_css_style_try_to_style() {
+        let context = CssContext::default();
+        let style = CssStyle("height: 5px; width: 100%;");
+        assert_eq!(style.try_to_style(&context).unwrap(), style.to_style(&context));
+        let errors = CssStyle("width: 10pq;").try_to_style(&context).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], BevyCssContextualError::InvalidValue(..)));
+        assert!(errors[0].error_string_with_location().contains("col: 8"));
+        let errors = CssStyle("colr: red; width: 10pq; height: 5px;").try_to_style(&context).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[0], BevyCssContextualError::UnsupportedProperty(..)));
+    }
+
+    #[test]
+    fn test_css_style_all_initial() {
+        let context = CssContext::default();
+        let style = CssStyle("width: 100%; display: none; all: initial; height: 100px;");
+        let res = ui::Style {
+            size: Size {
+                height: ui::Val::Px(100.0),
+                ..ui::Style::default().size
+            },
+            ..Default::default()
+        };
+        assert_eq!(style.to_style(&context), res)
+    }
+
+    #[test]
+    fn test_css_style_font_size() {
+        let context = CssContext { font_size: 10.0, ..Default::default() };
+        let style = CssStyle("width: 2em; font-size: 20px; height: 2em;");
+        let res = ui::Style {
+            size: Size {
+                width: ui::Val::Px(20.0),
+                height: ui::Val::Px(40.0)
+            },
+            ..Default::default()
+        };
+        assert_eq!(style.to_style(&context), res)
+    }
+
+    #[test]
+    fn test_css_style_context_from_window() {
+        use bevy::window::{Window, WindowDescriptor, WindowId};
+        use raw_window_handle::{RawWindowHandle, WebHandle};
+        // 1600x1200 physical pixels, at a scale factor of 2
+        let window = Window::new(
+            WindowId::primary(),
+            &WindowDescriptor::default(),
+            1600,
+            1200,
+            2.0,
+            None,
+            RawWindowHandle::Web(WebHandle::empty()),
+        );
+        let context = CssContext::from_window(&window);
+        assert_eq!(context.viewport_size, Vec2::new(800.0, 600.0));
+        let style = CssStyle("width: 100vw; height: 50vh;").to_style(&context);
+        assert_eq!(style.size.width, ui::Val::Px(800.0));
+        assert_eq!(style.size.height, ui::Val::Px(300.0));
+    }
+
+    #[test]
+    fn test_css_style_context_builder() {
+        let context = CssContext::new().with_viewport_size(Vec2::new(800.0, 600.0)).with_font_size(16.0);
+        let style = CssStyle("width: 50vw; height: 2em; min-width: 2rem;");
+        let res = ui::Style {
+            size: Size {
+                width: ui::Val::Px(400.0),
+                height: ui::Val::Px(32.0)
+            },
+            min_size: Size {
+                width: ui::Val::Px(2.0 * CssContext::default().root_font_size),
+                ..ui::Style::default().min_size
+            },
+            ..Default::default()
+        };
+        assert_eq!(style.to_style(&context), res);
+
+        let context = context.with_root_font_size(10.0).with_vertical_text(true);
+        assert_eq!(style.to_style(&context).min_size.width, ui::Val::Px(20.0));
+        assert!(context.vertical_text);
+    }
+
     #[test]
     fn test_css_style_color() {
         let style = CssStyle("color: rgb(65, 75, 85);");
@@ -121,4 +449,227 @@ mod tests {
         assert_eq!(style.to_ui_color().0, res.0)
     }
 
+    #[test]
+    fn test_css_style_background_color() {
+        let style = CssStyle("background-color: rgb(65, 75, 85);");
+        assert_eq!(style.to_ui_color().0, Color::rgb_u8(65, 75, 85));
+        // The last declaration wins, whichever of the two properties is used
+        let style = CssStyle("color: red; background-color: blue;");
+        assert_eq!(style.to_ui_color().0, Color::BLUE);
+        let style = CssStyle("background-color: blue; color: red;");
+        assert_eq!(style.to_ui_color().0, Color::RED);
+    }
+
+    #[test]
+    fn test_css_style_current_color() {
+        let style = CssStyle("color: red; color: currentColor;");
+        assert_eq!(style.to_ui_color().0, Color::RED);
+        // Nothing has been set yet, so the default is used
+        let style = CssStyle("color: currentColor;");
+        assert_eq!(style.to_ui_color().0, ui::UiColor::default().0)
+    }
+
+    #[test]
+    fn test_parsed_inline_style() {
+        let context = CssContext::default();
+        let style = CssStyle("width: 100%; margin: 1em auto; color: rgb(65, 75, 85);");
+        let parsed = style.parse();
+        assert_eq!(parsed.declarations.len(), 3);
+        // The parsed form can be reused, and always gives the same result as the one-shot path
+        for _ in 0..2 {
+            assert_eq!(parsed.to_style(&context), style.to_style(&context));
+            assert_eq!(parsed.to_ui_color().0, style.to_ui_color().0);
+        }
+    }
+
+    #[test]
+    fn test_css_style_parse() {
+        let style = CssStyle("width: 1px !important; height: 2px; color: red;");
+        let parsed = style.parse();
+        assert_eq!(parsed.declarations.len(), 2);
+        assert_eq!(parsed.important_declarations.len(), 1);
+        // Unlike the source order, the important declaration comes last, as it is applied last
+        assert_eq!(style.parse_inline(), parsed.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(style.parse_inline()[2], parsed.important_declarations[0]);
+    }
+
+    #[test]
+    fn test_css_style_text_style() {
+        let context = CssContext::new().with_font_size(10.0);
+        let base = TextStyle { font_size: 12.0, color: Color::WHITE, ..Default::default() };
+        let text_style = CssStyle("font-size: 24px; color: red").to_text_style(&context, &base);
+        assert_eq!(text_style.font_size, 24.0);
+        assert_eq!(text_style.color, Color::RED);
+        // `em` is relative to the context's font size, and anything not declared is kept
+        let text_style = CssStyle("font-size: 2em; width: 10px;").to_text_style(&context, &base);
+        assert_eq!(text_style.font_size, 20.0);
+        assert_eq!(text_style.color, Color::WHITE);
+        assert_eq!(text_style.font, base.font);
+    }
+
+    #[test]
+    fn test_css_style_min_larger_than_max() {
+        for context in [
+            CssContext::default(),
+            CssContext { warn_size_conflicts: false, ..Default::default() }
+        ] {
+            let style = CssStyle("min-width: 200px; max-width: 100px; max-height: 50%; min-height: 10%;")
+                .to_style(&context);
+            // The min size wins
+            assert_eq!(style.min_size.width, ui::Val::Px(200.0));
+            assert_eq!(style.max_size.width, ui::Val::Px(200.0));
+            assert_eq!(style.min_size.height, ui::Val::Percent(10.0));
+            assert_eq!(style.max_size.height, ui::Val::Percent(50.0));
+        }
+    }
+
+    #[test]
+    fn test_rules_matching_selector() {
+        let sheet = CssStylesheet::from(
+            ".item { width: 10px; } #root { width: 20px; } .item, .other { width: 30px; } .item { height: 5px; }"
+        );
+        let rules = sheet.rules_matching_selector(".item");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules.iter().map(|rule| rule.declarations.len()).collect::<Vec<_>>(),
+            vec![1, 1]
+        );
+        assert_eq!(rules[1].declarations[0], CssStyle("height: 5px;").parse_inline()[0]);
+        assert_eq!(sheet.rules_matching_selector(".item, .other").len(), 1);
+        assert!(sheet.rules_matching_selector(".missing").is_empty());
+    }
+
+    #[test]
+    fn test_stylesheet_errors() {
+        let sheet = CssStylesheet::from(
+            ".a { colr: red; width: 10px; }\n@media (min-width: 10px) { .b { height: 1pq; } }\n.c:bad { }\n.d { }"
+        );
+        // Everything that could be parsed is kept
+        assert_eq!(sheet.rules.len(), 3);
+        assert_eq!(sheet.errors.len(), 3);
+        assert!(sheet.errors[0].contains("colr: red"));
+        assert!(sheet.errors[1].contains("line: 1"));
+        assert!(sheet.errors[2].contains(".c:bad"));
+        assert!(CssStylesheet::from(".a { width: 10px; }").errors.is_empty());
+    }
+
+    #[test]
+    fn test_error_sink() {
+        use std::sync::{Arc, Mutex};
+        let collected = Arc::new(Mutex::new(Vec::new()));
+        let sink = {
+            let collected = collected.clone();
+            CssErrorSink::new(move |error| collected.lock().unwrap().push(error.error_string_with_location()))
+        };
+        // The loader uses the app's sink
+        let mut world = World::new();
+        world.insert_resource(sink);
+        let loader = CssStylesheetLoader::from_world(&mut world);
+        let sheet = CssStylesheet::parse_with_sink(".a { colr: red; } .b:bad { }", &loader.error_sink);
+        assert_eq!(*collected.lock().unwrap(), sheet.errors);
+        assert_eq!(sheet.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let css = ".a { colr: red; width: 10px; } .b { height: 5px; }";
+        let errors = CssStylesheet::parse_strict(css).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], BevyCssContextualError::UnsupportedProperty(css, _) if css.starts_with("colr: red")));
+        // Leniently, only the bad declaration is skipped
+        let sheet = CssStylesheet::from(css);
+        assert_eq!(sheet.rules.len(), 2);
+        assert_eq!(sheet.rules_matching_selector(".a")[0].declarations.len(), 1);
+        assert_eq!(CssStylesheet::parse_strict(".b { height: 5px; }").unwrap().rules.len(), 1);
+        // Warnings don't fail it
+        assert!(CssStylesheet::parse_strict(".b { opacity: 1.5; gap: 1px; }").is_ok());
+        assert!(CssStylesheet::from(".b { opacity: 1.5; }").errors[0].contains("`1` will be used"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let sheet = CssStylesheet::from(r#"
+            #id.a, .b > .c:hover { width: 10%; margin: 1px auto 2em; color: red !important; colr: red; }
+            @media (prefers-color-scheme: dark) and (min-width: 600px) {
+                .a { transition: width 0.2s ease-in; --spacing: 1px 2px; padding: var(--spacing); }
+            }
+        "#);
+        let json = serde_json::to_string(&sheet).unwrap();
+        let round_tripped: CssStylesheet = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped.errors, sheet.errors);
+        assert_eq!(round_tripped.rules.len(), sheet.rules.len());
+        let style_rules = |sheet: &CssStylesheet| sheet.rules.iter()
+            .flat_map(BevyCssRule::style_rules)
+            .map(|rule| (
+                rule.selectors.to_string(),
+                rule.declarations.as_ref().clone(),
+                rule.important_declarations.as_ref().clone(),
+            ))
+            .collect::<Vec<_>>();
+        assert_eq!(style_rules(&round_tripped), style_rules(&sheet));
+        assert_eq!(serde_json::to_string(&round_tripped).unwrap(), json);
+        // The selectors are re-parsed, so invalid ones are rejected
+        let json = json.replacen("#id.a, .b > .c:hover", ".a >>> .b", 1);
+        assert!(serde_json::from_str::<CssStylesheet>(&json).is_err());
+    }
+
+    #[test]
+    fn test_css_style_important() {
+        let context = CssContext::default();
+        // `!important` wins, wherever it is in the block
+        let style = CssStyle("width: 10px !important; width: 20px;");
+        assert_eq!(style.to_style(&context).size.width, ui::Val::Px(10.0));
+        let parsed = style.parse();
+        assert_eq!(parsed.declarations.len(), 1);
+        assert_eq!(parsed.important_declarations.len(), 1);
+        assert_eq!(parsed.to_style(&context), style.to_style(&context));
+    }
+
+    #[test]
+    fn test_importance_round_trip() {
+        let parsed = |importance: Importance| {
+            let css = format!("width: 10px {};", importance.to_css_string());
+            let parsed = CssStyle(&css).parse();
+            (parsed.declarations.len(), parsed.important_declarations.len())
+        };
+        assert_eq!(Importance::Important.to_css_string(), "!important");
+        assert_eq!(parsed(Importance::Important), (0, 1));
+        assert_eq!(Importance::Normal.to_css_string(), "");
+        assert_eq!(parsed(Importance::Normal), (1, 0));
+    }
+
+    #[test]
+    fn test_css_style_color_all_unset() {
+        let style = CssStyle("color: red; all: unset;");
+        assert_eq!(style.to_ui_color().0, ui::UiColor::default().0)
+    }
+
+    #[test]
+    fn test_display() {
+        let parsed = CssStyle("margin:10px  5px; color: red !important; width: 50%").parse();
+        assert_eq!(parsed.to_string(), "margin: 10px 5px; width: 50%; color: #ff0000 !important;");
+        assert_eq!(CssStyle(&parsed.to_string()).parse(), parsed);
+        assert_eq!(ParsedInlineStyle::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_unsupported_properties() {
+        let stylesheet = CssStylesheet::from(
+            ".a { column-count: 3; width: 10px; column-count: 2; } \
+             @media (prefers-color-scheme: dark) { .b { gap: 1px !important; } } \
+             @keyframes grow { to { order: 1; } }"
+        );
+        // They are valid, so aren't errors
+        assert!(stylesheet.errors.is_empty());
+        assert_eq!(stylesheet.unsupported_properties(), BTreeSet::from(["column-count", "gap", "order"]));
+        assert!(CssStylesheet::from(".a { width: 10px; }").unsupported_properties().is_empty());
+
+        let parsed = CssStyle("column-width: 10em; height: 1px").parse();
+        assert_eq!(parsed.unsupported_properties(), BTreeSet::from(["column-width"]));
+        // Values with `var()` aren't parsed yet, so are found by their property
+        let parsed = CssStyle("--spacing: 1px; gap: var(--spacing); width: var(--spacing)").parse();
+        assert_eq!(parsed.unsupported_properties(), BTreeSet::from(["gap"]));
+    }
+
 }
\ No newline at end of file
@@ -1,20 +1,46 @@
 pub mod absolute_length;
+pub mod alignment;
+pub mod angle;
+pub mod animation;
 pub mod bevy_converters;
 pub mod bevy_impl;
+pub mod box_shadow;
+pub mod color;
+pub mod color_space;
+pub mod content;
+pub mod css_wide_keyword;
+pub mod easing;
+pub mod flex;
+pub mod font;
 pub mod generic;
 pub mod length;
+pub mod line_height;
+pub mod math_function;
 pub mod number;
 pub mod parse;
 pub mod percentage;
 pub mod ratio;
 pub mod shorthand;
+pub mod time;
+pub mod transform;
+pub mod transition;
+pub mod visibility;
 
 pub use parse::Parse;
 pub use absolute_length::AbsoluteLength;
+pub use angle::Angle;
+pub use animation::AnimationSpec;
+pub use color::ColorOrCurrentColor;
+pub use css_wide_keyword::CssWideKeyword;
 pub use length::{
-    Length, LengthPercentage, LengthPercentageOrAuto,
+    Length, LengthPercentage, LengthPercentageOrAuto, NonNegativeLength, NonNegativeLengthPercentage, Size,
 };
-pub use number::{Number, NonNegativeNumber};
+pub use line_height::LineHeight;
+pub use number::{AlphaValue, Integer, Number, NonNegativeNumber};
 pub use ratio::{Ratio, RatioOrAuto};
-pub use shorthand::SidedValue;
+pub use shorthand::{PairedValue, SidedValue};
+pub use time::Time;
+pub use transform::TransformList;
+pub use transition::TransitionList;
+pub use visibility::Visibility;
 
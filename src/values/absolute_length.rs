@@ -1,5 +1,7 @@
 use std::cmp::Ordering;
+use std::fmt;
 use std::ops::Mul;
+use cssparser::ToCss;
 use crate::CssContext;
 use crate::values::generic::Numeric;
 
@@ -16,6 +18,7 @@ pub const AU_PER_Q : f32 = AU_PER_MM / 4.0;
 /// An absolute length in the given units. The conversion of in/mm/etc to px assumes 96 dpi
 /// See also: https://drafts.csswg.org/css-values/#absolute-length
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AbsoluteLength {
     Px(f32),                // pixels
     Mm(f32),                // millimeters
@@ -56,8 +59,16 @@ impl AbsoluteLength {
     }
 
     #[inline]
-    pub fn to_computed_px(&self, _context: &CssContext) -> f32 {
-        self.to_px()
+    pub fn is_physical(&self) -> bool {
+        !matches!(*self, Self::Px(_))
+    }
+
+    /// The length in Bevy pixels.  Every length is scaled by the context's `ui_scale`, and the
+    /// physical units (i.e. not `px`) are also scaled by its `device_pixel_ratio`.
+    #[inline]
+    pub fn to_computed_px(&self, context: &CssContext) -> f32 {
+        let device_pixel_ratio = if self.is_physical() { context.device_pixel_ratio } else { 1.0 };
+        self.to_px() * device_pixel_ratio * context.ui_scale
     }
 }
 
@@ -117,6 +128,21 @@ impl From<f32> for AbsoluteLength {
     }
 }
 
+impl ToCss for AbsoluteLength {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        let unit = match *self {
+            Self::Px(_) => "px",
+            Self::Mm(_) => "mm",
+            Self::Cm(_) => "cm",
+            Self::Q(_)  => "Q",
+            Self::In(_) => "in",
+            Self::Pc(_) => "pc",
+            Self::Pt(_) => "pt",
+        };
+        write!(dest, "{}{}", self.raw_value(), unit)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,6 +169,37 @@ mod tests {
         assert_eq!(AbsoluteLength::Pt(9.0).to_px(), 12.0);
     }
 
+    #[test]
+    fn test_to_computed_px() {
+        let context = CssContext::default();
+        assert_eq!(AbsoluteLength::Px(10.0).to_computed_px(&context), 10.0);
+        assert_eq!(AbsoluteLength::In(1.0).to_computed_px(&context), 96.0);
+    }
+
+    #[test]
+    fn test_ui_scale() {
+        let context = CssContext { ui_scale: 2.0, ..Default::default() };
+        assert_eq!(AbsoluteLength::Px(10.0).to_computed_px(&context), 20.0);
+        assert_eq!(AbsoluteLength::In(1.0).to_computed_px(&context), 192.0);
+        assert_eq!(AbsoluteLength::Pt(9.0).to_computed_px(&context), 24.0);
+    }
+
+    #[test]
+    fn test_device_pixel_ratio() {
+        let context = CssContext { device_pixel_ratio: 2.0, ..Default::default() };
+        // `px` isn't a physical unit
+        assert_eq!(AbsoluteLength::Px(10.0).to_computed_px(&context), 10.0);
+        assert_eq!(AbsoluteLength::In(1.0).to_computed_px(&context), 192.0);
+        assert_eq!(AbsoluteLength::Mm(254.0).to_computed_px(&context), 20.0);
+    }
+
+    #[test]
+    fn test_ui_scale_and_device_pixel_ratio() {
+        let context = CssContext { ui_scale: 0.5, device_pixel_ratio: 3.0, ..Default::default() };
+        assert_eq!(AbsoluteLength::Px(10.0).to_computed_px(&context), 5.0);
+        assert_eq!(AbsoluteLength::In(1.0).to_computed_px(&context), 144.0);
+    }
+
     #[test]
     fn test_numeric() {
         assert_eq!(AbsoluteLength::zero(), AbsoluteLength::Px(0.0));
@@ -0,0 +1,299 @@
+use std::fmt;
+use bevy::ui;
+use cssparser::{
+    Parser, ToCss,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::{bevy_impl::CssKeyword, Parse},
+};
+
+/// The values of the `place-content` shorthand, for `align-content` then `justify-content`.  A
+/// single value is used for both.
+/// See also: https://drafts.csswg.org/css-align-3/#place-content
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlaceContent {
+    pub align: ui::AlignContent,
+    pub justify: ui::JustifyContent,
+}
+
+impl Parse for PlaceContent {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let align = ui::AlignContent::parse(input)?;
+        let justify = match input.try_parse(ui::JustifyContent::parse) {
+            Ok(justify) => justify,
+            Err(_) => match align {
+                ui::AlignContent::FlexStart => ui::JustifyContent::FlexStart,
+                ui::AlignContent::FlexEnd => ui::JustifyContent::FlexEnd,
+                ui::AlignContent::Center => ui::JustifyContent::Center,
+                // `justify-content: stretch` behaves as `flex-start` in a flex box
+                ui::AlignContent::Stretch => ui::JustifyContent::FlexStart,
+                ui::AlignContent::SpaceBetween => ui::JustifyContent::SpaceBetween,
+                ui::AlignContent::SpaceAround => ui::JustifyContent::SpaceAround,
+            },
+        };
+        Ok(Self { align, justify })
+    }
+}
+
+impl ToCss for PlaceContent {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        write!(dest, "{} {}", self.align.css_keyword(), self.justify.css_keyword())
+    }
+}
+
+/// A `<self-position>` (or `left` / `right`), for where an item is justified in its container.
+/// See also: https://drafts.csswg.org/css-align-3/#typedef-self-position
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelfPosition {
+    Center,
+    Start,
+    End,
+    SelfStart,
+    SelfEnd,
+    FlexStart,
+    FlexEnd,
+    Left,
+    Right,
+}
+
+impl Parse for SelfPosition {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { ident,
+            "center" => Self::Center,
+            "start" => Self::Start,
+            "end" => Self::End,
+            "self-start" => Self::SelfStart,
+            "self-end" => Self::SelfEnd,
+            "flex-start" => Self::FlexStart,
+            "flex-end" => Self::FlexEnd,
+            "left" => Self::Left,
+            "right" => Self::Right,
+            _ => return Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidValue(ident.clone(), None)
+            ))
+        })
+    }
+}
+
+/// A `justify-items` value.  Bevy has no `justify-items`, so these are only parsed to be warned
+/// about, and don't keep the `first` / `last` of a baseline, the `safe` / `unsafe` of a position, or
+/// the direction of `legacy`.
+/// See also: https://drafts.csswg.org/css-align-3/#justify-items-property
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JustifyItems {
+    Normal,
+    Stretch,
+    Baseline,
+    Legacy,
+    Position(SelfPosition),
+}
+
+impl Parse for JustifyItems {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if try_keyword(input, &["normal"]) {
+            return Ok(Self::Normal)
+        }
+        if try_keyword(input, &["stretch"]) {
+            return Ok(Self::Stretch)
+        }
+        // `legacy && [ left | right | center ]?`
+        if try_keyword(input, &["legacy"]) {
+            try_keyword(input, &["left", "right", "center"]);
+            return Ok(Self::Legacy)
+        }
+        let legacy_last = input.try_parse(|input| {
+            match try_keyword(input, &["left", "right", "center"]) && try_keyword(input, &["legacy"]) {
+                true => Ok(()),
+                false => Err(()),
+            }
+        });
+        if legacy_last.is_ok() {
+            return Ok(Self::Legacy)
+        }
+        if input.try_parse(parse_baseline).is_ok() {
+            return Ok(Self::Baseline)
+        }
+        parse_position(input).map(Self::Position)
+    }
+}
+
+/// A `justify-self` value, which (as for `JustifyItems`) is only parsed to be warned about.
+/// See also: https://drafts.csswg.org/css-align-3/#justify-self-property
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JustifySelf {
+    Auto,
+    Normal,
+    Stretch,
+    Baseline,
+    Position(SelfPosition),
+}
+
+impl Parse for JustifySelf {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if try_keyword(input, &["auto"]) {
+            return Ok(Self::Auto)
+        }
+        if try_keyword(input, &["normal"]) {
+            return Ok(Self::Normal)
+        }
+        if try_keyword(input, &["stretch"]) {
+            return Ok(Self::Stretch)
+        }
+        if input.try_parse(parse_baseline).is_ok() {
+            return Ok(Self::Baseline)
+        }
+        parse_position(input).map(Self::Position)
+    }
+}
+
+/// Whether the next token is one of the `keywords` (ignoring case), in which case it is consumed
+fn try_keyword(input: &mut Parser, keywords: &[&str]) -> bool {
+    input.try_parse(|input| match input.expect_ident() {
+        Ok(ident) if keywords.iter().any(|keyword| ident.eq_ignore_ascii_case(keyword)) => Ok(()),
+        _ => Err(()),
+    }).is_ok()
+}
+
+/// `[ first | last ]? baseline`
+fn parse_baseline<'i, 't>(input: &mut Parser<'i, 't>) -> Result<(), BevyCssParsingError<'i>> {
+    try_keyword(input, &["first", "last"]);
+    input.expect_ident_matching("baseline")?;
+    Ok(())
+}
+
+/// `[ safe | unsafe ]? <self-position>`
+fn parse_position<'i, 't>(input: &mut Parser<'i, 't>) -> Result<SelfPosition, BevyCssParsingError<'i>> {
+    try_keyword(input, &["safe", "unsafe"]);
+    SelfPosition::parse(input)
+}
+
+/// The values of the `place-items` shorthand, for `align-items` then `justify-items`.  Bevy has no
+/// `justify-items`, so it is only kept if it was given (i.e. to be warned about).
+/// See also: https://drafts.csswg.org/css-align-3/#place-items-property
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlaceItems {
+    pub align: ui::AlignItems,
+    pub justify: Option<JustifyItems>,
+}
+
+impl Parse for PlaceItems {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let align = ui::AlignItems::parse(input)?;
+        let justify = input.try_parse(JustifyItems::parse).ok();
+        Ok(Self { align, justify })
+    }
+}
+
+/// The values of the `place-self` shorthand, for `align-self` then `justify-self`.  Bevy has no
+/// `justify-self`, so it is only kept if it was given (i.e. to be warned about).
+/// See also: https://drafts.csswg.org/css-align-3/#place-self-property
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlaceSelf {
+    pub align: ui::AlignSelf,
+    pub justify: Option<JustifySelf>,
+}
+
+impl Parse for PlaceSelf {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let align = ui::AlignSelf::parse(input)?;
+        let justify = input.try_parse(JustifySelf::parse).ok();
+        Ok(Self { align, justify })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::ParserInput;
+    use super::*;
+
+    fn parse_entirely<P: Parse>(css: &str) -> Result<P, BevyCssParsingError> {
+        let mut parser_input = ParserInput::new(css);
+        let mut input = Parser::new(&mut parser_input);
+        input.parse_entirely(P::parse)
+    }
+
+    #[test]
+    fn test_place_content() {
+        assert_eq!(
+            PlaceContent::parse_str("center space-between").unwrap(),
+            PlaceContent { align: ui::AlignContent::Center, justify: ui::JustifyContent::SpaceBetween }
+        );
+        assert_eq!(
+            PlaceContent::parse_str("space-around").unwrap(),
+            PlaceContent { align: ui::AlignContent::SpaceAround, justify: ui::JustifyContent::SpaceAround }
+        );
+        assert_eq!(
+            PlaceContent::parse_str("stretch").unwrap(),
+            PlaceContent { align: ui::AlignContent::Stretch, justify: ui::JustifyContent::FlexStart }
+        );
+        assert!(parse_entirely::<PlaceContent>("center stretch").is_err());
+        assert!(PlaceContent::parse_str("space-evenly").is_err());
+        assert!(PlaceContent::parse_str("").is_err());
+    }
+
+    #[test]
+    fn test_place_items_and_self() {
+        assert_eq!(
+            PlaceItems::parse_str("center").unwrap(),
+            PlaceItems { align: ui::AlignItems::Center, justify: None }
+        );
+        assert_eq!(
+            PlaceItems::parse_str("baseline flex-end").unwrap(),
+            PlaceItems {
+                align: ui::AlignItems::Baseline,
+                justify: Some(JustifyItems::Position(SelfPosition::FlexEnd)),
+            }
+        );
+        assert_eq!(
+            PlaceSelf::parse_str("auto center").unwrap(),
+            PlaceSelf { align: ui::AlignSelf::Auto, justify: Some(JustifySelf::Position(SelfPosition::Center)) }
+        );
+        assert!(PlaceItems::parse_str("auto").is_err());
+        assert!(parse_entirely::<PlaceSelf>("center center center").is_err());
+        // The second value has the `justify-` grammar, rather than the `align-` one
+        assert_eq!(
+            PlaceItems::parse_str("center left").unwrap().justify,
+            Some(JustifyItems::Position(SelfPosition::Left))
+        );
+        assert_eq!(PlaceSelf::parse_str("center auto").unwrap().justify, Some(JustifySelf::Auto));
+        assert!(parse_entirely::<PlaceItems>("center auto").is_err());
+        assert!(parse_entirely::<PlaceSelf>("center legacy").is_err());
+    }
+
+    #[test]
+    fn test_justify_items_and_self() {
+        for (css, expected) in [
+            ("normal", JustifyItems::Normal),
+            ("stretch", JustifyItems::Stretch),
+            ("last baseline", JustifyItems::Baseline),
+            ("legacy", JustifyItems::Legacy),
+            ("legacy right", JustifyItems::Legacy),
+            ("center legacy", JustifyItems::Legacy),
+            ("safe end", JustifyItems::Position(SelfPosition::End)),
+            ("self-start", JustifyItems::Position(SelfPosition::SelfStart)),
+        ] {
+            assert_eq!(parse_entirely::<JustifyItems>(css).unwrap(), expected, "{}", css);
+        }
+        for (css, expected) in [
+            ("auto", JustifySelf::Auto),
+            ("first baseline", JustifySelf::Baseline),
+            ("unsafe right", JustifySelf::Position(SelfPosition::Right)),
+        ] {
+            assert_eq!(parse_entirely::<JustifySelf>(css).unwrap(), expected, "{}", css);
+        }
+        assert!(parse_entirely::<JustifyItems>("auto").is_err());
+        assert!(parse_entirely::<JustifyItems>("safe").is_err());
+        assert!(parse_entirely::<JustifySelf>("legacy").is_err());
+        assert!(parse_entirely::<JustifySelf>("space-between").is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,119 @@
+use std::{f32::consts::TAU, fmt};
+use cssparser::{
+    CowRcStr, Parser, ToCss, Token,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::Parse,
+};
+
+/// An angle in the given units
+/// See also: https://drafts.csswg.org/css-values-3/#angles
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Angle {
+    Deg(f32),               // degrees (360 in a full circle)
+    Grad(f32),              // gradians (400 in a full circle)
+    Rad(f32),               // radians (2π in a full circle)
+    Turn(f32),              // turns (1 in a full circle)
+}
+
+impl Angle {
+    #[inline]
+    pub fn to_degrees(&self) -> f32 {
+        match *self {
+            Self::Deg(v) => v,
+            Self::Grad(v) => v * (360.0 / 400.0),
+            Self::Rad(v) => v.to_degrees(),
+            Self::Turn(v) => v * 360.0,
+        }
+    }
+
+    #[inline]
+    pub fn to_radians(&self) -> f32 {
+        match *self {
+            Self::Deg(v) => v.to_radians(),
+            Self::Grad(v) => v * (TAU / 400.0),
+            Self::Rad(v) => v,
+            Self::Turn(v) => v * TAU,
+        }
+    }
+
+    pub fn parse_dimension<'i>(
+        unit: &CowRcStr<'i>,
+        value: f32
+    ) -> Result<Self, BevyCssParsingErrorKind<'i>> {
+        Ok(match_ignore_ascii_case! { unit,
+            "deg"  => Self::Deg(value),
+            "grad" => Self::Grad(value),
+            "rad"  => Self::Rad(value),
+            "turn" => Self::Turn(value),
+            _ => return Err(BevyCssParsingErrorKind::UnexpectedDimension(unit.clone()))
+        })
+    }
+}
+
+impl Parse for Angle {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let token = input.next()?;
+        match *token {
+            Token::Dimension { value, ref unit, .. } =>
+                Self::parse_dimension(unit, value).map_err(|err| start.new_custom_error(err)),
+            // Apart from zero, a bare number (i.e. no dimension) is not allowed here
+            Token::Number { value, .. } if value == 0.0 => Ok(Self::Deg(0.0)),
+            Token::Number { .. } =>
+                Err(start.new_custom_error(BevyCssParsingErrorKind::MissingDimension(token.clone()))),
+            _ => Err(start.new_unexpected_token_error(token.clone()))
+        }
+    }
+}
+
+impl ToCss for Angle {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Deg(v) => write!(dest, "{}deg", v),
+            Self::Grad(v) => write!(dest, "{}grad", v),
+            Self::Rad(v) => write!(dest, "{}rad", v),
+            Self::Turn(v) => write!(dest, "{}turn", v),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::ParseErrorKind;
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Angle::parse_str("45deg").unwrap(), Angle::Deg(45.0));
+        assert_eq!(Angle::parse_str("50GRAD").unwrap(), Angle::Grad(50.0));
+        assert_eq!(Angle::parse_str("1.5rad").unwrap(), Angle::Rad(1.5));
+        assert_eq!(Angle::parse_str("-0.25turn").unwrap(), Angle::Turn(-0.25));
+        assert_eq!(Angle::parse_str("0").unwrap(), Angle::Deg(0.0));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            Angle::parse_str("45").unwrap_err().kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::MissingDimension(_))
+        ));
+        assert!(matches!(
+            Angle::parse_str("45px").unwrap_err().kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::UnexpectedDimension(_))
+        ));
+        assert!(Angle::parse_str("45%").is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let quarter = [Angle::Deg(90.0), Angle::Grad(100.0), Angle::Rad(TAU / 4.0), Angle::Turn(0.25)];
+        for angle in quarter {
+            assert!((angle.to_degrees() - 90.0).abs() < 1e-4, "{:?}", angle);
+            assert!((angle.to_radians() - TAU / 4.0).abs() < 1e-6, "{:?}", angle);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,262 @@
+use std::fmt;
+use cssparser::{
+    Parser, ToCss, Token,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+    serialize_identifier, serialize_string,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::{
+        easing::EasingFunction,
+        parse::AllowedValues,
+        time::Time,
+        Number, Parse,
+    },
+};
+
+/// The name of an `@keyframes` rule (or of the keyframes an `animation` uses), an identifier
+/// (except `none` & the css-wide keywords) or a string
+/// See also: https://drafts.csswg.org/css-animations/#typedef-keyframes-name
+pub fn parse_keyframes_name<'i, 't>(input: &mut Parser<'i, 't>) -> Result<String, BevyCssParsingError<'i>> {
+    let start = input.current_source_location();
+    match *input.next()? {
+        Token::QuotedString(ref name) => Ok(name.to_string()),
+        Token::Ident(ref name) => match_ignore_ascii_case! { name,
+            "none" | "initial" | "inherit" | "unset" | "default" =>
+                Err(start.new_custom_error(BevyCssParsingErrorKind::InvalidKeyword(name.clone()))),
+            _ => Ok(name.to_string()),
+        },
+        ref token => Err(start.new_unexpected_token_error(token.clone())),
+    }
+}
+
+/// How many times an animation runs
+/// See also: https://drafts.csswg.org/css-animations/#animation-iteration-count
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IterationCount {
+    /// Can be fractional, e.g. `0.5` stops half way through the keyframes
+    Count(f32),
+    Infinite,
+}
+
+impl Default for IterationCount {
+    fn default() -> Self {
+        Self::Count(1.0)
+    }
+}
+
+impl Parse for IterationCount {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("infinite")).is_ok() {
+            return Ok(Self::Infinite)
+        }
+        Number::parse_internal(input, AllowedValues::NonNegative).map(|count| Self::Count(count.0))
+    }
+}
+
+impl ToCss for IterationCount {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Count(count) => write!(dest, "{}", count),
+            Self::Infinite => dest.write_str("infinite"),
+        }
+    }
+}
+
+/// The value of the `animation` property, e.g. `pulse 1s infinite`.  The parts can be given in any
+/// order, but (as for transitions) the first time is always the duration & the second the delay.
+/// Only a single animation is supported, and the other `animation-*` parts (e.g. the direction or
+/// fill mode) are not.
+/// See also: https://drafts.csswg.org/css-animations/#animation
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnimationSpec {
+    /// The name of the `@keyframes` to run
+    pub name: String,
+    pub duration: Time,
+    pub timing_function: EasingFunction,
+    pub iteration_count: IterationCount,
+    pub delay: Time,
+}
+
+impl AnimationSpec {
+    /// A new animation of the named keyframes, with the initial values for everything else (i.e.
+    /// `0s ease 1 0s`)
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            duration: Time::default(),
+            timing_function: EasingFunction::default(),
+            iteration_count: IterationCount::default(),
+            delay: Time::default(),
+        }
+    }
+
+    /// How long until the animation has finished, inc. the delay (which is infinite for an
+    /// `infinite` animation, unless it has no duration)
+    pub fn total_duration(&self) -> f32 {
+        match self.iteration_count {
+            _ if self.duration.seconds() <= 0.0 => self.delay.seconds(),
+            IterationCount::Count(count) => self.delay.seconds() + count * self.duration.seconds(),
+            IterationCount::Infinite => f32::INFINITY,
+        }
+    }
+
+    /// How far through the keyframes (from `0` to `1`) the animation is, `elapsed` seconds after it
+    /// started.  `None` before the delay is over, or once the animation has finished.  The timing
+    /// function is not applied, as it eases each pair of keyframes separately.
+    pub fn progress(&self, elapsed: f32) -> Option<f32> {
+        let active = elapsed - self.delay.seconds();
+        if active < 0.0 || elapsed >= self.total_duration() {
+            return None
+        }
+        let iterations = active / self.duration.seconds();
+        Some(iterations.fract())
+    }
+}
+
+impl Parse for AnimationSpec {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let mut name = None;
+        let mut duration = None;
+        let mut timing_function = None;
+        let mut iteration_count = None;
+        let mut delay = None;
+        loop {
+            if delay.is_none() {
+                let start = input.current_source_location();
+                if let Ok(time) = input.try_parse(Time::parse) {
+                    if duration.is_none() {
+                        // Unlike the delay, the duration can't be negative
+                        if time.seconds() < 0.0 {
+                            return Err(start.new_custom_error(BevyCssParsingErrorKind::InvalidValue(
+                                "animation durations can't be negative".into(),
+                                None,
+                            )))
+                        }
+                        duration = Some(time);
+                    } else {
+                        delay = Some(time);
+                    }
+                    continue
+                }
+            }
+            // Keywords (e.g. `linear` or `infinite`) are tried before the name
+            if timing_function.is_none() {
+                if let Ok(value) = input.try_parse(EasingFunction::parse) {
+                    timing_function = Some(value);
+                    continue
+                }
+            }
+            if iteration_count.is_none() {
+                if let Ok(value) = input.try_parse(IterationCount::parse) {
+                    iteration_count = Some(value);
+                    continue
+                }
+            }
+            if name.is_none() {
+                if let Ok(value) = input.try_parse(parse_keyframes_name) {
+                    name = Some(value);
+                    continue
+                }
+            }
+            break
+        }
+        // Without the name, there is nothing to animate
+        let default = Self::new(match name {
+            Some(name) => name,
+            None => return Err(input.new_error_for_next_token()),
+        });
+        Ok(Self {
+            duration: duration.unwrap_or(default.duration),
+            timing_function: timing_function.unwrap_or(default.timing_function),
+            iteration_count: iteration_count.unwrap_or(default.iteration_count),
+            delay: delay.unwrap_or(default.delay),
+            ..default
+        })
+    }
+}
+
+impl ToCss for AnimationSpec {
+    /// The iteration count & delay are only written when they aren't their initial values
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        // A name that is also a keyword (e.g. `ease`) has to be a string, to be parsed as the name
+        let is_keyword = EasingFunction::parse_str(&self.name).is_ok()
+            || IterationCount::parse_str(&self.name).is_ok();
+        if is_keyword {
+            serialize_string(&self.name, dest)?;
+        } else {
+            serialize_identifier(&self.name, dest)?;
+        }
+        dest.write_char(' ')?;
+        self.duration.to_css(dest)?;
+        dest.write_char(' ')?;
+        self.timing_function.to_css(dest)?;
+        if self.iteration_count != IterationCount::default() {
+            dest.write_char(' ')?;
+            self.iteration_count.to_css(dest)?;
+        }
+        if self.delay != Time::default() {
+            dest.write_char(' ')?;
+            self.delay.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::values::easing::StepPosition;
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            AnimationSpec::parse_str("pulse 1s infinite").unwrap(),
+            AnimationSpec {
+                duration: Time(1.0),
+                iteration_count: IterationCount::Infinite,
+                ..AnimationSpec::new("pulse")
+            }
+        );
+        // Any order, with the second time being the delay
+        assert_eq!(
+            AnimationSpec::parse_str("2.5 step-end 200ms \"fade in\" -1s").unwrap(),
+            AnimationSpec {
+                name: "fade in".to_string(),
+                duration: Time(0.2),
+                timing_function: EasingFunction::Steps(1, StepPosition::JumpEnd),
+                iteration_count: IterationCount::Count(2.5),
+                delay: Time(-1.0),
+            }
+        );
+        assert_eq!(AnimationSpec::parse_str("spin").unwrap(), AnimationSpec::new("spin"));
+        assert_eq!(Option::<AnimationSpec>::parse_str("none").unwrap(), None);
+        assert!(AnimationSpec::parse_str("1s linear").is_err());
+        assert!(AnimationSpec::parse_str("spin -1s").is_err());
+        assert!(AnimationSpec::parse_str("initial 1s").is_err());
+    }
+
+    #[test]
+    fn test_progress() {
+        let spec = AnimationSpec::parse_str("pulse 2s 1.5 1s").unwrap();
+        assert_eq!(spec.total_duration(), 4.0);
+        assert_eq!(spec.progress(0.5), None);
+        assert_eq!(spec.progress(1.0), Some(0.0));
+        assert_eq!(spec.progress(2.0), Some(0.5));
+        assert_eq!(spec.progress(3.5), Some(0.25));
+        assert_eq!(spec.progress(4.0), None);
+        let spec = AnimationSpec::parse_str("pulse 2s infinite").unwrap();
+        assert_eq!(spec.progress(101.0), Some(0.5));
+        assert_eq!(AnimationSpec::parse_str("pulse infinite").unwrap().progress(1.0), None);
+    }
+
+    #[test]
+    fn test_to_css() {
+        let to_css = |css| AnimationSpec::parse_str(css).unwrap().to_css_string();
+        assert_eq!(to_css("pulse 1s"), "pulse 1s ease");
+        assert_eq!(to_css("pulse 1s infinite linear 0.5s"), "pulse 1s linear infinite 0.5s");
+        assert_eq!(to_css("'ease' 1s"), "\"ease\" 1s ease");
+    }
+}
\ No newline at end of file
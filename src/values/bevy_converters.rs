@@ -2,7 +2,11 @@ use bevy::{ui};
 use bevy::reflect::Reflect;
 use crate::{
     context::CssContext,
-    values::{LengthPercentage, LengthPercentageOrAuto, SidedValue}
+    values::{
+        generic::MaybeInitial,
+        percentage::Percentage,
+        LengthPercentage, LengthPercentageOrAuto, NonNegativeLengthPercentage, SidedValue, Size,
+    },
 };
 
 /// Very similar to the standard library `From<T>` trait, but allows the `::from()` function to take
@@ -21,15 +25,49 @@ impl<T, U: ContextualFrom<T>> ContextualInto<U> for T {
     }
 }
 
+impl ContextualFrom<LengthPercentage> for ui::Val {
+    fn contextual_from(context: &CssContext, len_pc: LengthPercentage) -> Self {
+        match len_pc {
+            // ui::Val::Percent takes values of 0.0 to 100.0 (not 0.0 to 1.0)
+            LengthPercentage::Percentage(pc) => ui::Val::Percent(pc.as_number()),
+            LengthPercentage::Length(len) => ui::Val::Px(len.to_computed_px(context))
+        }
+    }
+}
+
+impl ContextualFrom<NonNegativeLengthPercentage> for ui::Val {
+    #[inline]
+    fn contextual_from(context: &CssContext, len_pc: NonNegativeLengthPercentage) -> Self {
+        len_pc.0.contextual_into(context)
+    }
+}
+
 impl ContextualFrom<LengthPercentageOrAuto> for ui::Val {
     fn contextual_from(context: &CssContext, len: LengthPercentageOrAuto) -> Self {
         match len {
             LengthPercentageOrAuto::Auto => ui::Val::Auto,
-            LengthPercentageOrAuto::NotAuto(len_pc) => match len_pc {
-                // ui::Val::Percent takes values of 0.0 to 100.0 (not 0.0 to 1.0)
-                LengthPercentage::Percentage(pc) => ui::Val::Percent(pc.as_number()),
-                LengthPercentage::Length(len) => ui::Val::Px(len.to_computed_px(context))
-            },
+            LengthPercentageOrAuto::NotAuto(len_pc) => len_pc.contextual_into(context),
+        }
+    }
+}
+
+impl ContextualFrom<Size> for ui::Val {
+    fn contextual_from(context: &CssContext, size: Size) -> Self {
+        match size {
+            // @todo Bevy 0.8 has no intrinsic sizing, so these are all treated as `auto`, which is
+            //   the closest match (i.e. sized by the content).  `auto` maximum sizes are ignored.
+            Size::Intrinsic(_) => ui::Val::Auto,
+            Size::NotIntrinsic(len) => len.contextual_into(context),
+        }
+    }
+}
+
+/// `initial` is the Bevy default, e.g. `ui::Val::Undefined` for a side of the `margin`
+impl<T, U: Default + ContextualFrom<T>> ContextualFrom<MaybeInitial<T>> for U {
+    fn contextual_from(context: &CssContext, value: MaybeInitial<T>) -> Self {
+        match value {
+            MaybeInitial::Initial => U::default(),
+            MaybeInitial::NotInitial(value) => value.contextual_into(context),
         }
     }
 }
@@ -47,4 +85,22 @@ where
             left: sided_value.left.contextual_into(context),
         }
     }
+}
+
+/// The other way round to `ContextualFrom<LengthPercentageOrAuto>`, for writing a `ui::Style` back
+/// as css.  Lengths are always in `px`, and `ui::Val::Undefined` has no css value so can't be
+/// converted.
+impl TryFrom<ui::Val> for LengthPercentageOrAuto {
+    type Error = ();
+
+    fn try_from(val: ui::Val) -> Result<Self, Self::Error> {
+        match val {
+            ui::Val::Undefined => Err(()),
+            ui::Val::Auto => Ok(LengthPercentageOrAuto::Auto),
+            ui::Val::Px(px) => Ok(LengthPercentageOrAuto::NotAuto(LengthPercentage::from(px))),
+            // ui::Val::Percent takes values of 0.0 to 100.0 (not 0.0 to 1.0)
+            ui::Val::Percent(pc) =>
+                Ok(LengthPercentageOrAuto::NotAuto(LengthPercentage::from(Percentage::new(pc / 100.0)))),
+        }
+    }
 }
\ No newline at end of file
@@ -1,16 +1,23 @@
+use std::fmt;
 use bevy::{
     prelude::Color,
     ui
 };
 use cssparser::{
-    Parser,
+    Parser, Token,
     match_ignore_ascii_case, _cssparser_internal_to_lowercase,
 };
 use crate::{
     errors::{BevyCssParsingError, BevyCssParsingErrorKind},
-    values::Parse,
+    values::{color, Parse},
 };
 
+/// The css keyword of a Bevy enum value, i.e. the keyword that parses to it.  (`cssparser::ToCss`
+/// can't be implemented for Bevy's types outside of either crate.)
+pub trait CssKeyword {
+    fn css_keyword(&self) -> &'static str;
+}
+
 impl Parse for ui::Display {
     fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
         let start = input.current_source_location();
@@ -179,18 +186,162 @@ impl Parse for Color {
         if none_keyword.is_ok() {
             return Ok(Color::NONE)
         }
+        // `cssparser` accepts 3, 4, 6, & 8 digit hex colors, but only gives a generic token error
+        // for any other length.  Handle hex colors here to give a more useful error.
+        let state = input.state();
+        let token = input.next()?;
+        if let Token::Hash(ref value) | Token::IDHash(ref value) = *token {
+            return match cssparser::Color::parse_hash(value.as_bytes()) {
+                Ok(cssparser::Color::RGBA(rgba)) => Ok(rgba_to_color(rgba)),
+                _ => Err(start.new_custom_error(
+                    BevyCssParsingErrorKind::InvalidValue(value.clone(), Some(token.clone()))
+                )),
+            }
+        }
+        if let Token::Function(ref name) = *token {
+            let name = name.clone();
+            if let Some(rgba) = color::parse_extra_function(&name, input)? {
+                return Ok(rgba_to_color(rgba))
+            }
+            color::check_argument_count(start, &name, input)?;
+        }
+        input.reset(&state);
         match cssparser::Color::parse(input)? {
             cssparser::Color::CurrentColor => Err(start.new_custom_error(
                 BevyCssParsingErrorKind::InvalidKeyword("currentcolor".into())
             )),
-            cssparser::Color::RGBA(rgba) => Ok(
-                Color::rgba(
-                    rgba.red_f32(),
-                    rgba.green_f32(),
-                    rgba.blue_f32(),
-                    rgba.alpha_f32()
-                )
-            ),
+            cssparser::Color::RGBA(rgba) => Ok(rgba_to_color(rgba)),
         }
     }
+}
+
+#[inline]
+fn rgba_to_color(rgba: cssparser::RGBA) -> Color {
+    Color::rgba(
+        rgba.red_f32(),
+        rgba.green_f32(),
+        rgba.blue_f32(),
+        rgba.alpha_f32()
+    )
+}
+
+impl CssKeyword for ui::Display {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::Display::Flex => "flex",
+            ui::Display::None => "none",
+        }
+    }
+}
+
+impl CssKeyword for ui::PositionType {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::PositionType::Relative => "relative",
+            ui::PositionType::Absolute => "absolute",
+        }
+    }
+}
+
+impl CssKeyword for ui::Direction {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::Direction::Inherit => "inherit",
+            ui::Direction::LeftToRight => "ltr",
+            ui::Direction::RightToLeft => "rtl",
+        }
+    }
+}
+
+impl CssKeyword for ui::FlexDirection {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::FlexDirection::Row => "row",
+            ui::FlexDirection::Column => "column",
+            ui::FlexDirection::RowReverse => "row-reverse",
+            ui::FlexDirection::ColumnReverse => "column-reverse",
+        }
+    }
+}
+
+impl CssKeyword for ui::FlexWrap {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::FlexWrap::NoWrap => "nowrap",
+            ui::FlexWrap::Wrap => "wrap",
+            ui::FlexWrap::WrapReverse => "wrap-reverse",
+        }
+    }
+}
+
+impl CssKeyword for ui::AlignItems {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::AlignItems::FlexStart => "flex-start",
+            ui::AlignItems::FlexEnd => "flex-end",
+            ui::AlignItems::Center => "center",
+            ui::AlignItems::Baseline => "baseline",
+            ui::AlignItems::Stretch => "stretch",
+        }
+    }
+}
+
+impl CssKeyword for ui::AlignSelf {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::AlignSelf::Auto => "auto",
+            ui::AlignSelf::FlexStart => "flex-start",
+            ui::AlignSelf::FlexEnd => "flex-end",
+            ui::AlignSelf::Center => "center",
+            ui::AlignSelf::Baseline => "baseline",
+            ui::AlignSelf::Stretch => "stretch",
+        }
+    }
+}
+
+impl CssKeyword for ui::AlignContent {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::AlignContent::FlexStart => "flex-start",
+            ui::AlignContent::FlexEnd => "flex-end",
+            ui::AlignContent::Center => "center",
+            ui::AlignContent::Stretch => "stretch",
+            ui::AlignContent::SpaceBetween => "space-between",
+            ui::AlignContent::SpaceAround => "space-around",
+        }
+    }
+}
+
+impl CssKeyword for ui::JustifyContent {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::JustifyContent::FlexStart => "flex-start",
+            ui::JustifyContent::FlexEnd => "flex-end",
+            ui::JustifyContent::Center => "center",
+            ui::JustifyContent::SpaceBetween => "space-between",
+            ui::JustifyContent::SpaceAround => "space-around",
+            ui::JustifyContent::SpaceEvenly => "space-evenly",
+        }
+    }
+}
+
+impl CssKeyword for ui::Overflow {
+    fn css_keyword(&self) -> &'static str {
+        match *self {
+            ui::Overflow::Visible => "visible",
+            ui::Overflow::Hidden => "hidden",
+        }
+    }
+}
+
+/// Writes the color as a hex color, i.e. `#rrggbb`, or `#rrggbbaa` if it isn't opaque.  Parsed
+/// colors only have 8 bits per channel, so they are written without any loss.
+pub fn color_to_css<W>(color: &Color, dest: &mut W) -> fmt::Result where W: fmt::Write {
+    let [red, green, blue, alpha] = color.as_rgba_f32();
+    let to_u8 = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+    write!(dest, "#{:02x}{:02x}{:02x}", to_u8(red), to_u8(green), to_u8(blue))?;
+    if to_u8(alpha) != u8::MAX {
+        write!(dest, "{:02x}", to_u8(alpha))?;
+    }
+    Ok(())
 }
\ No newline at end of file
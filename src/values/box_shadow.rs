@@ -0,0 +1,209 @@
+use std::fmt;
+use cssparser::{Parser, ToCss};
+use crate::{
+    errors::BevyCssParsingError,
+    values::{
+        length::NonNegativeLength,
+        ColorOrCurrentColor, Length, Parse,
+    },
+};
+
+/// A single shadow of the `box-shadow` property.  The offsets, blur & spread radius, color, and the
+/// `inset` keyword can be given in any order, as long as the lengths are kept together.
+/// See also: https://drafts.csswg.org/css-backgrounds/#box-shadow
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoxShadow {
+    pub inset: bool,
+    pub offset_x: Length,
+    pub offset_y: Length,
+    pub blur_radius: Option<NonNegativeLength>,
+    pub spread_radius: Option<Length>,
+    /// Where no color is given, `currentcolor` is used
+    pub color: Option<ColorOrCurrentColor>,
+}
+
+impl BoxShadow {
+    fn parse_lengths<'i, 't>(
+        input: &mut Parser<'i, 't>
+    ) -> Result<(Length, Length, Option<NonNegativeLength>, Option<Length>), BevyCssParsingError<'i>> {
+        let offset_x = Length::parse(input)?;
+        let offset_y = Length::parse(input)?;
+        let blur_radius = input.try_parse(NonNegativeLength::parse).ok();
+        let spread_radius = match blur_radius {
+            // The spread radius can only be given after the blur radius
+            Some(_) => input.try_parse(Length::parse).ok(),
+            None => None,
+        };
+        Ok((offset_x, offset_y, blur_radius, spread_radius))
+    }
+}
+
+impl Parse for BoxShadow {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let mut inset = false;
+        let mut lengths = None;
+        let mut color = None;
+        loop {
+            if !inset && input.try_parse(|i| i.expect_ident_matching("inset")).is_ok() {
+                inset = true;
+                continue
+            }
+            if lengths.is_none() {
+                if let Ok(value) = input.try_parse(Self::parse_lengths) {
+                    lengths = Some(value);
+                    continue
+                }
+            }
+            if color.is_none() {
+                if let Ok(value) = input.try_parse(ColorOrCurrentColor::parse) {
+                    color = Some(value);
+                    continue
+                }
+            }
+            break
+        }
+        let (offset_x, offset_y, blur_radius, spread_radius) = match lengths {
+            Some(lengths) => lengths,
+            // The offsets are the only required part of a shadow
+            None => return Err(input.new_error_for_next_token()),
+        };
+        Ok(Self { inset, offset_x, offset_y, blur_radius, spread_radius, color, })
+    }
+}
+
+impl ToCss for BoxShadow {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        if self.inset {
+            dest.write_str("inset ")?;
+        }
+        self.offset_x.to_css(dest)?;
+        dest.write_char(' ')?;
+        self.offset_y.to_css(dest)?;
+        if let Some(ref blur_radius) = self.blur_radius {
+            dest.write_char(' ')?;
+            blur_radius.to_css(dest)?;
+        }
+        if let Some(ref spread_radius) = self.spread_radius {
+            dest.write_char(' ')?;
+            spread_radius.to_css(dest)?;
+        }
+        if let Some(ref color) = self.color {
+            dest.write_char(' ')?;
+            color.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Color;
+    use cssparser::ParserInput;
+    use crate::values::{
+        generic::{CommaSeparated, NonNegative},
+        length::FontRelativeLength,
+    };
+    use super::*;
+
+    fn shadow(offset_x: f32, offset_y: f32) -> BoxShadow {
+        BoxShadow {
+            inset: false,
+            offset_x: Length::from(offset_x),
+            offset_y: Length::from(offset_y),
+            blur_radius: None,
+            spread_radius: None,
+            color: None,
+        }
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(BoxShadow::parse_str("2px 3px").unwrap(), shadow(2.0, 3.0));
+        assert_eq!(BoxShadow::parse_str("-2px 0").unwrap(), shadow(-2.0, 0.0));
+        assert_eq!(
+            BoxShadow::parse_str("1em 2px").unwrap().offset_x,
+            Length::from(FontRelativeLength::Em(1.0))
+        );
+    }
+
+    #[test]
+    fn test_blur_and_spread() {
+        assert_eq!(
+            BoxShadow::parse_str("2px 2px 4px").unwrap(),
+            BoxShadow { blur_radius: Some(NonNegative(Length::from(4.0))), ..shadow(2.0, 2.0) }
+        );
+        assert_eq!(
+            BoxShadow::parse_str("2px 2px 4px -1px").unwrap(),
+            BoxShadow {
+                blur_radius: Some(NonNegative(Length::from(4.0))),
+                spread_radius: Some(Length::from(-1.0)),
+                ..shadow(2.0, 2.0)
+            }
+        );
+        // The blur radius can't be negative
+        let mut parser_input = ParserInput::new("2px 2px -4px");
+        let mut input = Parser::new(&mut parser_input);
+        assert!(input.parse_entirely(BoxShadow::parse).is_err());
+    }
+
+    #[test]
+    fn test_color() {
+        let black_shadow = BoxShadow {
+            blur_radius: Some(NonNegative(Length::from(4.0))),
+            color: Some(ColorOrCurrentColor::Color(Color::BLACK)),
+            ..shadow(2.0, 2.0)
+        };
+        assert_eq!(BoxShadow::parse_str("2px 2px 4px black").unwrap(), black_shadow);
+        assert_eq!(BoxShadow::parse_str("black 2px 2px 4px").unwrap(), black_shadow);
+        assert_eq!(
+            BoxShadow::parse_str("2px 2px currentcolor").unwrap().color,
+            Some(ColorOrCurrentColor::CurrentColor)
+        );
+    }
+
+    #[test]
+    fn test_inset() {
+        let inset_shadow = BoxShadow {
+            inset: true,
+            color: Some(ColorOrCurrentColor::Color(Color::rgb_u8(255, 0, 0))),
+            ..shadow(1.0, 1.0)
+        };
+        assert_eq!(BoxShadow::parse_str("inset 1px 1px red").unwrap(), inset_shadow);
+        assert_eq!(BoxShadow::parse_str("1px 1px red inset").unwrap(), inset_shadow);
+        assert_eq!(BoxShadow::parse_str("red inset 1px 1px").unwrap(), inset_shadow);
+    }
+
+    #[test]
+    fn test_list() {
+        let shadows = CommaSeparated::<BoxShadow>::parse_str("1px 1px, inset 2px 2px blue").unwrap();
+        assert_eq!(shadows.0.len(), 2);
+        assert_eq!(shadows.0[0], shadow(1.0, 1.0));
+        assert!(shadows.0[1].inset);
+        assert_eq!(
+            Option::<CommaSeparated<BoxShadow>>::parse_str("none").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_two_shadow_list() {
+        let shadows = CommaSeparated::<BoxShadow>::parse_str("2px 2px red, -2px -2px blue").unwrap();
+        assert_eq!(
+            shadows,
+            CommaSeparated(vec![
+                BoxShadow { color: Some(ColorOrCurrentColor::Color(Color::RED)), ..shadow(2.0, 2.0) },
+                BoxShadow { color: Some(ColorOrCurrentColor::Color(Color::BLUE)), ..shadow(-2.0, -2.0) },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_bad_shadow() {
+        assert!(BoxShadow::parse_str("").is_err());
+        assert!(BoxShadow::parse_str("red").is_err());
+        assert!(BoxShadow::parse_str("inset").is_err());
+        assert!(BoxShadow::parse_str("1px").is_err());
+        assert!(CommaSeparated::<BoxShadow>::parse_str("1px 1px,").is_err());
+    }
+}
\ No newline at end of file
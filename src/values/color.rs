@@ -0,0 +1,388 @@
+use std::fmt;
+use bevy::prelude::Color;
+use cssparser::{
+    CowRcStr, Parser, SourceLocation, ToCss, Token, RGBA,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::{bevy_impl::color_to_css, color_space, Angle, Parse},
+};
+
+/// A `<color>` value, where the `currentcolor` keyword could be used as well.
+/// `currentcolor` can only be resolved when the value is applied, as it refers to the color that
+/// is already in use at that time.
+/// See also: https://www.w3.org/TR/css-color-4/#currentcolor-color
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorOrCurrentColor {
+    CurrentColor,
+    Color(Color),
+}
+
+impl ColorOrCurrentColor {
+    /// The color to use, given the `current` color in use (e.g. a `UiColor` that is about to be
+    /// overwritten)
+    #[inline]
+    pub fn resolve(&self, current: Color) -> Color {
+        match *self {
+            Self::CurrentColor => current,
+            Self::Color(color) => color,
+        }
+    }
+}
+
+impl From<Color> for ColorOrCurrentColor {
+    #[inline]
+    fn from(color: Color) -> Self {
+        Self::Color(color)
+    }
+}
+
+impl Parse for ColorOrCurrentColor {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("currentcolor")).is_ok() {
+            Ok(Self::CurrentColor)
+        } else {
+            Ok(Self::Color( Color::parse(input)? ))
+        }
+    }
+}
+
+impl ToCss for ColorOrCurrentColor {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::CurrentColor => dest.write_str("currentcolor"),
+            Self::Color(ref color) => color_to_css(color, dest),
+        }
+    }
+}
+
+/// Parses the color functions that `cssparser` does not support, where the function's
+/// `Token::Function` has already been consumed.  Returns `Ok(None)` for any other function `name`,
+/// without consuming any more input.
+pub(crate) fn parse_extra_function<'i, 't>(
+    name: &CowRcStr<'i>,
+    input: &mut Parser<'i, 't>,
+) -> Result<Option<RGBA>, BevyCssParsingError<'i>> {
+    Ok(Some(match_ignore_ascii_case! { name,
+        "hwb" => input.parse_nested_block(parse_hwb)?,
+        "lab" => input.parse_nested_block(parse_lab)?,
+        "lch" => input.parse_nested_block(parse_lch)?,
+        _ => return Ok(None),
+    }))
+}
+
+/// Checks the number of arguments given to the color functions that `cssparser` parses, where the
+/// function's `Token::Function` has already been consumed.  `cssparser` only gives a generic token
+/// error when there are too few or too many.  Both `,` and `/` separators are skipped, so
+/// `rgb(1, 2, 3, 0.5)` and `rgb(1 2 3 / 0.5)` both have 4 arguments.
+pub(crate) fn check_argument_count<'i, 't>(
+    start: SourceLocation,
+    name: &CowRcStr<'i>,
+    input: &mut Parser<'i, 't>,
+) -> Result<(), BevyCssParsingError<'i>> {
+    let expected = match_ignore_ascii_case! { name,
+        "rgb" | "rgba" | "hsl" | "hsla" => 3..=4,
+        _ => return Ok(()),
+    };
+    let count = input.parse_nested_block(|i| {
+        let mut count = 0;
+        while let Ok(token) = i.next() {
+            if !matches!(*token, Token::Comma | Token::Delim('/')) {
+                count += 1
+            }
+        }
+        Ok(count)
+    })?;
+    if expected.contains(&count) {
+        Ok(())
+    } else {
+        Err(start.new_custom_error(BevyCssParsingErrorKind::WrongArgumentCount(
+            name.clone(), format!("{} or {}", expected.start(), expected.end()).into()
+        )))
+    }
+}
+
+/// Parses the arguments of a `hwb()` color function, where its `Token::Function` has already been
+/// consumed.  Both the comma separated (`hwb(H, W%, B%, A)`) and the whitespace separated
+/// (`hwb(H W% B% / A)`) forms are accepted.
+/// See also: https://www.w3.org/TR/css-color-4/#the-hwb-notation
+fn parse_hwb<'i, 't>(input: &mut Parser<'i, 't>) -> Result<RGBA, BevyCssParsingError<'i>> {
+    let hue = parse_hue(input)?;
+    let uses_commas = input.try_parse(|i| i.expect_comma()).is_ok();
+    let whiteness = input.expect_percentage()?.clamp(0.0, 1.0);
+    if uses_commas {
+        input.expect_comma()?;
+    }
+    let blackness = input.expect_percentage()?.clamp(0.0, 1.0);
+    let alpha = if !uses_commas {
+        parse_slash_alpha(input)?
+    } else if input.try_parse(|i| i.expect_comma()).is_ok() {
+        parse_alpha(input)?
+    } else {
+        1.0
+    };
+
+    let (red, green, blue) = hwb_to_rgb(hue, whiteness, blackness);
+    Ok(RGBA::from_floats(red, green, blue, alpha))
+}
+
+/// Parses the arguments of a `lab()` color function, e.g. `lab(52% 40 59 / 0.5)`.
+/// For `a` and `b`, `100%` is equivalent to `125`.
+/// See also: https://www.w3.org/TR/css-color-4/#specifying-lab-lch
+fn parse_lab<'i, 't>(input: &mut Parser<'i, 't>) -> Result<RGBA, BevyCssParsingError<'i>> {
+    let lightness = parse_lightness(input)?;
+    let a = parse_number_or_percentage(input, 125.0)?;
+    let b = parse_number_or_percentage(input, 125.0)?;
+    let alpha = parse_slash_alpha(input)?;
+
+    let [red, green, blue] = color_space::lab_to_srgb(lightness, a, b);
+    Ok(RGBA::from_floats(red, green, blue, alpha))
+}
+
+/// Parses the arguments of a `lch()` color function, e.g. `lch(52% 72 40 / 0.5)`.
+/// For chroma, `100%` is equivalent to `150`.
+/// See also: https://www.w3.org/TR/css-color-4/#specifying-lab-lch
+fn parse_lch<'i, 't>(input: &mut Parser<'i, 't>) -> Result<RGBA, BevyCssParsingError<'i>> {
+    let lightness = parse_lightness(input)?;
+    let chroma = parse_number_or_percentage(input, 150.0)?.max(0.0);
+    let hue = parse_hue(input)?;
+    let alpha = parse_slash_alpha(input)?;
+
+    let [red, green, blue] = color_space::lch_to_srgb(lightness, chroma, hue);
+    Ok(RGBA::from_floats(red, green, blue, alpha))
+}
+
+/// CIE lightness, given as either a `<number>` or a `<percentage>`, clamped to `0.0 ... 100.0`
+#[inline]
+fn parse_lightness<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BevyCssParsingError<'i>> {
+    Ok(parse_number_or_percentage(input, 100.0)?.clamp(0.0, 100.0))
+}
+
+/// A `<number>` or a `<percentage>`, where `100%` is equivalent to `hundred_percent`
+fn parse_number_or_percentage<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    hundred_percent: f32,
+) -> Result<f32, BevyCssParsingError<'i>> {
+    let start = input.current_source_location();
+    match *input.next()? {
+        Token::Number { value, .. } => Ok(value),
+        Token::Percentage { unit_value, .. } => Ok(unit_value * hundred_percent),
+        ref token => Err(start.new_unexpected_token_error(token.clone())),
+    }
+}
+
+/// An optional alpha value, following a `/`.  Defaults to `1.0` where no alpha is given.
+#[inline]
+fn parse_slash_alpha<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BevyCssParsingError<'i>> {
+    if input.try_parse(|i| i.expect_delim('/')).is_ok() {
+        parse_alpha(input)
+    } else {
+        Ok(1.0)
+    }
+}
+
+/// Converts a hue (degrees), whiteness and blackness (`0.0 ... 1.0`) to red, green and blue
+/// (`0.0 ... 1.0`).  Where whiteness and blackness add to more than `100%`, they are normalised and
+/// the result is a shade of grey.
+fn hwb_to_rgb(hue: f32, whiteness: f32, blackness: f32) -> (f32, f32, f32) {
+    if whiteness + blackness >= 1.0 {
+        let grey = whiteness / (whiteness + blackness);
+        return (grey, grey, grey)
+    }
+    let [red, green, blue, _] = Color::hsl(hue, 1.0, 0.5).as_rgba_f32();
+    let scale = 1.0 - whiteness - blackness;
+    (red * scale + whiteness, green * scale + whiteness, blue * scale + whiteness)
+}
+
+/// A hue, given as either a `<number>` (of degrees) or an `<angle>`, normalised to `0.0 ... 360.0`
+fn parse_hue<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BevyCssParsingError<'i>> {
+    let start = input.current_source_location();
+    let token = input.next()?;
+    let degrees = match *token {
+        Token::Number { value, .. } => value,
+        Token::Dimension { value, ref unit, .. } => Angle::parse_dimension(unit, value)
+            .map_err(|err| start.new_custom_error(err))?
+            .to_degrees(),
+        _ => return Err(start.new_unexpected_token_error(token.clone())),
+    };
+    Ok(degrees.rem_euclid(360.0))
+}
+
+/// An alpha value, given as either a `<number>` or a `<percentage>`, clamped to `0.0 ... 1.0`
+fn parse_alpha<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BevyCssParsingError<'i>> {
+    Ok(parse_number_or_percentage(input, 1.0)?.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_color(css: &str) -> Color {
+        Color::parse_str(css).unwrap()
+    }
+
+    #[test]
+    fn test_hwb() {
+        assert_eq!(parse_color("hwb(0 0% 0%)"), Color::RED);
+        assert_eq!(parse_color("hwb(120 0% 0%)"), Color::rgb_u8(0, 255, 0));
+        assert_eq!(parse_color("hwb(180 20% 40%)"), Color::rgb_u8(51, 153, 153));
+        assert_eq!(parse_color("HWB(0deg 0% 0%)"), Color::RED);
+        assert_eq!(parse_color("hwb(0.5turn 20% 40%)"), Color::rgb_u8(51, 153, 153));
+        assert_eq!(parse_color("hwb(-180 20% 40%)"), Color::rgb_u8(51, 153, 153));
+    }
+
+    #[test]
+    fn test_hwb_separators() {
+        assert_eq!(parse_color("hwb(0, 0%, 0%)"), parse_color("hwb(0 0% 0%)"));
+        assert_eq!(parse_color("hwb(0 0% 0% / 0.5)"), Color::rgba_u8(255, 0, 0, 128));
+        assert_eq!(parse_color("hwb(0 0% 0% / 50%)"), Color::rgba_u8(255, 0, 0, 128));
+        assert_eq!(parse_color("hwb(0, 0%, 0%, 0.5)"), Color::rgba_u8(255, 0, 0, 128));
+        // Mixing the two forms is not allowed
+        assert!(Color::parse_str("hwb(0, 0% 0%)").is_err());
+        assert!(Color::parse_str("hwb(0 0% 0%, 0.5)").is_err());
+        assert!(Color::parse_str("hwb(0, 0%, 0% / 0.5)").is_err());
+    }
+
+    #[test]
+    fn test_hwb_greys() {
+        assert_eq!(parse_color("hwb(0 100% 0%)"), Color::WHITE);
+        assert_eq!(parse_color("hwb(0 0% 100%)"), Color::BLACK);
+        // Whiteness + blackness over `100%` is normalised, ignoring the hue
+        assert_eq!(parse_color("hwb(90 60% 60%)"), parse_color("hwb(0 50% 50%)"));
+        assert_eq!(parse_color("hwb(0 75% 75%)"), Color::rgb_u8(128, 128, 128));
+    }
+
+    fn assert_close(color: Color, expected: Color) {
+        let (actual, expected) = (color.as_rgba_f32(), expected.as_rgba_f32());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            // `cssparser` stores colors as `u8`
+            assert!((a - e).abs() <= 1.0 / 255.0, "{:?} is not close to {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_lab() {
+        assert_close(parse_color("lab(54.29% 80.8 69.89)"), Color::RED);
+        assert_close(parse_color("lab(54.29 80.8 69.89)"), Color::RED);
+        assert_close(parse_color("lab(54.29% 64.64% 55.912%)"), Color::RED);
+        assert_close(parse_color("lab(100% 0 0)"), Color::WHITE);
+        assert_close(parse_color("lab(0% 0 0)"), Color::BLACK);
+        assert_close(parse_color("lab(50% 0 0 / 0.5)"), Color::rgba(0.46633, 0.46633, 0.46633, 0.5));
+        assert_close(parse_color("LAB(50% 0 0 / 50%)"), Color::rgba(0.46633, 0.46633, 0.46633, 0.5));
+        // Out of gamut colors are clamped
+        assert_close(parse_color("lab(100% 0 0 / 2)"), Color::WHITE);
+        assert_close(parse_color("lab(150% 0 0)"), Color::WHITE);
+        assert!(Color::parse_str("lab(50%, 0, 0)").is_err());
+        assert!(Color::parse_str("lab(50% 0)").is_err());
+    }
+
+    #[test]
+    fn test_lch() {
+        assert_close(parse_color("lch(54.29% 106.83 40.86)"), Color::RED);
+        assert_close(parse_color("lch(54.29% 106.83 40.86deg)"), Color::RED);
+        assert_close(parse_color("lch(50% 0 180 / 0.5)"), Color::rgba(0.46633, 0.46633, 0.46633, 0.5));
+        assert_close(parse_color("lch(100% 0 0)"), Color::WHITE);
+        assert!(Color::parse_str("lch(50%, 0, 0)").is_err());
+    }
+
+    #[test]
+    fn test_modern_syntax() {
+        // `cssparser` handles both the legacy comma separated & whitespace separated forms
+        assert_eq!(parse_color("rgb(255 0 0)"), parse_color("rgb(255,0,0)"));
+        assert_eq!(parse_color("rgb(10 20 30 / 0.5)"), Color::rgba_u8(10, 20, 30, 128));
+        assert_eq!(parse_color("rgb(10 20 30 / 50%)"), parse_color("rgba(10, 20, 30, 0.5)"));
+        assert_eq!(parse_color("rgba(10 20 30 / 0.5)"), parse_color("rgba(10, 20, 30, 0.5)"));
+        assert_eq!(parse_color("rgb(100% 0% 0%)"), Color::RED);
+        assert_eq!(parse_color("hsl(180 60% 70%)"), parse_color("hsl(180, 60%, 70%)"));
+        assert_eq!(parse_color("hsl(180 60% 70% / 50%)"), Color::rgba_u8(133, 224, 224, 128));
+        assert_eq!(parse_color("hsl(180deg 60% 70% / 0.5)"), parse_color("hsla(180, 60%, 70%, 0.5)"));
+        // Mixing the two forms is not allowed
+        assert!(Color::parse_str("rgb(10, 20 30)").is_err());
+        assert!(Color::parse_str("rgb(10 20 30, 0.5)").is_err());
+        assert!(Color::parse_str("hsl(180, 60%, 70% / 0.5)").is_err());
+    }
+
+    #[test]
+    fn test_legacy_and_modern_forms_match() {
+        // The form is taken from the first separator, so the same arguments give the same color
+        let pairs = [
+            ("rgb(1,2,3)", "rgb(1 2 3)"),
+            ("rgb( 1 , 2 , 3 )", "rgb( 1  2  3 )"),
+            ("rgba(1, 2, 3, 0.25)", "rgba(1 2 3 / 0.25)"),
+            ("rgb(10%, 20%, 30%)", "rgb(10% 20% 30%)"),
+            ("hsl(120, 50%, 50%)", "hsl(120 50% 50%)"),
+            ("hsla(120deg, 50%, 50%, 25%)", "hsla(120deg 50% 50% / 25%)"),
+            ("hwb(120, 10%, 20%)", "hwb(120 10% 20%)"),
+        ];
+        for (legacy, modern) in pairs {
+            assert_eq!(parse_color(legacy), parse_color(modern), "{} & {}", legacy, modern);
+            let legacy_property = ColorOrCurrentColor::parse_str(legacy).unwrap();
+            assert_eq!(legacy_property, ColorOrCurrentColor::parse_str(modern).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_argument_count() {
+        use cssparser::ParseErrorKind;
+        use crate::errors::BevyCssContextualError;
+
+        for css in [
+            "rgb(10, 20)", "rgb(10 20)", "rgba(10,20,30,40,50)", "rgb(10 20 30 / 0.5 0.5)",
+            "hsl(180, 60%)", "hsla(180, 60%, 70%, 0.5, 0.5)", "rgb()",
+        ] {
+            let err = Color::parse_str(css).unwrap_err();
+            match err.kind {
+                ParseErrorKind::Custom(BevyCssParsingErrorKind::WrongArgumentCount(ref name, ref expected)) => {
+                    assert!(css.starts_with(&**name), "{}", css);
+                    assert_eq!(&**expected, "3 or 4");
+                },
+                ref kind => panic!("Unexpected error for `{}`: {:?}", css, kind),
+            }
+            let message = BevyCssContextualError::InvalidValue(css, err).to_string();
+            assert!(message.contains("() expects 3 or 4 arguments"), "{}", message);
+        }
+        assert!(BevyCssContextualError::InvalidValue("", Color::parse_str("rgb(10, 20)").unwrap_err())
+            .to_string()
+            .contains("rgb() expects 3 or 4 arguments"));
+        // The right number of arguments is still parsed by `cssparser`
+        assert_eq!(parse_color("rgba(10, 20, 30)"), Color::rgb_u8(10, 20, 30));
+        assert!(Color::parse_str("rgb(10, 20, 30 40)").is_err());
+    }
+
+    #[test]
+    fn test_named_colors() {
+        // The full CSS named color list is used (via `cssparser`), not Bevy's color constants
+        let named_colors = [
+            ("rebeccapurple", (102, 51, 153)),
+            ("cornflowerblue", (100, 149, 237)),
+            ("darkslategray", (47, 79, 79)),
+            ("darkslategrey", (47, 79, 79)),
+            ("lightgray", (211, 211, 211)),
+            ("lightgrey", (211, 211, 211)),
+            ("grey", (128, 128, 128)),
+            ("gray", (128, 128, 128)),
+            ("papayawhip", (255, 239, 213)),
+            ("mediumaquamarine", (102, 205, 170)),
+            ("lightgoldenrodyellow", (250, 250, 210)),
+            ("yellowgreen", (154, 205, 50)),
+            ("aliceblue", (240, 248, 255)),
+            ("CornflowerBlue", (100, 149, 237)),
+        ];
+        for (name, (red, green, blue)) in named_colors {
+            assert_eq!(parse_color(name), Color::rgb_u8(red, green, blue), "{}", name);
+        }
+        assert!(Color::parse_str("notacolor").is_err());
+    }
+
+    #[test]
+    fn test_current_color() {
+        assert_eq!(
+            ColorOrCurrentColor::parse_str("currentcolor").unwrap(),
+            ColorOrCurrentColor::CurrentColor
+        );
+        assert_eq!(ColorOrCurrentColor::CurrentColor.resolve(Color::RED), Color::RED);
+        assert_eq!(ColorOrCurrentColor::Color(Color::BLUE).resolve(Color::RED), Color::BLUE);
+    }
+}
\ No newline at end of file
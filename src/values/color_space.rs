@@ -0,0 +1,115 @@
+// References: https://www.w3.org/TR/css-color-4/#color-conversion-code
+
+/// The `D50` reference white, used by CIELAB
+const D50_WHITE: [f32; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+/// Bradford chromatic adaptation from `D50` to `D65`
+const D50_TO_D65: [[f32; 3]; 3] = [
+    [ 0.9554734,   -0.023098538,  0.06325931 ],
+    [-0.028369706,  1.0099955,    0.021041399],
+    [ 0.012314002, -0.020507697,  1.3303659  ],
+];
+
+/// `D65` XYZ to linear-light sRGB
+const XYZ_TO_LINEAR_SRGB: [[f32; 3]; 3] = [
+    [ 3.24097,     -1.5373832,   -0.49861076 ],
+    [-0.96924365,   1.8759675,    0.04155506 ],
+    [ 0.05563008,  -0.20397696,   1.0569715  ],
+];
+
+/// CIE constants, as rational numbers to avoid rounding errors
+const KAPPA: f32 = 24389.0 / 27.0;
+const EPSILON: f32 = 216.0 / 24389.0;
+
+#[inline]
+fn multiply(matrix: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    [
+        matrix[0][0] * v[0] + matrix[0][1] * v[1] + matrix[0][2] * v[2],
+        matrix[1][0] * v[0] + matrix[1][1] * v[1] + matrix[1][2] * v[2],
+        matrix[2][0] * v[0] + matrix[2][1] * v[1] + matrix[2][2] * v[2],
+    ]
+}
+
+/// Applies the sRGB transfer function to a linear-light value
+#[inline]
+fn gamma_encode(linear: f32) -> f32 {
+    let abs = linear.abs();
+    let encoded = if abs <= 0.0031308 {
+        abs * 12.92
+    } else {
+        1.055 * abs.powf(1.0 / 2.4) - 0.055
+    };
+    encoded.copysign(linear)
+}
+
+/// Converts CIELAB lightness (`0.0 ... 100.0`), `a` and `b` to `D50` XYZ
+pub fn lab_to_xyz(lightness: f32, a: f32, b: f32) -> [f32; 3] {
+    let f1 = (lightness + 16.0) / 116.0;
+    let f0 = a / 500.0 + f1;
+    let f2 = f1 - b / 200.0;
+
+    let x = if f0.powi(3) > EPSILON { f0.powi(3) } else { (116.0 * f0 - 16.0) / KAPPA };
+    let y = if lightness > KAPPA * EPSILON { f1.powi(3) } else { lightness / KAPPA };
+    let z = if f2.powi(3) > EPSILON { f2.powi(3) } else { (116.0 * f2 - 16.0) / KAPPA };
+
+    [x * D50_WHITE[0], y * D50_WHITE[1], z * D50_WHITE[2]]
+}
+
+/// Converts CIELCH chroma & hue (in degrees) to CIELAB `a` and `b`
+#[inline]
+pub fn lch_to_lab(chroma: f32, hue: f32) -> (f32, f32) {
+    let hue = hue.to_radians();
+    (chroma * hue.cos(), chroma * hue.sin())
+}
+
+/// Converts `D50` XYZ to gamma encoded sRGB.  Colors outside of the sRGB gamut are clamped to
+/// `0.0 ... 1.0`.
+pub fn xyz_to_srgb(xyz: [f32; 3]) -> [f32; 3] {
+    let linear = multiply(&XYZ_TO_LINEAR_SRGB, multiply(&D50_TO_D65, xyz));
+    linear.map(|c| gamma_encode(c).clamp(0.0, 1.0))
+}
+
+/// Converts CIELAB lightness (`0.0 ... 100.0`), `a` and `b` to gamma encoded sRGB
+#[inline]
+pub fn lab_to_srgb(lightness: f32, a: f32, b: f32) -> [f32; 3] {
+    xyz_to_srgb(lab_to_xyz(lightness, a, b))
+}
+
+/// Converts CIELCH lightness (`0.0 ... 100.0`), chroma & hue (in degrees) to gamma encoded sRGB
+#[inline]
+pub fn lch_to_srgb(lightness: f32, chroma: f32, hue: f32) -> [f32; 3] {
+    let (a, b) = lch_to_lab(chroma, hue);
+    lab_to_srgb(lightness, a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 0.001, "{:?} is not close to {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_lab_to_srgb() {
+        assert_close(lab_to_srgb(0.0, 0.0, 0.0), [0.0, 0.0, 0.0]);
+        assert_close(lab_to_srgb(100.0, 0.0, 0.0), [1.0, 1.0, 1.0]);
+        assert_close(lab_to_srgb(50.0, 0.0, 0.0), [0.46633, 0.46633, 0.46633]);
+        assert_close(lab_to_srgb(54.29, 80.80, 69.89), [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_lch_to_srgb() {
+        assert_close(lch_to_srgb(50.0, 0.0, 123.0), [0.46633, 0.46633, 0.46633]);
+        assert_close(lch_to_srgb(54.29, 106.83, 40.86), [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_out_of_gamut() {
+        for c in lab_to_srgb(50.0, 200.0, -200.0).iter().chain(lab_to_srgb(120.0, 0.0, 0.0).iter()) {
+            assert!((0.0..=1.0).contains(c));
+        }
+    }
+}
\ No newline at end of file
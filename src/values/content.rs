@@ -0,0 +1,211 @@
+use std::fmt;
+use cssparser::{
+    Parser, ToCss, Token,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+    serialize_identifier, serialize_string,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::Parse,
+};
+
+/// One item of the `content` property.  Counters (and attributes) would need pseudo-elements to be
+/// rendered, so they are only kept as they were written.
+/// See also: https://drafts.csswg.org/css-content/#content-property
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContentItem {
+    String(String),
+    /// `attr(data-label)`, or `attr(data-label, "fallback")`
+    Attr { name: String, fallback: Option<String> },
+    /// `counter(name)`, or `counter(name, upper-roman)` with a counter style
+    Counter { name: String, style: Option<String> },
+    /// `counters(name, ".")`, or `counters(name, ".", upper-roman)` with a counter style
+    Counters { name: String, separator: String, style: Option<String> },
+}
+
+impl Parse for ContentItem {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let token = input.next()?.clone();
+        match token {
+            Token::QuotedString(value) => Ok(Self::String(value.to_string())),
+            Token::Function(name) => input.parse_nested_block(|i| {
+                // Anything else left in the block is an error
+                Ok(match_ignore_ascii_case! { &name,
+                    "attr" => Self::Attr { name: expect_ident(i)?, fallback: parse_optional_arg(i, expect_string)? },
+                    "counter" => Self::Counter { name: expect_ident(i)?, style: parse_optional_arg(i, expect_ident)? },
+                    "counters" => {
+                        let name = expect_ident(i)?;
+                        i.expect_comma()?;
+                        let separator = expect_string(i)?;
+                        Self::Counters { name, separator, style: parse_optional_arg(i, expect_ident)? }
+                    },
+                    _ => return Err(start.new_custom_error(
+                        BevyCssParsingErrorKind::FunctionNotSupported(name.clone())
+                    )),
+                })
+            }),
+            _ => Err(start.new_unexpected_token_error(token)),
+        }
+    }
+}
+
+/// An argument after a comma, or `None` if there isn't a comma (i.e. the end of the arguments)
+fn parse_optional_arg<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    parse_arg: fn(&mut Parser<'i, 't>) -> Result<String, BevyCssParsingError<'i>>,
+) -> Result<Option<String>, BevyCssParsingError<'i>> {
+    if input.try_parse(Parser::expect_comma).is_err() {
+        return Ok(None)
+    }
+    parse_arg(input).map(Some)
+}
+
+fn expect_string<'i, 't>(input: &mut Parser<'i, 't>) -> Result<String, BevyCssParsingError<'i>> {
+    Ok(input.expect_string()?.to_string())
+}
+
+fn expect_ident<'i, 't>(input: &mut Parser<'i, 't>) -> Result<String, BevyCssParsingError<'i>> {
+    Ok(input.expect_ident()?.to_string())
+}
+
+impl ToCss for ContentItem {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        let (function, name) = match *self {
+            Self::String(ref value) => return serialize_string(value, dest),
+            Self::Attr { ref name, .. } => ("attr(", name),
+            Self::Counter { ref name, .. } => ("counter(", name),
+            Self::Counters { ref name, .. } => ("counters(", name),
+        };
+        dest.write_str(function)?;
+        serialize_identifier(name, dest)?;
+        match *self {
+            Self::Attr { fallback: Some(ref fallback), .. } => {
+                dest.write_str(", ")?;
+                serialize_string(fallback, dest)?;
+            },
+            Self::Counters { ref separator, .. } => {
+                dest.write_str(", ")?;
+                serialize_string(separator, dest)?;
+            },
+            _ => (),
+        }
+        match *self {
+            Self::Counter { style: Some(ref style), .. } | Self::Counters { style: Some(ref style), .. } => {
+                dest.write_str(", ")?;
+                serialize_identifier(style, dest)?;
+            },
+            _ => (),
+        }
+        dest.write_char(')')
+    }
+}
+
+/// The value of the `content` property.  It is parsed (so a block using it isn't broken), but Bevy
+/// has no pseudo-elements (e.g. `::before`) to put the content in.
+/// See also: https://drafts.csswg.org/css-content/#content-property
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Content {
+    Normal,
+    None,
+    Items(Vec<ContentItem>),
+}
+
+impl Parse for Content {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("normal")).is_ok() {
+            return Ok(Self::Normal)
+        }
+        if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+            return Ok(Self::None)
+        }
+        let mut items = vec![ContentItem::parse(input)?];
+        while let Ok(item) = input.try_parse(ContentItem::parse) {
+            items.push(item)
+        }
+        Ok(Self::Items(items))
+    }
+}
+
+impl ToCss for Content {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Normal => dest.write_str("normal"),
+            Self::None => dest.write_str("none"),
+            Self::Items(ref items) => {
+                for (n, item) in items.iter().enumerate() {
+                    if n > 0 {
+                        dest.write_char(' ')?;
+                    }
+                    item.to_css(dest)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::ParserInput;
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Content::parse_str("normal").unwrap(), Content::Normal);
+        assert_eq!(Content::parse_str("NONE").unwrap(), Content::None);
+        assert_eq!(
+            Content::parse_str("\"Item \" counter(item) \": \" attr(data-label)").unwrap(),
+            Content::Items(vec![
+                ContentItem::String("Item ".to_string()),
+                ContentItem::Counter { name: "item".to_string(), style: None },
+                ContentItem::String(": ".to_string()),
+                ContentItem::Attr { name: "data-label".to_string(), fallback: None },
+            ])
+        );
+        assert_eq!(
+            Content::parse_str("counters(section, \".\", upper-roman) attr(data-x, \"none\")").unwrap(),
+            Content::Items(vec![
+                ContentItem::Counters {
+                    name: "section".to_string(),
+                    separator: ".".to_string(),
+                    style: Some("upper-roman".to_string()),
+                },
+                ContentItem::Attr { name: "data-x".to_string(), fallback: Some("none".to_string()) },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_to_css() {
+        for css in [
+            "counter(item) \". \"",
+            "counter(item, upper-roman)",
+            "counters(section, \".\")",
+            "counters(section, \".\", lower-alpha) attr(data-x, \"none\")",
+        ] {
+            assert_eq!(Content::parse_str(css).unwrap().to_css_string(), css);
+        }
+    }
+
+    #[test]
+    fn test_bad_content() {
+        assert!(Content::parse_str("").is_err());
+        assert!(Content::parse_str("label").is_err());
+        assert!(Content::parse_str("url(icon.png)").is_err());
+        assert!(Content::parse_str("image(\"icon.png\")").is_err());
+        assert!(Content::parse_str("attr()").is_err());
+        // Only the arguments each function takes, separated by commas
+        assert!(Content::parse_str("counters(section)").is_err());
+        assert!(Content::parse_str("counter(item upper-roman)").is_err());
+        assert!(Content::parse_str("counter(item, \".\")").is_err());
+        assert!(Content::parse_str("attr(data-x, \"a\", \"b\")").is_err());
+        assert!(Content::parse_str("attr(data-x !)").is_err());
+        // `normal` can't have anything after it
+        let mut parser_input = ParserInput::new("normal \"a\"");
+        let mut input = Parser::new(&mut parser_input);
+        assert!(input.parse_entirely(Content::parse).is_err());
+    }
+}
\ No newline at end of file
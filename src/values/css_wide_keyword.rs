@@ -0,0 +1,44 @@
+use std::fmt;
+use cssparser::{
+    Parser, ToCss,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::Parse,
+};
+
+/// The keywords that every CSS property accepts
+/// See also: https://drafts.csswg.org/css-cascade/#defaulting-keywords
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CssWideKeyword {
+    Initial,    // the property's initial (i.e. bevy default) value
+    Inherit,    // the parent element's value for the property
+    Unset,      // `inherit` for inherited properties, `initial` otherwise
+}
+
+impl Parse for CssWideKeyword {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { ident,
+            "initial" => Self::Initial,
+            "inherit" => Self::Inherit,
+            "unset" => Self::Unset,
+            _ => return Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidValue(ident.clone(), None)
+            ))
+        })
+    }
+}
+
+impl ToCss for CssWideKeyword {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        dest.write_str(match *self {
+            Self::Initial => "initial",
+            Self::Inherit => "inherit",
+            Self::Unset => "unset",
+        })
+    }
+}
\ No newline at end of file
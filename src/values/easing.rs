@@ -0,0 +1,260 @@
+use std::fmt;
+use cssparser::{
+    Parser, ToCss, Token,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::Parse,
+};
+
+/// Where the jumps of a `steps()` easing function are
+/// See also: https://drafts.csswg.org/css-easing/#step-position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StepPosition {
+    JumpStart,
+    JumpEnd,
+    JumpNone,
+    JumpBoth,
+}
+
+/// How a transition (or animation) progresses over its duration.  The keywords are kept as the
+/// function they stand for, e.g. `ease-in` is `cubic-bezier(0.42, 0, 1, 1)` & `step-end` is
+/// `steps(1, jump-end)`.
+/// See also: https://drafts.csswg.org/css-easing/#easing-functions
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EasingFunction {
+    Linear,
+    /// The x & y of the two control points
+    CubicBezier(f32, f32, f32, f32),
+    Steps(u32, StepPosition),
+}
+
+impl EasingFunction {
+    pub const EASE: Self = Self::CubicBezier(0.25, 0.1, 0.25, 1.0);
+    pub const EASE_IN: Self = Self::CubicBezier(0.42, 0.0, 1.0, 1.0);
+    pub const EASE_OUT: Self = Self::CubicBezier(0.0, 0.0, 0.58, 1.0);
+    pub const EASE_IN_OUT: Self = Self::CubicBezier(0.42, 0.0, 0.58, 1.0);
+
+    /// The eased progress, for the linear `progress` (from `0` to `1`) through the duration
+    pub fn evaluate(&self, progress: f32) -> f32 {
+        match *self {
+            Self::Linear => progress,
+            Self::CubicBezier(x1, y1, x2, y2) => {
+                if progress <= 0.0 || progress >= 1.0 {
+                    return progress
+                }
+                // As the x coordinates are between 0 & 1, x only ever increases along the curve, so
+                // the point at `progress` can be found by bisection
+                let bezier = |p1: f32, p2: f32, t: f32| {
+                    let u = 1.0 - t;
+                    3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t
+                };
+                let (mut low, mut high) = (0.0, 1.0);
+                let mut t = progress;
+                for _ in 0..32 {
+                    let x = bezier(x1, x2, t);
+                    if (x - progress).abs() < 1e-6 {
+                        break
+                    }
+                    if x < progress { low = t } else { high = t }
+                    t = (low + high) / 2.0;
+                }
+                bezier(y1, y2, t)
+            },
+            // See also: https://drafts.csswg.org/css-easing/#step-easing-algo
+            Self::Steps(steps, position) => {
+                let steps = steps as f32;
+                let mut step = (progress * steps).floor();
+                if matches!(position, StepPosition::JumpStart | StepPosition::JumpBoth) {
+                    step += 1.0;
+                }
+                let jumps = match position {
+                    StepPosition::JumpStart | StepPosition::JumpEnd => steps,
+                    StepPosition::JumpNone => steps - 1.0,
+                    StepPosition::JumpBoth => steps + 1.0,
+                };
+                if progress >= 0.0 && step < 0.0 {
+                    step = 0.0;
+                }
+                if progress <= 1.0 && step > jumps {
+                    step = jumps;
+                }
+                step / jumps
+            },
+        }
+    }
+
+    fn parse_cubic_bezier<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let mut points = [0.0; 4];
+        for (n, point) in points.iter_mut().enumerate() {
+            if n > 0 {
+                input.expect_comma()?;
+            }
+            let start = input.current_source_location();
+            *point = input.expect_number()?;
+            // The x coordinates have to be within the duration
+            if n % 2 == 0 && !(0.0..=1.0).contains(point) {
+                return Err(start.new_custom_error(BevyCssParsingErrorKind::InvalidValue(
+                    "cubic-bezier() x coordinates must be between 0 & 1".into(),
+                    None,
+                )))
+            }
+        }
+        let [x1, y1, x2, y2] = points;
+        Ok(Self::CubicBezier(x1, y1, x2, y2))
+    }
+
+    fn parse_steps<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let count = input.expect_integer()?;
+        let position = if input.try_parse(|i| i.expect_comma()).is_ok() {
+            let ident = input.expect_ident()?;
+            match_ignore_ascii_case! { ident,
+                "jump-start" | "start" => StepPosition::JumpStart,
+                "jump-end" | "end" => StepPosition::JumpEnd,
+                "jump-none" => StepPosition::JumpNone,
+                "jump-both" => StepPosition::JumpBoth,
+                _ => return Err(start.new_custom_error(
+                    BevyCssParsingErrorKind::InvalidValue(ident.clone(), None)
+                ))
+            }
+        } else {
+            StepPosition::JumpEnd
+        };
+        // `jump-none` needs at least 2 steps, so that it moves at all
+        let min_count = if position == StepPosition::JumpNone { 2 } else { 1 };
+        if count < min_count {
+            return Err(start.new_custom_error(BevyCssParsingErrorKind::InvalidValue(
+                "steps() needs a positive number of steps".into(),
+                None,
+            )))
+        }
+        Ok(Self::Steps(count as u32, position))
+    }
+}
+
+impl Default for EasingFunction {
+    fn default() -> Self {
+        Self::EASE
+    }
+}
+
+impl Parse for EasingFunction {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let token = input.next()?.clone();
+        match token {
+            Token::Ident(ref ident) => Ok(match_ignore_ascii_case! { ident,
+                "linear" => Self::Linear,
+                "ease" => Self::EASE,
+                "ease-in" => Self::EASE_IN,
+                "ease-out" => Self::EASE_OUT,
+                "ease-in-out" => Self::EASE_IN_OUT,
+                "step-start" => Self::Steps(1, StepPosition::JumpStart),
+                "step-end" => Self::Steps(1, StepPosition::JumpEnd),
+                _ => return Err(start.new_unexpected_token_error(token.clone()))
+            }),
+            Token::Function(ref name) => match_ignore_ascii_case! { name,
+                "cubic-bezier" => input.parse_nested_block(Self::parse_cubic_bezier),
+                "steps" => input.parse_nested_block(Self::parse_steps),
+                _ => Err(start.new_custom_error(BevyCssParsingErrorKind::FunctionNotSupported(name.clone())))
+            },
+            _ => Err(start.new_unexpected_token_error(token))
+        }
+    }
+}
+
+impl ToCss for StepPosition {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        dest.write_str(match *self {
+            Self::JumpStart => "jump-start",
+            Self::JumpEnd => "jump-end",
+            Self::JumpNone => "jump-none",
+            Self::JumpBoth => "jump-both",
+        })
+    }
+}
+
+impl ToCss for EasingFunction {
+    /// The functions that have a keyword are written as the keyword (e.g. `ease-in`)
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        let keywords = [
+            (Self::EASE, "ease"),
+            (Self::EASE_IN, "ease-in"),
+            (Self::EASE_OUT, "ease-out"),
+            (Self::EASE_IN_OUT, "ease-in-out"),
+        ];
+        if let Some((_, keyword)) = keywords.iter().find(|(function, _)| function == self) {
+            return dest.write_str(keyword)
+        }
+        match *self {
+            Self::Linear => dest.write_str("linear"),
+            Self::CubicBezier(x1, y1, x2, y2) => write!(dest, "cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2),
+            Self::Steps(1, StepPosition::JumpStart) => dest.write_str("step-start"),
+            Self::Steps(1, StepPosition::JumpEnd) => dest.write_str("step-end"),
+            Self::Steps(count, StepPosition::JumpEnd) => write!(dest, "steps({})", count),
+            Self::Steps(count, position) => {
+                write!(dest, "steps({}, ", count)?;
+                position.to_css(dest)?;
+                dest.write_char(')')
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords() {
+        assert_eq!(EasingFunction::parse_str("linear").unwrap(), EasingFunction::Linear);
+        assert_eq!(EasingFunction::parse_str("ease").unwrap(), EasingFunction::default());
+        assert_eq!(EasingFunction::parse_str("Ease-In").unwrap(), EasingFunction::CubicBezier(0.42, 0.0, 1.0, 1.0));
+        assert_eq!(EasingFunction::parse_str("step-start").unwrap(), EasingFunction::Steps(1, StepPosition::JumpStart));
+        assert!(EasingFunction::parse_str("ease-in-out-out").is_err());
+    }
+
+    #[test]
+    fn test_functions() {
+        assert_eq!(
+            EasingFunction::parse_str("cubic-bezier(0.1, -0.5, 0.9, 1.5)").unwrap(),
+            EasingFunction::CubicBezier(0.1, -0.5, 0.9, 1.5)
+        );
+        assert_eq!(EasingFunction::parse_str("steps(4)").unwrap(), EasingFunction::Steps(4, StepPosition::JumpEnd));
+        assert_eq!(
+            EasingFunction::parse_str("steps(2, jump-none)").unwrap(),
+            EasingFunction::Steps(2, StepPosition::JumpNone)
+        );
+        assert_eq!(EasingFunction::parse_str("steps(3, start)").unwrap(), EasingFunction::Steps(3, StepPosition::JumpStart));
+        assert!(EasingFunction::parse_str("cubic-bezier(1.5, 0, 0, 1)").is_err());
+        assert!(EasingFunction::parse_str("cubic-bezier(0, 0, 1)").is_err());
+        assert!(EasingFunction::parse_str("steps(0)").is_err());
+        assert!(EasingFunction::parse_str("steps(1, jump-none)").is_err());
+        assert!(EasingFunction::parse_str("steps(1.5)").is_err());
+        assert!(EasingFunction::parse_str("bounce(1)").is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        assert_eq!(EasingFunction::Linear.evaluate(0.25), 0.25);
+        assert!(close(EasingFunction::EASE_IN_OUT.evaluate(0.5), 0.5));
+        assert!(EasingFunction::EASE_IN.evaluate(0.5) < 0.5);
+        assert!(EasingFunction::EASE_OUT.evaluate(0.5) > 0.5);
+        assert_eq!(EasingFunction::EASE.evaluate(0.0), 0.0);
+        assert_eq!(EasingFunction::EASE.evaluate(1.0), 1.0);
+        // A linear curve, written as a bezier
+        assert!(close(EasingFunction::CubicBezier(0.0, 0.0, 1.0, 1.0).evaluate(0.3), 0.3));
+
+        let steps = |count, position| EasingFunction::Steps(count, position);
+        assert_eq!(steps(4, StepPosition::JumpEnd).evaluate(0.3), 0.25);
+        assert_eq!(steps(4, StepPosition::JumpEnd).evaluate(1.0), 1.0);
+        assert_eq!(steps(4, StepPosition::JumpStart).evaluate(0.0), 0.25);
+        assert_eq!(steps(3, StepPosition::JumpNone).evaluate(0.5), 0.5);
+        assert_eq!(steps(1, StepPosition::JumpBoth).evaluate(0.5), 0.5);
+    }
+}
\ No newline at end of file
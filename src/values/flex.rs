@@ -0,0 +1,222 @@
+use std::fmt;
+use bevy::ui;
+use cssparser::{Parser, ToCss};
+use crate::{
+    errors::BevyCssParsingError,
+    values::{
+        bevy_impl::CssKeyword,
+        generic::Numeric,
+        LengthPercentageOrAuto, NonNegativeNumber, Parse,
+    },
+};
+
+/// The values of the `flex` shorthand, for `flex-grow`, `flex-shrink` & `flex-basis`.  The grow &
+/// shrink factors have to be kept together, but the basis can be given before or after them.
+/// See also: https://drafts.csswg.org/css-flexbox-1/#flex-property
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flex {
+    pub grow: NonNegativeNumber,
+    pub shrink: NonNegativeNumber,
+    pub basis: LengthPercentageOrAuto,
+}
+
+impl Flex {
+    /// `flex: none`, i.e. `0 0 auto`
+    #[inline]
+    pub fn none() -> Self {
+        Self {
+            grow: NonNegativeNumber::zero(),
+            shrink: NonNegativeNumber::zero(),
+            basis: LengthPercentageOrAuto::Auto,
+        }
+    }
+
+    fn parse_factors<'i, 't>(
+        input: &mut Parser<'i, 't>
+    ) -> Result<(NonNegativeNumber, Option<NonNegativeNumber>), BevyCssParsingError<'i>> {
+        let grow = NonNegativeNumber::parse(input)?;
+        let shrink = input.try_parse(NonNegativeNumber::parse).ok();
+        Ok((grow, shrink))
+    }
+}
+
+impl Parse for Flex {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+            return Ok(Self::none())
+        }
+        let mut factors = None;
+        let mut basis = None;
+        loop {
+            // The factors are tried first, so a unitless zero is a factor rather than the basis
+            if factors.is_none() {
+                if let Ok(value) = input.try_parse(Self::parse_factors) {
+                    factors = Some(value);
+                    continue
+                }
+            }
+            if basis.is_none() {
+                if let Ok(value) = input.try_parse(LengthPercentageOrAuto::parse) {
+                    basis = Some(value);
+                    continue
+                }
+            }
+            break
+        }
+        match (factors, basis) {
+            (None, None) => Err(input.new_error_for_next_token()),
+            // e.g. `flex: 2` is `2 1 0`, while `flex: auto` is `1 1 auto`
+            (Some((grow, shrink)), basis) => Ok(Self {
+                grow,
+                shrink: shrink.unwrap_or_else(NonNegativeNumber::one),
+                basis: basis.unwrap_or_else(LengthPercentageOrAuto::zero),
+            }),
+            (None, Some(basis)) => Ok(Self {
+                grow: NonNegativeNumber::one(),
+                shrink: NonNegativeNumber::one(),
+                basis,
+            }),
+        }
+    }
+}
+
+/// The values of the `flex-flow` shorthand, for `flex-direction` & `flex-wrap`, given in either
+/// order.  Like any css shorthand, a missing value is reset to its default.
+/// See also: https://drafts.csswg.org/css-flexbox-1/#flex-flow-property
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlexFlow {
+    pub direction: ui::FlexDirection,
+    pub wrap: ui::FlexWrap,
+}
+
+impl Parse for FlexFlow {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let mut direction = None;
+        let mut wrap = None;
+        loop {
+            if direction.is_none() {
+                if let Ok(value) = input.try_parse(ui::FlexDirection::parse) {
+                    direction = Some(value);
+                    continue
+                }
+            }
+            if wrap.is_none() {
+                if let Ok(value) = input.try_parse(ui::FlexWrap::parse) {
+                    wrap = Some(value);
+                    continue
+                }
+            }
+            break
+        }
+        if direction.is_none() && wrap.is_none() {
+            return Err(input.new_error_for_next_token())
+        }
+        Ok(Self {
+            direction: direction.unwrap_or_default(),
+            wrap: wrap.unwrap_or_default(),
+        })
+    }
+}
+
+impl ToCss for Flex {
+    /// Always all three values, as `flex: 2` is not the same as `flex-grow: 2`
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        self.grow.to_css(dest)?;
+        dest.write_char(' ')?;
+        self.shrink.to_css(dest)?;
+        dest.write_char(' ')?;
+        self.basis.to_css(dest)
+    }
+}
+
+impl ToCss for FlexFlow {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        write!(dest, "{} {}", self.direction.css_keyword(), self.wrap.css_keyword())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::ParserInput;
+    use crate::values::{
+        generic::NonNegative,
+        LengthPercentage, Number,
+    };
+    use super::*;
+
+    fn flex(grow: f32, shrink: f32, basis: LengthPercentageOrAuto) -> Flex {
+        Flex {
+            grow: NonNegative(Number(grow)),
+            shrink: NonNegative(Number(shrink)),
+            basis,
+        }
+    }
+
+    fn px(value: f32) -> LengthPercentageOrAuto {
+        LengthPercentageOrAuto::NotAuto(LengthPercentage::parse_str(&format!("{}px", value)).unwrap())
+    }
+
+    fn parse_flex(css: &str) -> Result<Flex, BevyCssParsingError> {
+        let mut parser_input = ParserInput::new(css);
+        let mut input = Parser::new(&mut parser_input);
+        input.parse_entirely(Flex::parse)
+    }
+
+    #[test]
+    fn test_keywords() {
+        assert_eq!(parse_flex("none").unwrap(), flex(0.0, 0.0, LengthPercentageOrAuto::Auto));
+        assert_eq!(parse_flex("auto").unwrap(), flex(1.0, 1.0, LengthPercentageOrAuto::Auto));
+    }
+
+    #[test]
+    fn test_single_value() {
+        assert_eq!(parse_flex("1").unwrap(), flex(1.0, 1.0, LengthPercentageOrAuto::zero()));
+        assert_eq!(parse_flex("2.5").unwrap(), flex(2.5, 1.0, LengthPercentageOrAuto::zero()));
+        assert_eq!(parse_flex("0").unwrap(), flex(0.0, 1.0, LengthPercentageOrAuto::zero()));
+        assert_eq!(parse_flex("10px").unwrap(), flex(1.0, 1.0, px(10.0)));
+    }
+
+    #[test]
+    fn test_multiple_values() {
+        assert_eq!(parse_flex("1 1 auto").unwrap(), flex(1.0, 1.0, LengthPercentageOrAuto::Auto));
+        assert_eq!(parse_flex("2 3").unwrap(), flex(2.0, 3.0, LengthPercentageOrAuto::zero()));
+        assert_eq!(parse_flex("2 10px").unwrap(), flex(2.0, 1.0, px(10.0)));
+        assert_eq!(parse_flex("10px 2").unwrap(), flex(2.0, 1.0, px(10.0)));
+        assert_eq!(parse_flex("10px 2 3").unwrap(), flex(2.0, 3.0, px(10.0)));
+        assert_eq!(parse_flex("0 0 0").unwrap(), flex(0.0, 0.0, LengthPercentageOrAuto::zero()));
+    }
+
+    #[test]
+    fn test_flex_flow() {
+        let flex_flow = |css| {
+            let mut parser_input = ParserInput::new(css);
+            let mut input = Parser::new(&mut parser_input);
+            input.parse_entirely(FlexFlow::parse).map(|flow| (flow.direction, flow.wrap))
+        };
+        assert_eq!(flex_flow("row wrap").unwrap(), (ui::FlexDirection::Row, ui::FlexWrap::Wrap));
+        assert_eq!(flex_flow("wrap row").unwrap(), (ui::FlexDirection::Row, ui::FlexWrap::Wrap));
+        assert_eq!(
+            flex_flow("column-reverse wrap-reverse").unwrap(),
+            (ui::FlexDirection::ColumnReverse, ui::FlexWrap::WrapReverse)
+        );
+        assert_eq!(flex_flow("column").unwrap(), (ui::FlexDirection::Column, ui::FlexWrap::NoWrap));
+        assert_eq!(flex_flow("wrap").unwrap(), (ui::FlexDirection::Row, ui::FlexWrap::Wrap));
+        assert!(flex_flow("").is_err());
+        assert!(flex_flow("row column").is_err());
+        assert!(flex_flow("wrap nowrap").is_err());
+        assert!(flex_flow("row wrap row").is_err());
+    }
+
+    #[test]
+    fn test_bad_flex() {
+        assert!(parse_flex("").is_err());
+        assert!(parse_flex("1 1 auto 2").is_err());
+        assert!(parse_flex("1 1 1").is_err());
+        assert!(parse_flex("2 10px 3").is_err());
+        assert!(parse_flex("auto auto").is_err());
+        assert!(parse_flex("-1").is_err());
+        assert!(parse_flex("none 1").is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,120 @@
+use std::fmt;
+use cssparser::{Parser, ToCss, serialize_string};
+use crate::{
+    errors::BevyCssParsingError,
+    values::{
+        parse::AllowedValues,
+        Number, Parse,
+    },
+};
+
+/// The value of the `font-weight` property, from 1 to 1000.  `normal` is 400, and `bold` is 700.
+/// The relative `bolder` & `lighter` keywords need the parent's weight, so aren't supported.
+/// See also: https://drafts.csswg.org/css-fonts/#font-weight-prop
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontWeight(pub f32);
+
+impl FontWeight {
+    pub const NORMAL: Self = Self(400.0);
+    pub const BOLD: Self = Self(700.0);
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+impl Parse for FontWeight {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("normal")).is_ok() {
+            return Ok(Self::NORMAL)
+        }
+        if input.try_parse(|i| i.expect_ident_matching("bold")).is_ok() {
+            return Ok(Self::BOLD)
+        }
+        Number::parse_internal(input, AllowedValues::Range(1.0, 1000.0)).map(|weight| Self(weight.0))
+    }
+}
+
+/// The value of the `font-family` property, a comma separated list of family names in order of
+/// preference.  A name can be quoted (e.g. `"Fira Sans"`) or not (e.g. `Fira Sans`).
+/// See also: https://drafts.csswg.org/css-fonts/#font-family-prop
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontFamily(pub Vec<String>);
+
+impl FontFamily {
+    fn parse_name<'i, 't>(input: &mut Parser<'i, 't>) -> Result<String, BevyCssParsingError<'i>> {
+        if let Ok(name) = input.try_parse(|i| i.expect_string().map(|name| name.to_string())) {
+            return Ok(name)
+        }
+        // An unquoted name is any number of identifiers, separated by a space
+        let mut name = input.expect_ident()?.to_string();
+        while let Ok(ident) = input.try_parse(|i| i.expect_ident().map(|ident| ident.to_string())) {
+            name.push(' ');
+            name.push_str(&ident);
+        }
+        Ok(name)
+    }
+}
+
+impl Parse for FontFamily {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        Ok(Self(input.parse_comma_separated(Self::parse_name)?))
+    }
+}
+
+impl ToCss for FontWeight {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        write!(dest, "{}", self.0)
+    }
+}
+
+impl ToCss for FontFamily {
+    /// The generic families (e.g. `serif`) are keywords, any other name is written as a string
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        const GENERIC_FAMILIES: [&str; 5] = ["serif", "sans-serif", "monospace", "cursive", "fantasy"];
+        for (n, name) in self.0.iter().enumerate() {
+            if n > 0 {
+                dest.write_str(", ")?;
+            }
+            if GENERIC_FAMILIES.iter().any(|generic| generic.eq_ignore_ascii_case(name)) {
+                dest.write_str(name)?;
+            } else {
+                serialize_string(name, dest)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_weight() {
+        assert_eq!(FontWeight::parse_str("normal").unwrap(), FontWeight(400.0));
+        assert_eq!(FontWeight::parse_str("BOLD").unwrap(), FontWeight(700.0));
+        assert_eq!(FontWeight::parse_str("600").unwrap(), FontWeight(600.0));
+        assert_eq!(FontWeight::parse_str("1").unwrap(), FontWeight(1.0));
+        assert_eq!(FontWeight::parse_str("1000").unwrap(), FontWeight(1000.0));
+        assert!(FontWeight::parse_str("0").is_err());
+        assert!(FontWeight::parse_str("1001").is_err());
+        assert!(FontWeight::parse_str("bolder").is_err());
+        assert!(FontWeight::parse_str("600px").is_err());
+    }
+
+    #[test]
+    fn test_font_family() {
+        let family = |css| FontFamily::parse_str(css).unwrap().0;
+        assert_eq!(family("Fira Sans"), vec!["Fira Sans"]);
+        assert_eq!(family("\"Fira Sans\", serif"), vec!["Fira Sans", "serif"]);
+        assert_eq!(family("'Fira Mono',Fira  Sans , sans-serif"), vec!["Fira Mono", "Fira Sans", "sans-serif"]);
+        assert!(FontFamily::parse_str("").is_err());
+        assert!(FontFamily::parse_str("Fira, ").is_err());
+        assert!(FontFamily::parse_str("12px").is_err());
+    }
+}
\ No newline at end of file
@@ -1,7 +1,7 @@
-use std::fmt::Debug;
-use cssparser::Parser;
+use std::fmt::{self, Debug};
+use cssparser::{Parser, ToCss, match_ignore_ascii_case, _cssparser_internal_to_lowercase};
 use crate::{
-    errors::BevyCssParsingError,
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
     values::Parse,
 };
 
@@ -16,6 +16,7 @@ pub trait Numeric {
 
 /// Wrapper type where the value must not be negative
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NonNegative<NumericType: Numeric>(pub NumericType);
 
 impl<NumericType: Numeric> Numeric for NonNegative<NumericType> {
@@ -45,6 +46,7 @@ impl<NumericType: Numeric> Numeric for NonNegative<NumericType> {
 
 /// Wrapper type where the `auto` keyword can be used
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MaybeAuto<T>  {
     Auto,
     NotAuto(T),
@@ -134,4 +136,166 @@ impl<T: Parse + Clone + Copy> Parse for MaybeAuto<T> {
     fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
         Self::parse_maybe_auto(input, <T as Parse>::parse)
     }
+}
+
+/// The intrinsic sizing keywords, where a size is based on the node's content
+/// See also: https://drafts.csswg.org/css-sizing-3/#sizing-values
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntrinsicSize {
+    MinContent,
+    MaxContent,
+    FitContent,
+}
+
+impl Parse for IntrinsicSize {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { ident,
+            "min-content" => Self::MinContent,
+            "max-content" => Self::MaxContent,
+            "fit-content" => Self::FitContent,
+            _ => return Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidValue(ident.clone(), None)
+            ))
+        })
+    }
+}
+
+/// Wrapper type where an intrinsic sizing keyword (e.g. `min-content`) can be used
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaybeIntrinsic<T> {
+    Intrinsic(IntrinsicSize),
+    NotIntrinsic(T),
+}
+
+impl<T> MaybeIntrinsic<T> {
+    #[inline]
+    pub fn is_intrinsic(&self) -> bool {
+        matches!(*self, Self::Intrinsic(_))
+    }
+}
+
+impl<T> From<T> for MaybeIntrinsic<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::NotIntrinsic(value)
+    }
+}
+
+impl<T: Parse> Parse for MaybeIntrinsic<T> {
+    #[inline]
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if let Ok(intrinsic) = input.try_parse(IntrinsicSize::parse) {
+            Ok(Self::Intrinsic(intrinsic))
+        } else {
+            Ok(Self::NotIntrinsic( <T as Parse>::parse(input)? ))
+        }
+    }
+}
+
+/// Wrapper type where the `initial` keyword can be used, to reset a value to its default
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MaybeInitial<T> {
+    Initial,
+    NotInitial(T),
+}
+
+impl<T: Default> MaybeInitial<T> {
+    #[inline]
+    pub fn is_initial(&self) -> bool {
+        matches!(*self, Self::Initial)
+    }
+
+    #[inline]
+    pub fn initial_or_default(self) -> T {
+        match self {
+            Self::Initial => T::default(),
+            Self::NotInitial(val) => val,
+        }
+    }
+}
+
+impl<T: Parse> Parse for MaybeInitial<T> {
+    #[inline]
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("initial")).is_ok() {
+            Ok(Self::Initial)
+        } else {
+            Ok(Self::NotInitial( <T as Parse>::parse(input)? ))
+        }
+    }
+}
+
+/// A list of one or more comma separated values, e.g. `box-shadow: 1px 1px red, 2px 2px blue;`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommaSeparated<T>(pub Vec<T>);
+
+impl<T: Parse> Parse for CommaSeparated<T> {
+    #[inline]
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        Ok(Self(
+            input.parse_comma_separated(<T as Parse>::parse)?
+        ))
+    }
+}
+
+impl<NumericType: Numeric + ToCss> ToCss for NonNegative<NumericType> {
+    #[inline]
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        self.0.to_css(dest)
+    }
+}
+
+impl<T: ToCss> ToCss for MaybeAuto<T> {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Auto => dest.write_str("auto"),
+            Self::NotAuto(ref value) => value.to_css(dest),
+        }
+    }
+}
+
+impl ToCss for IntrinsicSize {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        dest.write_str(match *self {
+            Self::MinContent => "min-content",
+            Self::MaxContent => "max-content",
+            Self::FitContent => "fit-content",
+        })
+    }
+}
+
+impl<T: ToCss> ToCss for MaybeIntrinsic<T> {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Intrinsic(ref intrinsic) => intrinsic.to_css(dest),
+            Self::NotIntrinsic(ref value) => value.to_css(dest),
+        }
+    }
+}
+
+impl<T: ToCss> ToCss for MaybeInitial<T> {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Initial => dest.write_str("initial"),
+            Self::NotInitial(ref value) => value.to_css(dest),
+        }
+    }
+}
+
+impl<T: ToCss> ToCss for CommaSeparated<T> {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        for (n, value) in self.0.iter().enumerate() {
+            if n > 0 {
+                dest.write_str(", ")?;
+            }
+            value.to_css(dest)?;
+        }
+        Ok(())
+    }
 }
\ No newline at end of file
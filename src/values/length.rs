@@ -2,18 +2,21 @@
 
 use std::{
     cmp::Ordering,
+    fmt,
     ops::Mul,
 };
 use bevy::math::Vec2;
 use cssparser::{
-    Parser, Token, match_ignore_ascii_case, _cssparser_internal_to_lowercase, CowRcStr
+    Parser, SourceLocation, ToCss, Token, match_ignore_ascii_case, _cssparser_internal_to_lowercase, CowRcStr
 };
 use crate::{
     context::CssContext,
     errors::{BevyCssParsingError, BevyCssParsingErrorKind},
     values::{
         AbsoluteLength,
-        generic::{MaybeAuto, NonNegative, Numeric},
+        generic::{MaybeAuto, MaybeIntrinsic, NonNegative, Numeric},
+        line_height::NORMAL_LINE_HEIGHT,
+        math_function::MathFunction,
         number::Number,
         parse::{AllowedValues, Parse},
         percentage::Percentage,
@@ -27,6 +30,7 @@ use crate::{
 /// A length relative to the font base font size of the associated element/node.
 /// See also: https://drafts.csswg.org/css-values/#font-relative-lengths
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FontRelativeLength {
     Em(f32),      // relative to the selected element `font-size`
     Rem(f32),     // relative to the root element `font-size`
@@ -34,15 +38,23 @@ pub enum FontRelativeLength {
                   // https://drafts.csswg.org/css-values/#ex
     Ch(f32),      // relative to the font advance width/height of a 0/zero glyph
                   // https://drafts.csswg.org/css-values/#ch
-    // @todo `cap`, `ic`, `lh`, `rlh`
+    Lh(f32),      // relative to the selected element `line-height`
+    Rlh(f32),     // relative to the root element `line-height`
+    // @todo `cap`, `ic`
 }
 
+/// The assumed x-height of a font, as a fraction of its size, where the real one isn't known
+pub const DEFAULT_X_HEIGHT_RATIO: f32 = 0.5;
+/// The assumed advance width of a font's `0` glyph, as a fraction of its size, where the real one
+/// isn't known.  For vertical text, the advance height is assumed to be the font size.
+pub const DEFAULT_ZERO_ADVANCE_RATIO: f32 = 0.5;
+
 impl FontRelativeLength {
     #[inline]
     fn raw_value(&self) -> f32 {
         use FontRelativeLength::*;
         match *self {
-            Em(v)  | Rem(v) | Ex(v)  | Ch(v)  => v,
+            Em(v)  | Rem(v) | Ex(v)  | Ch(v)  | Lh(v)  | Rlh(v) => v,
         }
     }
 
@@ -55,20 +67,32 @@ impl FontRelativeLength {
         match *self {
             Self::Em(relative_length) => base_length * relative_length,
             Self::Rem(relative_length) => root_base_length * relative_length,
-            // @fixme Purely assumed x-height of 0.5
-            Self::Ex(relative_length) => base_length * relative_length * 0.5,
-            // @fixme Purely assumed character advance of 0.5 for horizontal and 1.0 for vertical text
-            Self::Ch(relative_length) => base_length * relative_length * if is_vertical {1.0} else {0.5},
+            // Without a context, the font metrics can only be assumed
+            Self::Ex(relative_length) => base_length * relative_length * DEFAULT_X_HEIGHT_RATIO,
+            Self::Ch(relative_length) =>
+                base_length * relative_length * if is_vertical {1.0} else {DEFAULT_ZERO_ADVANCE_RATIO},
+            // Without a context, the line heights can only be assumed to be `normal`
+            Self::Lh(relative_length) => base_length * relative_length * NORMAL_LINE_HEIGHT,
+            Self::Rlh(relative_length) => root_base_length * relative_length * NORMAL_LINE_HEIGHT,
         }
     }
 
     #[inline]
     pub fn to_computed_px(&self, context: &CssContext) -> f32 {
-        self.to_px(
-            context.font_size,
-            context.vertical_text,
-            context.root_font_size
-        )
+        match *self {
+            Self::Lh(relative_length) => context.computed_line_height() * relative_length,
+            Self::Rlh(relative_length) => context.computed_root_line_height() * relative_length,
+            Self::Ex(relative_length) => context.font_size * context.x_height_ratio * relative_length,
+            Self::Ch(relative_length) => {
+                let ratio = if context.vertical_text { 1.0 } else { context.zero_advance_ratio };
+                context.font_size * ratio * relative_length
+            },
+            _ => self.to_px(
+                context.font_size,
+                context.vertical_text,
+                context.root_font_size
+            ),
+        }
     }
 }
 
@@ -111,6 +135,8 @@ impl PartialOrd for FontRelativeLength {
             &Self::Rem(left) => left.partial_cmp(&other.raw_value()),
             &Self::Ex (left) => left.partial_cmp(&other.raw_value()),
             &Self::Ch (left) => left.partial_cmp(&other.raw_value()),
+            &Self::Lh (left) => left.partial_cmp(&other.raw_value()),
+            &Self::Rlh(left) => left.partial_cmp(&other.raw_value()),
         }
     }
 }
@@ -124,6 +150,8 @@ impl Mul<f32> for FontRelativeLength {
             Self::Rem(v) => Self::Rem(v * rhs),
             Self::Ex(v)  => Self::Ex(v * rhs),
             Self::Ch(v)  => Self::Ch(v * rhs),
+            Self::Lh(v)  => Self::Lh(v * rhs),
+            Self::Rlh(v) => Self::Rlh(v * rhs),
         }
     }
 }
@@ -131,6 +159,7 @@ impl Mul<f32> for FontRelativeLength {
 /// A length specified as an fixed proportion of the containing viewport height/width.
 /// See also: https://drafts.csswg.org/css-values/#viewport-relative-lengths
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ViewportRelativeLength {
     Vw(f32),        // relative to the viewport width
     Vh(f32),        // relative to the viewport height
@@ -227,6 +256,7 @@ impl Mul<f32> for ViewportRelativeLength {
 /// A container for the various specific length types, where the value is not a css `calc(` function
 /// See also: https://drafts.csswg.org/css-values/#lengths
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NoCalcLength {
     Absolute(AbsoluteLength),
     FontRelative(FontRelativeLength),
@@ -260,6 +290,8 @@ impl NoCalcLength {
             "em"  => Self::FontRelative(FontRelativeLength::Em(value)),
             "ex"  => Self::FontRelative(FontRelativeLength::Ex(value)),
             "ch"  => Self::FontRelative(FontRelativeLength::Ch(value)),
+            "lh"  => Self::FontRelative(FontRelativeLength::Lh(value)),
+            "rlh" => Self::FontRelative(FontRelativeLength::Rlh(value)),
             "rem" => Self::FontRelative(FontRelativeLength::Rem(value)),
             // Viewport Relative
             "vw"   => Self::ViewportRelative(ViewportRelativeLength::Vw(value)),
@@ -402,6 +434,7 @@ impl From<ViewportRelativeLength> for NoCalcLength {
 /// A container for any specific length type, including where the value is a css `calc()` function
 /// See also: https://drafts.csswg.org/css-values/#lengths
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Length {
     NoCalc(NoCalcLength),
     //Calc(Box<???>), @todo Add support for css `calc()` functions
@@ -417,16 +450,23 @@ impl Length {
         value
     }
 
-    /// It is the caller's responsibility to only pass `Token::Function` tokens
-    pub(super) fn from_func_token<'i>(
-        token: &Token<'i>,
-        _allowed_values: AllowedValues,
-    ) -> Result<Self, BevyCssParsingErrorKind<'i>> {
-        assert!(matches!(token, Token::Function(_)));
-        if let Token::Function(ref name) = *token {
+    #[inline]
+    pub fn to_computed_px(&self, context: &CssContext) -> f32 {
+        match self {
+            Self::NoCalc(len) => len.to_computed_px(context),
             // @todo Add support for css `calc()` functions
-            Err(BevyCssParsingErrorKind::FunctionNotSupported(name.to_owned()))
-        } else { unreachable!() }
+        }
+    }
+
+    /// Parses a css math function (e.g. `min()`), where its `Token::Function` has already been consumed
+    pub(super) fn parse_function<'i, 't>(
+        start: SourceLocation,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+        allowed_values: AllowedValues,
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        // @todo Add support for css `calc()` functions
+        MathFunction::parse(start, name, input, |i| Self::parse_internal(i, allowed_values))
     }
 
     pub fn parse_internal<'i, 't>(
@@ -444,9 +484,13 @@ impl Length {
                 NoCalcLength::from_num_token(token, allowed_values)
                     .map(Self::NoCalc)
                     .map_err(|err| start.new_custom_error(err)),
-            Token::Function { .. } =>
-                Self::from_func_token(token, allowed_values)
-                    .map_err(|err| start.new_custom_error(err)),
+            Token::Function(ref name) => {
+                let name = name.clone();
+                Self::parse_function(start, name, input, allowed_values)
+            },
+            // Anywhere `auto` is allowed, it is parsed before the length (e.g. by `MaybeAuto`)
+            Token::Ident(ref ident) if ident.eq_ignore_ascii_case("auto") =>
+                Err(start.new_custom_error(BevyCssParsingErrorKind::AutoNotAllowed)),
             _ => Err(start.new_unexpected_token_error(token.clone()))
         }
     }
@@ -551,6 +595,7 @@ impl Parse for Length {
 /// A container for any specific length (inc. css `calc()`) where percentage (`%`) can be used too
 /// See also: https://drafts.csswg.org/css-values-4/#typedef-length-percentage
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LengthPercentage {
     Length(NoCalcLength),
     Percentage(Percentage),
@@ -566,16 +611,15 @@ impl LengthPercentage {
         }
     }
 
-    /// It is the caller's responsibility to only pass `Token::Function` tokens
-    pub(super) fn from_func_token<'i>(
-        token: &Token<'i>,
-        _allowed_values: AllowedValues,
-    ) -> Result<Self, BevyCssParsingErrorKind<'i>> {
-        assert!(matches!(token, Token::Function(_)));
-        if let Token::Function(ref name) = *token {
-            // @todo Add support for css `calc()` functions
-            Err(BevyCssParsingErrorKind::FunctionNotSupported(name.to_owned()))
-        } else { unreachable!() }
+    /// Parses a css math function (e.g. `min()`), where its `Token::Function` has already been consumed
+    pub(super) fn parse_function<'i, 't>(
+        start: SourceLocation,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+        allowed_values: AllowedValues,
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        // @todo Add support for css `calc()` functions
+        MathFunction::parse(start, name, input, |i| Self::parse_internal(i, allowed_values))
     }
 
     pub fn parse_internal<'i, 't>(
@@ -597,9 +641,13 @@ impl LengthPercentage {
                 NoCalcLength::from_num_token(token, allowed_values)
                     .map(Self::Length)
                     .map_err(|err| start.new_custom_error(err)),
-            Token::Function { .. } =>
-                Self::from_func_token(token, allowed_values)
-                    .map_err(|err| start.new_custom_error(err)),
+            Token::Function(ref name) => {
+                let name = name.clone();
+                Self::parse_function(start, name, input, allowed_values)
+            },
+            // Anywhere `auto` is allowed, it is parsed before the length (e.g. by `MaybeAuto`)
+            Token::Ident(ref ident) if ident.eq_ignore_ascii_case("auto") =>
+                Err(start.new_custom_error(BevyCssParsingErrorKind::AutoNotAllowed)),
             _ => Err(start.new_unexpected_token_error(token.clone()))
         }
     }
@@ -753,8 +801,149 @@ impl Parse for NonNegativeLengthPercentage {
 /// A wrapper around `LengthPercentage` that allows the use of `auto`
 pub type LengthPercentageOrAuto = MaybeAuto<LengthPercentage>;
 
+/// The value of sizing properties (e.g. `width`), where the intrinsic sizing keywords (e.g.
+/// `min-content`) can be used as well as `auto`
+pub type Size = MaybeIntrinsic<LengthPercentageOrAuto>;
+
 //// A wrapper around `NonNegativeLength` that allows the use of `auto`
 //pub type NonNegativeLengthOrAuto = MaybeAuto<NonNegativeLength>;
 
 //// A wrapper around `NonNegativeLengthPercentage` that allows the use of `auto`
-//pub type NonNegativeLengthPercentageOrAuto = MaybeAuto<NonNegativeLengthPercentage>;
\ No newline at end of file
+//pub type NonNegativeLengthPercentageOrAuto = MaybeAuto<NonNegativeLengthPercentage>;
+
+impl ToCss for FontRelativeLength {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        let unit = match *self {
+            Self::Em(_)  => "em",
+            Self::Rem(_) => "rem",
+            Self::Ex(_)  => "ex",
+            Self::Ch(_)  => "ch",
+            Self::Lh(_)  => "lh",
+            Self::Rlh(_) => "rlh",
+        };
+        write!(dest, "{}{}", self.raw_value(), unit)
+    }
+}
+
+impl ToCss for ViewportRelativeLength {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        let unit = match *self {
+            Self::Vw(_)   => "vw",
+            Self::Vh(_)   => "vh",
+            Self::Vmin(_) => "vmin",
+            Self::Vmax(_) => "vmax",
+        };
+        write!(dest, "{}{}", self.raw_value(), unit)
+    }
+}
+
+impl ToCss for NoCalcLength {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Absolute(ref length) => length.to_css(dest),
+            Self::FontRelative(ref length) => length.to_css(dest),
+            Self::ViewportRelative(ref length) => length.to_css(dest),
+        }
+    }
+}
+
+impl ToCss for Length {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::NoCalc(ref length) => length.to_css(dest),
+        }
+    }
+}
+
+impl ToCss for LengthPercentage {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Length(ref length) => length.to_css(dest),
+            Self::Percentage(ref pc) => pc.to_css(dest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::{ParseErrorKind, ParserInput};
+    use crate::errors::BevyCssContextualError;
+    use super::*;
+
+    fn dim_token_to_length(css: &str) -> NoCalcLength {
+        let mut parser_input = ParserInput::new(css);
+        let mut input = Parser::new(&mut parser_input);
+        let token = input.next().unwrap().clone();
+        NoCalcLength::from_dim_token(&token, AllowedValues::All).unwrap()
+    }
+
+    #[test]
+    fn test_parse_dimension() {
+        assert_eq!(
+            NoCalcLength::parse_dimension(&"px".into(), 100.0).unwrap(),
+            NoCalcLength::Absolute(AbsoluteLength::Px(100.0))
+        );
+        assert_eq!(
+            NoCalcLength::parse_dimension(&"EM".into(), 1.5).unwrap(),
+            NoCalcLength::FontRelative(FontRelativeLength::Em(1.5))
+        );
+        assert!(NoCalcLength::parse_dimension(&"foo".into(), 1.0).is_err());
+    }
+
+    #[test]
+    fn test_exponent() {
+        assert_eq!(dim_token_to_length("1e2px"), NoCalcLength::from(100.0));
+        assert_eq!(dim_token_to_length("1E2px"), NoCalcLength::from(100.0));
+        assert_eq!(dim_token_to_length("2.5e1px"), NoCalcLength::from(25.0));
+        assert_eq!(dim_token_to_length("1e+2px"), NoCalcLength::from(100.0));
+        assert_eq!(dim_token_to_length("1e-1em"), NoCalcLength::from(FontRelativeLength::Em(0.1)));
+    }
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(dim_token_to_length("+1.5em"), NoCalcLength::from(FontRelativeLength::Em(1.5)));
+        assert_eq!(dim_token_to_length("-1.5em"), NoCalcLength::from(FontRelativeLength::Em(-1.5)));
+        assert_eq!(dim_token_to_length("-1e2px"), NoCalcLength::from(-100.0));
+        assert_eq!(Length::parse_str("+1.5em").unwrap(), Length::from(FontRelativeLength::Em(1.5)));
+        assert_eq!(LengthPercentage::parse_str("+1e2px").unwrap(), LengthPercentage::from(100.0));
+    }
+
+    #[test]
+    fn test_negative_not_allowed() {
+        assert!(NonNegativeLengthPercentage::parse_str("-1e2px").is_err());
+        assert!(NonNegativeLengthPercentage::parse_str("+1e2px").is_ok());
+    }
+
+    #[test]
+    fn test_font_metrics() {
+        let mut context = CssContext { font_size: 10.0, ..Default::default() };
+        let computed = |css, context: &CssContext| Length::parse_str(css).unwrap().to_computed_px(context);
+        // The defaults are assumed
+        assert_eq!(computed("1ex", &context), 5.0);
+        assert_eq!(computed("2ch", &context), 10.0);
+        context.x_height_ratio = 0.6;
+        context.zero_advance_ratio = 0.55;
+        assert_eq!(computed("1ex", &context), 6.0);
+        assert_eq!(computed("2ch", &context), 11.0);
+        // Vertical text uses the advance height
+        context.vertical_text = true;
+        assert_eq!(computed("2ch", &context), 20.0);
+    }
+
+    #[test]
+    fn test_auto_not_allowed() {
+        let is_auto_error = |result: Result<_, BevyCssParsingError>| matches!(
+            result.map_err(|err| err.kind),
+            Err(ParseErrorKind::Custom(BevyCssParsingErrorKind::AutoNotAllowed))
+        );
+        assert!(is_auto_error(Length::parse_str("auto").map(|_| ())));
+        assert!(is_auto_error(NonNegativeLengthPercentage::parse_str("AUTO").map(|_| ())));
+        assert!(LengthPercentageOrAuto::parse_str("auto").is_ok());
+
+        let err = Length::parse_str("auto").unwrap_err();
+        assert_eq!(
+            BevyCssContextualError::InvalidValue("auto", err).to_string(),
+            "The value of a property is invalid: 'auto' is not valid here"
+        );
+    }
+}
\ No newline at end of file
@@ -0,0 +1,93 @@
+use std::fmt;
+use cssparser::{Parser, ToCss};
+use crate::{
+    context::CssContext,
+    errors::BevyCssParsingError,
+    values::{
+        LengthPercentage, NonNegativeLengthPercentage, NonNegativeNumber, Parse,
+    },
+};
+
+/// The line height used for `normal`, as a multiple of the font size.  Browsers use a value from
+/// the font itself, but this is the usual default.
+pub const NORMAL_LINE_HEIGHT: f32 = 1.2;
+
+/// The value of the `line-height` property.  A unitless number is a multiple of the font size, and a
+/// percentage is of the font size.
+/// See also: https://drafts.csswg.org/css-inline-3/#line-height-property
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineHeight {
+    Normal,
+    Number(NonNegativeNumber),
+    LengthPercentage(NonNegativeLengthPercentage),
+}
+
+impl LineHeight {
+    /// The line height in pixels, for the font size of the `context`.  `None` for `normal`.
+    pub fn to_computed_px(&self, context: &CssContext) -> Option<f32> {
+        match *self {
+            Self::Normal => None,
+            Self::Number(number) => Some(number.0.0 * context.font_size),
+            Self::LengthPercentage(length) => Some(match length.0 {
+                LengthPercentage::Length(length) => length.to_computed_px(context),
+                LengthPercentage::Percentage(pc) => pc.as_fraction() * context.font_size,
+            }),
+        }
+    }
+}
+
+impl Parse for LineHeight {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("normal")).is_ok() {
+            return Ok(Self::Normal)
+        }
+        // The number is tried first, so a unitless zero is a number rather than a length
+        if let Ok(number) = input.try_parse(NonNegativeNumber::parse) {
+            return Ok(Self::Number(number))
+        }
+        NonNegativeLengthPercentage::parse(input).map(Self::LengthPercentage)
+    }
+}
+
+impl ToCss for LineHeight {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Normal => dest.write_str("normal"),
+            Self::Number(ref number) => number.to_css(dest),
+            Self::LengthPercentage(ref length) => length.to_css(dest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::values::{
+        generic::NonNegative,
+        Number,
+    };
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(LineHeight::parse_str("normal").unwrap(), LineHeight::Normal);
+        assert_eq!(LineHeight::parse_str("1.5").unwrap(), LineHeight::Number(NonNegative(Number(1.5))));
+        assert_eq!(LineHeight::parse_str("0").unwrap(), LineHeight::Number(NonNegative(Number(0.0))));
+        assert!(matches!(LineHeight::parse_str("20px").unwrap(), LineHeight::LengthPercentage(_)));
+        assert!(matches!(LineHeight::parse_str("150%").unwrap(), LineHeight::LengthPercentage(_)));
+        assert!(LineHeight::parse_str("-1").is_err());
+        assert!(LineHeight::parse_str("-10px").is_err());
+        assert!(LineHeight::parse_str("auto").is_err());
+    }
+
+    #[test]
+    fn test_to_computed_px() {
+        let context = CssContext { font_size: 20.0, ..Default::default() };
+        let computed = |css| LineHeight::parse_str(css).unwrap().to_computed_px(&context);
+        assert_eq!(computed("normal"), None);
+        assert_eq!(computed("1.5"), Some(30.0));
+        assert_eq!(computed("150%"), Some(30.0));
+        assert_eq!(computed("25px"), Some(25.0));
+        assert_eq!(computed("2em"), Some(40.0));
+    }
+}
\ No newline at end of file
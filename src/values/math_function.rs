@@ -0,0 +1,136 @@
+use std::cmp::Ordering;
+use cssparser::{
+    CowRcStr, Parser, SourceLocation, match_ignore_ascii_case, _cssparser_internal_to_lowercase
+};
+use crate::errors::{BevyCssParsingError, BevyCssParsingErrorKind};
+
+/// The css math functions that can be resolved while parsing, i.e. where every argument has the
+/// same type and unit
+/// See also: https://drafts.csswg.org/css-values-4/#comp-func
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MathFunction {
+    Min,
+    Max,
+}
+
+impl MathFunction {
+    pub fn from_name<'i>(name: &CowRcStr<'i>) -> Option<Self> {
+        Some(match_ignore_ascii_case! { name,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            _ => return None,
+        })
+    }
+
+    /// Parses the arguments of the function `name`, where its `Token::Function` has already been
+    /// consumed.  Each (comma separated) argument is parsed with `parse_arg`.
+    pub fn parse<'i, 't, T: PartialOrd>(
+        start: SourceLocation,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+        parse_arg: impl Fn(&mut Parser<'i, '_>) -> Result<T, BevyCssParsingError<'i>>,
+    ) -> Result<T, BevyCssParsingError<'i>> {
+        let function = Self::from_name(&name).ok_or_else(||
+            start.new_custom_error(BevyCssParsingErrorKind::FunctionNotSupported(name.clone()))
+        )?;
+        let args = input.parse_nested_block(|i| {
+            if i.is_exhausted() {
+                return Err(i.new_custom_error(BevyCssParsingErrorKind::MissingArguments(name.clone())))
+            }
+            i.parse_comma_separated(|arg| parse_arg(arg))
+        })?;
+
+        let wanted = match function {
+            Self::Min => Ordering::Less,
+            Self::Max => Ordering::Greater,
+        };
+        let mut args = args.into_iter();
+        // `parse_comma_separated` always returns at least one value
+        let mut result = args.next().unwrap();
+        for arg in args {
+            match arg.partial_cmp(&result) {
+                Some(ordering) if ordering == wanted => result = arg,
+                Some(_) => {},
+                // @todo Add support for css `calc()` functions, so mixed units can be compared
+                None => return Err(start.new_custom_error(
+                    BevyCssParsingErrorKind::FunctionNotSupported(name)
+                )),
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::{ParseErrorKind, ParserInput};
+    use crate::values::{
+        AbsoluteLength, Length, LengthPercentage, Number, Parse,
+        length::FontRelativeLength,
+        percentage::Percentage,
+    };
+    use super::*;
+
+    #[test]
+    fn test_single_argument() {
+        assert_eq!(Length::parse_str("min(10px)").unwrap(), Length::from(10.0));
+        assert_eq!(Length::parse_str("max(10px)").unwrap(), Length::from(10.0));
+        assert_eq!(Number::parse_str("min(2)").unwrap(), Number(2.0));
+        assert_eq!(
+            LengthPercentage::parse_str("max(50%)").unwrap(),
+            LengthPercentage::from(Percentage::new(0.5))
+        );
+    }
+
+    #[test]
+    fn test_multiple_arguments() {
+        assert_eq!(Length::parse_str("min(10px, 5px, 20px)").unwrap(), Length::from(5.0));
+        assert_eq!(Length::parse_str("MAX(10px, 5px, 20px)").unwrap(), Length::from(20.0));
+        assert_eq!(
+            Length::parse_str("min(2em, 1em)").unwrap(),
+            Length::from(FontRelativeLength::Em(1.0))
+        );
+        assert_eq!(
+            Length::parse_str("max(1in, 2in)").unwrap(),
+            Length::from(AbsoluteLength::In(2.0))
+        );
+    }
+
+    #[test]
+    fn test_no_arguments() {
+        for css in ["min()", "max( )", "min(/* nothing */)"] {
+            let err = Length::parse_str(css).unwrap_err();
+            assert!(matches!(
+                err.kind,
+                ParseErrorKind::Custom(BevyCssParsingErrorKind::MissingArguments(..))
+            ), "{} should not parse", css);
+        }
+    }
+
+    #[test]
+    fn test_mixed_units() {
+        let err = Length::parse_str("min(10px, 1em)").unwrap_err();
+        assert!(matches!(
+            err.kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::FunctionNotSupported(..))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_function() {
+        let err = Length::parse_str("clamp(1px, 2px, 3px)").unwrap_err();
+        assert!(matches!(
+            err.kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::FunctionNotSupported(..))
+        ));
+    }
+
+    #[test]
+    fn test_rest_of_input() {
+        let mut parser_input = ParserInput::new("min(10px) 20px");
+        let mut input = Parser::new(&mut parser_input);
+        assert_eq!(Length::parse(&mut input).unwrap(), Length::from(10.0));
+        assert_eq!(Length::parse(&mut input).unwrap(), Length::from(20.0));
+        assert!(input.is_exhausted());
+    }
+}
\ No newline at end of file
@@ -1,9 +1,10 @@
-use std::ops::Mul;
-use cssparser::{Parser, Token};
+use std::{fmt, ops::Mul};
+use cssparser::{CowRcStr, Parser, SourceLocation, ToCss, Token};
 use crate::{
-    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    errors::{notice, BevyCssParsingError, BevyCssParsingErrorKind},
     values::{
         generic::{NonNegative, Numeric},
+        math_function::MathFunction,
         parse::{AllowedValues, Parse},
     },
 };
@@ -11,6 +12,7 @@ use crate::{
 /// A bare number, without units or `%`.  Can be teh result of a `calc()` css function.
 /// See also: https://drafts.csswg.org/css-values-3/#number-value
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Number(pub f32);
 
 impl Number {
@@ -32,16 +34,15 @@ impl Number {
         } else { unreachable!() }
     }
 
-    /// It is the caller's responsibility to only pass `Token::Function` tokens
-    pub(super) fn from_func_token<'i>(
-        token: &Token<'i>,
-        _allowed_values: AllowedValues,
-    ) -> Result<Self, BevyCssParsingErrorKind<'i>> {
-        assert!(matches!(token, Token::Function(_)));
-        if let Token::Function(ref name) = *token {
-            // @todo Add support for css `calc()` functions
-            Err(BevyCssParsingErrorKind::FunctionNotSupported(name.to_owned()))
-        } else { unreachable!() }
+    /// Parses a css math function (e.g. `min()`), where its `Token::Function` has already been consumed
+    pub(super) fn parse_function<'i, 't>(
+        start: SourceLocation,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+        allowed_values: AllowedValues,
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        // @todo Add support for css `calc()` functions
+        MathFunction::parse(start, name, input, |i| Self::parse_internal(i, allowed_values))
     }
 
     pub fn parse_internal<'i, 't>(
@@ -54,9 +55,10 @@ impl Number {
             Token::Number { .. } =>
                 Self::from_num_token(token, allowed_values)
                     .map_err(|err| start.new_custom_error(err)),
-            Token::Function { .. } =>
-                Self::from_func_token(token, allowed_values)
-                    .map_err(|err| start.new_custom_error(err)),
+            Token::Function(ref name) => {
+                let name = name.clone();
+                Self::parse_function(start, name, input, allowed_values)
+            },
             _ => Err(start.new_unexpected_token_error(token.clone()))
         }
     }
@@ -128,8 +130,158 @@ impl Parse for NonNegativeNumber {
     }
 }
 
+/// Parses a number that css clamps to a range (e.g. `opacity: 1.5` is `opacity: 1`), rather than
+/// rejecting.  A `notice()` is given where the value has to be clamped.
+pub fn parse_in_range<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    min: f32,
+    max: f32,
+) -> Result<Number, BevyCssParsingError<'i>> {
+    let allowed_values = AllowedValues::Range(min, max);
+    let Number(value) = Number::parse(input)?;
+    if allowed_values.is_ok(value) {
+        Ok(Number(value))
+    } else {
+        let clamped = allowed_values.clamp(value);
+        notice(out_of_range_message(value, clamped, allowed_values));
+        Ok(Number(clamped))
+    }
+}
+
+#[inline]
+fn out_of_range_message(value: f32, clamped: f32, allowed_values: AllowedValues) -> String {
+    format!("The value `{}` is outside of the allowed {}, so `{}` will be used", value, allowed_values, clamped)
+}
+
+/// A number from `0` (transparent) to `1` (opaque), where values outside of the range are clamped
+/// See also: https://drafts.csswg.org/css-color/#transparency
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AlphaValue(pub f32);
+
+impl Parse for AlphaValue {
+    #[inline]
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        parse_in_range(input, 0.0, 1.0).map(|Number(value)| Self(value))
+    }
+}
+
+/// A whole number, without units or `%` (e.g. `2` or `-1`, but not `1.5`)
+/// See also: https://drafts.csswg.org/css-values-3/#integers
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Integer(pub i32);
+
+impl Integer {
+    /// Parses a css math function (e.g. `min()`), where its `Token::Function` has already been consumed
+    pub(super) fn parse_function<'i, 't>(
+        start: SourceLocation,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        MathFunction::parse(start, name, input, Self::parse)
+    }
+}
+
+impl From<Integer> for i32 {
+    #[inline]
+    fn from(int: Integer) -> Self {
+        int.0
+    }
+}
+
+impl Parse for Integer {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let token = input.next()?;
+        match *token {
+            Token::Number { int_value: Some(value), .. } => Ok(Self(value)),
+            Token::Number { .. } => Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidValue("Integer".into(), Some(token.clone()))
+            )),
+            Token::Function(ref name) => {
+                let name = name.clone();
+                Self::parse_function(start, name, input)
+            },
+            _ => Err(start.new_unexpected_token_error(token.clone()))
+        }
+    }
+}
+
 //// A wrapper around `Number` that allows the use of `auto`
 //pub type NumberOrAuto = MaybeAuto<Number>;
 
 //// A wrapper around `NonNegativeLength` that allows the use of `auto`
-//pub type NonNegativeNumberOrAuto = MaybeAuto<NonNegativeNumber>;
\ No newline at end of file
+//pub type NonNegativeNumberOrAuto = MaybeAuto<NonNegativeNumber>;
+
+impl ToCss for Number {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        write!(dest, "{}", self.0)
+    }
+}
+
+impl ToCss for AlphaValue {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        write!(dest, "{}", self.0)
+    }
+}
+
+impl ToCss for Integer {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        write!(dest, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::ParseErrorKind;
+    use super::*;
+
+    #[test]
+    fn test_parse_in_range() {
+        let in_range = |css| {
+            let mut parser_input = cssparser::ParserInput::new(css);
+            let mut input = Parser::new(&mut parser_input);
+            parse_in_range(&mut input, 0.0, 1.0)
+        };
+        assert_eq!(in_range("0.5").unwrap(), Number(0.5));
+        assert_eq!(in_range("1").unwrap(), Number(1.0));
+        assert_eq!(in_range("1.5").unwrap(), Number(1.0));
+        assert_eq!(in_range("-2").unwrap(), Number(0.0));
+        assert_eq!(in_range("max(0.2, 0.4)").unwrap(), Number(0.4));
+        assert!(in_range("50%").is_err());
+        assert!(in_range("1px").is_err());
+        assert_eq!(AlphaValue::parse_str("1.5").unwrap(), AlphaValue(1.0));
+        // Clamping gives a notice, rather than logging
+        let (_, notices) = crate::errors::collect_notices(|| in_range("1.5"));
+        assert_eq!(notices, vec![out_of_range_message(1.5, 1.0, AllowedValues::Range(0.0, 1.0))]);
+        assert!(crate::errors::collect_notices(|| in_range("0.5")).1.is_empty());
+    }
+
+    #[test]
+    fn test_out_of_range_message() {
+        assert_eq!(
+            out_of_range_message(1.5, 1.0, AllowedValues::Range(0.0, 1.0)),
+            "The value `1.5` is outside of the allowed Range(0 to 1), so `1` will be used"
+        );
+    }
+
+    #[test]
+    fn test_integer() {
+        assert_eq!(Integer::parse_str("2").unwrap(), Integer(2));
+        assert_eq!(Integer::parse_str("+3").unwrap(), Integer(3));
+        assert_eq!(Integer::parse_str("-1").unwrap(), Integer(-1));
+        assert_eq!(Integer::parse_str("0").unwrap(), Integer(0));
+        assert_eq!(Integer::parse_str("max(-1, 4)").unwrap(), Integer(4));
+    }
+
+    #[test]
+    fn test_integer_errors() {
+        assert!(matches!(
+            Integer::parse_str("1.5").unwrap_err().kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::InvalidValue(_, _))
+        ));
+        assert!(Integer::parse_str("2px").is_err());
+        assert!(Integer::parse_str("50%").is_err());
+    }
+}
\ No newline at end of file
@@ -32,10 +32,13 @@ impl<P: Parse> Parse for Option<P> {
 
 /// Used to (possibly) restrict the range of values an internal parsing function will take.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AllowedValues {
     All,
     NonNegative,
     AtLeastOne,
+    /// Between the two values (inclusive)
+    Range(f32, f32),
 }
 
 impl Default for AllowedValues {
@@ -47,7 +50,10 @@ impl Default for AllowedValues {
 
 impl fmt::Display for AllowedValues {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match *self {
+            Self::Range(min, max) => write!(f, "Range({} to {})", min, max),
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
@@ -64,6 +70,7 @@ impl AllowedValues {
             Self::All => true,
             Self::NonNegative => value >= 0.0,
             Self::AtLeastOne => value >= 1.0,
+            Self::Range(min, max) => (min..=max).contains(&value),
         }
     }
 
@@ -72,6 +79,7 @@ impl AllowedValues {
         match *self {
             Self::NonNegative if value < 0.0 => 0.0,
             Self::AtLeastOne if value < 1.0 => 1.0,
+            Self::Range(min, max) => value.clamp(min, max),
             _ => value
         }
     }
@@ -1,12 +1,14 @@
 use std::{
     cmp::Ordering,
+    fmt,
     ops::Mul,
 };
-use cssparser::{Parser, Token};
+use cssparser::{CowRcStr, Parser, SourceLocation, ToCss, Token};
 use crate::{
     errors::{BevyCssParsingError, BevyCssParsingErrorKind},
     values::{
         generic::Numeric,
+        math_function::MathFunction,
         parse::{AllowedValues, Parse},
     }
 };
@@ -14,6 +16,7 @@ use crate::{
 /// A `percentage` as specified in CSS with `<number>%`; and is some fraction of a reference
 /// See also: https://drafts.csswg.org/css-values-4/#percentages
 #[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Percentage {
     /// `0%` to `100%` maps to `0.0` to `1.0` as a float
     pub value: f32,
@@ -106,16 +109,15 @@ impl Percentage {
         } else { unreachable!() }
     }
 
-    /// It is the caller's responsibility to only pass `Token::Function` tokens
-    pub(super) fn from_func_token<'i>(
-        token: &Token<'i>,
-        _allowed_values: AllowedValues,
-    ) -> Result<Self, BevyCssParsingErrorKind<'i>> {
-        assert!(matches!(token, Token::Function(_)));
-        if let Token::Function(ref name) = *token {
-            // @todo Add support for css `calc()` functions
-            Err(BevyCssParsingErrorKind::FunctionNotSupported(name.to_owned()))
-        } else { unreachable!() }
+    /// Parses a css math function (e.g. `min()`), where its `Token::Function` has already been consumed
+    pub(super) fn parse_function<'i, 't>(
+        start: SourceLocation,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+        allowed_values: AllowedValues,
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        // @todo Add support for css `calc()` functions
+        MathFunction::parse(start, name, input, |i| Self::parse_internal(i, allowed_values))
     }
 
     pub(super) fn parse_internal<'i, 't>(
@@ -128,9 +130,10 @@ impl Percentage {
             Token::Percentage { .. } =>
                 Self::from_pc_token(token, allowed_values)
                     .map_err(|err| start.new_custom_error(err)),
-            Token::Function(_) =>
-                Self::from_func_token(token, allowed_values)
-                    .map_err(|err| start.new_custom_error(err)),
+            Token::Function(ref name) => {
+                let name = name.clone();
+                Self::parse_function(start, name, input, allowed_values)
+            },
             _ => Err(start.new_unexpected_token_error(token.clone())),
         }
     }
@@ -186,4 +189,10 @@ impl Parse for Percentage {
     fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
         Self::parse_internal(input, AllowedValues::All)
     }
+}
+
+impl ToCss for Percentage {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        write!(dest, "{}%", self.value * 100.0)
+    }
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
-use std::cmp::Ordering;
-use cssparser::Parser;
+use std::{cmp::Ordering, fmt};
+use cssparser::{Parser, ToCss};
 use crate::errors::BevyCssParsingError;
 use crate::values::{
     generic::{MaybeAuto, Numeric},
@@ -10,6 +10,7 @@ use crate::values::{
 /// See also: https://drafts.csswg.org/css-values-4/#ratio-value
 /// See also: https://developer.mozilla.org/en-US/docs/Web/CSS/ratio
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ratio(pub NonNegativeNumber, pub NonNegativeNumber);
 
 impl Ratio {
@@ -47,6 +48,18 @@ impl Parse for Ratio {
 /// A ratio, where the `auto` keyword could be used as well
 pub type RatioOrAuto = MaybeAuto<Ratio>;
 
+impl ToCss for Ratio {
+    /// A ratio with a second number of `1` is written as just the first (e.g. `1.5`)
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        self.0.to_css(dest)?;
+        if self.1 != NonNegativeNumber::one() {
+            dest.write_str(" / ")?;
+            self.1.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::values::generic::NonNegative;
@@ -119,6 +132,14 @@ mod tests {
         assert_eq!(Ratio::parse_str("0.5").unwrap().as_fraction(), 0.5);
     }
 
+    #[test]
+    fn test_parse_spacing() {
+        // Whitespace around the `/` is optional
+        for css in ["16/9", "16 / 9", "16 /9", "16/ 9", "16/*comment*/9", "16.0/9.0"] {
+            assert_eq!(Ratio::parse_str(css).unwrap(), ratio_from(16.0, 9.0), "{}", css);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_bad_ratio() {
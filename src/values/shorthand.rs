@@ -1,10 +1,12 @@
-use cssparser::Parser;
+use std::fmt;
+use cssparser::{Parser, ToCss};
 use crate::{
     errors::BevyCssParsingError,
     values::Parse,
 };
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SidedValue<T> {
     pub top: T,
     pub right: T,
@@ -61,6 +63,78 @@ impl<T: Parse + Clone + Copy> Parse for SidedValue<T> {
     }
 }
 
+/// A shorthand value where 1 or 2 values can be given, e.g. `gap: 1px 2px;`.  If only one value is
+/// given, it is used for both.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PairedValue<T> {
+    pub first: T,
+    pub second: T,
+}
+
+impl<T: Clone + Copy> PairedValue<T> {
+    #[inline]
+    pub(crate) fn new_2(first: T, second: T) -> Self {
+        Self { first, second, }
+    }
+
+    #[inline]
+    pub(crate) fn new_1(value: T) -> Self {
+        Self { first: value, second: value, }
+    }
+
+    pub fn parse_internal<'i, 't>(
+        input: &mut Parser<'i, 't>,
+        value_parser: impl Fn(&mut Parser<'i, 't>) -> Result<T, BevyCssParsingError<'i>>,
+    ) -> Result<Self, BevyCssParsingError<'i>> {
+        let first = value_parser(input)?;
+        match input.try_parse(|i| value_parser(i)) {
+            Ok(second) => Ok(Self::new_2(first, second)),
+            // only 1 value was given
+            Err(_) => Ok(Self::new_1(first)),
+        }
+    }
+}
+
+impl<T: Parse + Clone + Copy> Parse for PairedValue<T> {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        Self::parse_internal(input, <T as Parse>::parse)
+    }
+}
+
+impl<T: ToCss + PartialEq> ToCss for SidedValue<T> {
+    /// The fewest values that give the same sides, e.g. `1px 2px` rather than `1px 2px 1px 2px`
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        self.top.to_css(dest)?;
+        if self.left == self.right && self.bottom == self.top && self.right == self.top {
+            return Ok(())
+        }
+        dest.write_char(' ')?;
+        self.right.to_css(dest)?;
+        if self.left == self.right && self.bottom == self.top {
+            return Ok(())
+        }
+        dest.write_char(' ')?;
+        self.bottom.to_css(dest)?;
+        if self.left == self.right {
+            return Ok(())
+        }
+        dest.write_char(' ')?;
+        self.left.to_css(dest)
+    }
+}
+
+impl<T: ToCss + PartialEq> ToCss for PairedValue<T> {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        self.first.to_css(dest)?;
+        if self.second != self.first {
+            dest.write_char(' ')?;
+            self.second.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::values::Number;
@@ -145,4 +219,25 @@ mod tests {
         SidedValue::<Number>::parse_str("1 2 3 4 5").unwrap();
     }
 
+    #[test]
+    fn test_paired_parse() {
+        assert_eq!(
+            PairedValue::<Number>::parse_str("1").unwrap(),
+            PairedValue { first: Number(1.0), second: Number(1.0) }
+        );
+        assert_eq!(
+            PairedValue::<Number>::parse_str("1 2").unwrap(),
+            PairedValue { first: Number(1.0), second: Number(2.0) }
+        );
+    }
+
+    #[test]
+    fn test_paired_bad_parse() {
+        assert!(PairedValue::<Number>::parse_str("").is_err());
+        let mut parser_input = cssparser::ParserInput::new("1 2 3");
+        let mut input = Parser::new(&mut parser_input);
+        assert!(PairedValue::<Number>::parse(&mut input).is_ok());
+        assert!(!input.is_exhausted());
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,77 @@
+use std::fmt;
+use cssparser::{
+    CowRcStr, Parser, ToCss, Token,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::Parse,
+};
+
+/// A duration (or delay), in seconds
+/// See also: https://drafts.csswg.org/css-values-3/#time
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Time(pub f32);
+
+impl Time {
+    #[inline]
+    pub fn seconds(&self) -> f32 {
+        self.0
+    }
+
+    pub fn parse_dimension<'i>(
+        unit: &CowRcStr<'i>,
+        value: f32
+    ) -> Result<Self, BevyCssParsingErrorKind<'i>> {
+        Ok(match_ignore_ascii_case! { unit,
+            "s"  => Self(value),
+            "ms" => Self(value / 1000.0),
+            _ => return Err(BevyCssParsingErrorKind::UnexpectedDimension(unit.clone()))
+        })
+    }
+}
+
+impl Parse for Time {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let token = input.next()?;
+        match *token {
+            Token::Dimension { value, ref unit, .. } =>
+                Self::parse_dimension(unit, value).map_err(|err| start.new_custom_error(err)),
+            // Unlike lengths & angles, even zero needs a unit
+            Token::Number { .. } =>
+                Err(start.new_custom_error(BevyCssParsingErrorKind::MissingDimension(token.clone()))),
+            _ => Err(start.new_unexpected_token_error(token.clone()))
+        }
+    }
+}
+
+impl ToCss for Time {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        write!(dest, "{}s", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cssparser::ParseErrorKind;
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Time::parse_str("0.3s").unwrap(), Time(0.3));
+        assert_eq!(Time::parse_str("250ms").unwrap(), Time(0.25));
+        assert_eq!(Time::parse_str("-1S").unwrap(), Time(-1.0));
+        assert_eq!(Time::parse_str("0s").unwrap().seconds(), 0.0);
+        assert!(matches!(
+            Time::parse_str("0").unwrap_err().kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::MissingDimension(_))
+        ));
+        assert!(matches!(
+            Time::parse_str("1px").unwrap_err().kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::UnexpectedDimension(_))
+        ));
+        assert!(Time::parse_str("fast").is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,246 @@
+use bevy::{
+    math::{Quat, Vec3},
+    transform::components::Transform,
+};
+use std::fmt;
+use cssparser::{
+    Parser, ToCss,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    context::CssContext,
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::{
+        generic::Numeric,
+        Angle, Length, Number, Parse,
+    },
+};
+
+/// A single css transform function.  Only the 2D functions that can be represented by a Bevy
+/// `Transform` are supported (e.g. no `skew()`, or percentages, which need the size of the node).
+/// `translateX()` etc. are stored as their `translate()` equivalents.
+/// See also: https://drafts.csswg.org/css-transforms-1/#transform-functions
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransformFunction {
+    Translate(Length, Length),
+    Rotate(Angle),
+    Scale(Number, Number),
+}
+
+impl TransformFunction {
+    /// Css is y-down with clockwise rotations, where Bevy is y-up with counter-clockwise rotations
+    pub fn to_transform(&self, context: &CssContext) -> Transform {
+        match *self {
+            Self::Translate(x, y) => Transform::from_xyz(
+                x.to_computed_px(context),
+                -y.to_computed_px(context),
+                0.0
+            ),
+            Self::Rotate(angle) => Transform::from_rotation(Quat::from_rotation_z(-angle.to_radians())),
+            Self::Scale(x, y) => Transform::from_scale(Vec3::new(x.0, y.0, 1.0)),
+        }
+    }
+}
+
+impl Parse for TransformFunction {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let name = input.expect_function()?.clone();
+        input.parse_nested_block(|i| {
+            Ok(match_ignore_ascii_case! { &name,
+                "translate" => {
+                    let x = Length::parse(i)?;
+                    let y = if i.try_parse(|i| i.expect_comma()).is_ok() {
+                        Length::parse(i)?
+                    } else {
+                        Length::zero()
+                    };
+                    Self::Translate(x, y)
+                },
+                "translatex" => Self::Translate(Length::parse(i)?, Length::zero()),
+                "translatey" => Self::Translate(Length::zero(), Length::parse(i)?),
+                "rotate" => Self::Rotate(Angle::parse(i)?),
+                "scale" => {
+                    let x = Number::parse(i)?;
+                    let y = if i.try_parse(|i| i.expect_comma()).is_ok() {
+                        Number::parse(i)?
+                    } else {
+                        x
+                    };
+                    Self::Scale(x, y)
+                },
+                "scalex" => Self::Scale(Number::parse(i)?, Number::one()),
+                "scaley" => Self::Scale(Number::one(), Number::parse(i)?),
+                _ => return Err(start.new_custom_error(
+                    BevyCssParsingErrorKind::FunctionNotSupported(name.clone())
+                )),
+            })
+        })
+    }
+}
+
+impl ToCss for TransformFunction {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::Translate(ref x, ref y) => {
+                dest.write_str("translate(")?;
+                x.to_css(dest)?;
+                dest.write_str(", ")?;
+                y.to_css(dest)?;
+            },
+            Self::Rotate(ref angle) => {
+                dest.write_str("rotate(")?;
+                angle.to_css(dest)?;
+            },
+            Self::Scale(ref x, ref y) => {
+                dest.write_str("scale(")?;
+                x.to_css(dest)?;
+                if y != x {
+                    dest.write_str(", ")?;
+                    y.to_css(dest)?;
+                }
+            },
+        }
+        dest.write_char(')')
+    }
+}
+
+/// A (space separated) list of transform functions, or `none`
+/// See also: https://drafts.csswg.org/css-transforms-1/#transform-property
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransformList(pub Vec<TransformFunction>);
+
+impl TransformList {
+    /// The functions are composed left-to-right, as successive matrix multiplications (i.e. the
+    /// right-most function is applied to the entity first)
+    pub fn to_transform(&self, context: &CssContext) -> Transform {
+        self.0.iter().fold(
+            Transform::identity(),
+            |transform, function| transform.mul_transform(function.to_transform(context))
+        )
+    }
+}
+
+impl Parse for TransformList {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
+            return Ok(Self::default())
+        }
+        let mut functions = vec![TransformFunction::parse(input)?];
+        while let Ok(function) = input.try_parse(TransformFunction::parse) {
+            functions.push(function)
+        }
+        Ok(Self(functions))
+    }
+}
+
+impl ToCss for TransformList {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        if self.0.is_empty() {
+            return dest.write_str("none")
+        }
+        for (n, function) in self.0.iter().enumerate() {
+            if n > 0 {
+                dest.write_char(' ')?;
+            }
+            function.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+    use cssparser::ParseErrorKind;
+    use super::*;
+
+    fn assert_vec3_eq(left: Vec3, right: Vec3) {
+        assert!(left.abs_diff_eq(right, 1e-4), "{:?} != {:?}", left, right);
+    }
+
+    #[test]
+    fn test_parse_functions() {
+        assert_eq!(
+            TransformFunction::parse_str("translate(10px, 2em)").unwrap(),
+            TransformFunction::Translate(Length::from(10.0), Length::parse_str("2em").unwrap())
+        );
+        assert_eq!(
+            TransformFunction::parse_str("translate(10px)").unwrap(),
+            TransformFunction::Translate(Length::from(10.0), Length::zero())
+        );
+        assert_eq!(
+            TransformFunction::parse_str("translateY(10px)").unwrap(),
+            TransformFunction::Translate(Length::zero(), Length::from(10.0))
+        );
+        assert_eq!(
+            TransformFunction::parse_str("rotate(45deg)").unwrap(),
+            TransformFunction::Rotate(Angle::Deg(45.0))
+        );
+        assert_eq!(
+            TransformFunction::parse_str("scale(2)").unwrap(),
+            TransformFunction::Scale(Number(2.0), Number(2.0))
+        );
+        assert_eq!(
+            TransformFunction::parse_str("scaleX(2)").unwrap(),
+            TransformFunction::Scale(Number(2.0), Number(1.0))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            TransformFunction::parse_str("skew(10deg)").unwrap_err().kind,
+            ParseErrorKind::Custom(BevyCssParsingErrorKind::FunctionNotSupported(_))
+        ));
+        assert!(TransformFunction::parse_str("translate(10%)").is_err());
+        assert!(TransformFunction::parse_str("rotate(45)").is_err());
+        assert!(TransformList::parse_str("").is_err());
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(TransformList::parse_str("none").unwrap(), TransformList(vec![]));
+        assert_eq!(
+            TransformList::parse_str("translate(10px, 20px) rotate(45deg) scale(2)").unwrap(),
+            TransformList(vec![
+                TransformFunction::Translate(Length::from(10.0), Length::from(20.0)),
+                TransformFunction::Rotate(Angle::Deg(45.0)),
+                TransformFunction::Scale(Number(2.0), Number(2.0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_to_transform() {
+        let context = CssContext::default();
+        let transform = TransformList::parse_str("translate(10px,0)").unwrap().to_transform(&context);
+        assert_vec3_eq(transform.translation, Vec3::new(10.0, 0.0, 0.0));
+
+        // Css y-down is Bevy y-up
+        let transform = TransformList::parse_str("translateY(10px)").unwrap().to_transform(&context);
+        assert_vec3_eq(transform.translation, Vec3::new(0.0, -10.0, 0.0));
+
+        let transform = TransformList::parse_str("rotate(45deg)").unwrap().to_transform(&context);
+        assert!(transform.rotation.abs_diff_eq(Quat::from_rotation_z(-FRAC_PI_4), 1e-6));
+    }
+
+    #[test]
+    fn test_composition_order() {
+        let context = CssContext::default();
+        // The scale applies to the following translation, but not the preceding one
+        let transform = TransformList::parse_str("translate(10px) scale(2) translate(10px)")
+            .unwrap()
+            .to_transform(&context);
+        assert_vec3_eq(transform.translation, Vec3::new(30.0, 0.0, 0.0));
+        assert_vec3_eq(transform.scale, Vec3::new(2.0, 2.0, 1.0));
+
+        // A translation after a quarter turn is rotated with it
+        let transform = TransformList::parse_str("rotate(90deg) translate(10px)")
+            .unwrap()
+            .to_transform(&context);
+        assert_vec3_eq(transform.translation, Vec3::new(0.0, -10.0, 0.0));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,278 @@
+use std::fmt;
+use cssparser::{
+    Parser, ToCss,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::{
+        easing::EasingFunction,
+        time::Time,
+        Parse,
+    },
+};
+
+/// Which property a transition applies to
+/// See also: https://drafts.csswg.org/css-transitions/#transition-property-property
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransitionProperty {
+    All,
+    None,
+    /// The (lowercase) property name.  It doesn't have to be a property this crate knows about.
+    Property(String),
+}
+
+impl Parse for TransitionProperty {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { ident,
+            "all" => Self::All,
+            "none" => Self::None,
+            // The css-wide keywords can't be used as property names
+            "initial" | "inherit" | "unset" | "default" => return Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidKeyword(ident.clone())
+            )),
+            _ => Self::Property(ident.to_ascii_lowercase())
+        })
+    }
+}
+
+/// A single transition of the `transition` property, e.g. `width 0.2s ease-in 1s`.  The parts can be
+/// given in any order, but the first time is always the duration & the second the delay.
+/// See also: https://drafts.csswg.org/css-transitions/#single-transition
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransitionSpec {
+    pub property: TransitionProperty,
+    pub duration: Time,
+    pub timing_function: EasingFunction,
+    pub delay: Time,
+}
+
+impl Default for TransitionSpec {
+    /// `all 0s ease 0s`
+    fn default() -> Self {
+        Self {
+            property: TransitionProperty::All,
+            duration: Time::default(),
+            timing_function: EasingFunction::default(),
+            delay: Time::default(),
+        }
+    }
+}
+
+impl Parse for TransitionSpec {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let mut property = None;
+        let mut duration = None;
+        let mut timing_function = None;
+        let mut delay = None;
+        loop {
+            if delay.is_none() {
+                let start = input.current_source_location();
+                if let Ok(time) = input.try_parse(Time::parse) {
+                    if duration.is_none() {
+                        // Unlike the delay, the duration can't be negative
+                        if time.seconds() < 0.0 {
+                            return Err(start.new_custom_error(BevyCssParsingErrorKind::InvalidValue(
+                                "transition durations can't be negative".into(),
+                                None,
+                            )))
+                        }
+                        duration = Some(time);
+                    } else {
+                        delay = Some(time);
+                    }
+                    continue
+                }
+            }
+            // Easing keywords (e.g. `linear`) are tried before property names
+            if timing_function.is_none() {
+                if let Ok(value) = input.try_parse(EasingFunction::parse) {
+                    timing_function = Some(value);
+                    continue
+                }
+            }
+            if property.is_none() {
+                if let Ok(value) = input.try_parse(TransitionProperty::parse) {
+                    property = Some(value);
+                    continue
+                }
+            }
+            break
+        }
+        if property.is_none() && duration.is_none() && timing_function.is_none() {
+            return Err(input.new_error_for_next_token())
+        }
+        let default = Self::default();
+        Ok(Self {
+            property: property.unwrap_or(default.property),
+            duration: duration.unwrap_or(default.duration),
+            timing_function: timing_function.unwrap_or(default.timing_function),
+            delay: delay.unwrap_or(default.delay),
+        })
+    }
+}
+
+/// The value of the `transition` property, a comma separated list of transitions
+/// See also: https://drafts.csswg.org/css-transitions/#transition-shorthand-property
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransitionList(pub Vec<TransitionSpec>);
+
+impl TransitionList {
+    /// The transition for the (lowercase) property name, if it has one.  A shorthand transitions
+    /// each of its longhands (e.g. `margin` transitions `margin-top`), and where several apply, the
+    /// last one is used.
+    pub fn for_property(&self, name: &str) -> Option<&TransitionSpec> {
+        self.0.iter().rev().find(|spec| match spec.property {
+            TransitionProperty::All => true,
+            TransitionProperty::None => false,
+            TransitionProperty::Property(ref property) =>
+                name.strip_prefix(property.as_str()).map_or(false, |rest| rest.is_empty() || rest.starts_with('-')),
+        })
+    }
+
+    /// The eased progress (from `0` to `1`) of the property's transition, `elapsed` seconds after
+    /// it started.  Properties without a transition (or with a `0s` duration) change immediately.
+    pub fn progress(&self, name: &str, elapsed: f32) -> f32 {
+        match self.for_property(name) {
+            Some(spec) if spec.duration.seconds() > 0.0 => {
+                let progress = (elapsed - spec.delay.seconds()) / spec.duration.seconds();
+                spec.timing_function.evaluate(progress.clamp(0.0, 1.0))
+            },
+            Some(spec) if elapsed < spec.delay.seconds() => 0.0,
+            _ => 1.0,
+        }
+    }
+
+    /// How long until every transition has finished, inc. the delays
+    pub fn total_duration(&self) -> f32 {
+        self.0.iter()
+            .filter(|spec| spec.property != TransitionProperty::None)
+            .map(|spec| spec.delay.seconds() + spec.duration.seconds())
+            .fold(0.0, f32::max)
+    }
+}
+
+impl Default for TransitionList {
+    fn default() -> Self {
+        Self(vec![TransitionSpec::default()])
+    }
+}
+
+impl Parse for TransitionList {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let specs = input.parse_comma_separated(TransitionSpec::parse)?;
+        // `none` is only allowed on its own
+        if specs.len() > 1 && specs.iter().any(|spec| spec.property == TransitionProperty::None) {
+            return Err(start.new_custom_error(BevyCssParsingErrorKind::InvalidValue(
+                "`none` can only be used as the only transition".into(),
+                None,
+            )))
+        }
+        Ok(Self(specs))
+    }
+}
+
+impl ToCss for TransitionProperty {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        match *self {
+            Self::All => dest.write_str("all"),
+            Self::None => dest.write_str("none"),
+            Self::Property(ref name) => dest.write_str(name),
+        }
+    }
+}
+
+impl ToCss for TransitionSpec {
+    /// The delay is only written when it isn't `0s`
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        self.property.to_css(dest)?;
+        dest.write_char(' ')?;
+        self.duration.to_css(dest)?;
+        dest.write_char(' ')?;
+        self.timing_function.to_css(dest)?;
+        if self.delay != Time::default() {
+            dest.write_char(' ')?;
+            self.delay.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+impl ToCss for TransitionList {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        for (n, spec) in self.0.iter().enumerate() {
+            if n > 0 {
+                dest.write_str(", ")?;
+            }
+            spec.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::values::easing::{EasingFunction, StepPosition};
+    use super::*;
+
+    #[test]
+    fn test_single_transition() {
+        assert_eq!(
+            TransitionSpec::parse_str("width 0.2s").unwrap(),
+            TransitionSpec {
+                property: TransitionProperty::Property("width".to_string()),
+                duration: Time(0.2),
+                ..Default::default()
+            }
+        );
+        // Any order, with the second time being the delay
+        assert_eq!(
+            TransitionSpec::parse_str("100ms step-end -1s Opacity").unwrap(),
+            TransitionSpec {
+                property: TransitionProperty::Property("opacity".to_string()),
+                duration: Time(0.1),
+                timing_function: EasingFunction::Steps(1, StepPosition::JumpEnd),
+                delay: Time(-1.0),
+            }
+        );
+        assert_eq!(TransitionSpec::parse_str("none").unwrap().property, TransitionProperty::None);
+        assert!(TransitionSpec::parse_str("-1s").is_err());
+        assert!(TransitionSpec::parse_str("initial 1s").is_err());
+    }
+
+    #[test]
+    fn test_transition_list() {
+        let list = TransitionList::parse_str("width 0.2s, opacity 0.5s ease-in").unwrap();
+        assert_eq!(list.0.len(), 2);
+        assert_eq!(list.for_property("width").unwrap().duration, Time(0.2));
+        assert_eq!(list.for_property("opacity").unwrap().timing_function, EasingFunction::EASE_IN);
+        assert_eq!(list.for_property("height"), None);
+        assert_eq!(
+            TransitionList::parse_str("all 1s, width 2s").unwrap().for_property("height").unwrap().duration,
+            Time(1.0)
+        );
+        assert!(TransitionList::parse_str("none, width 1s").is_err());
+        assert_eq!(TransitionList::parse_str("margin 1s").unwrap().for_property("margin-top").unwrap().duration, Time(1.0));
+        assert_eq!(TransitionList::parse_str("margin 1s").unwrap().for_property("margins"), None);
+        assert!(TransitionList::parse_str("width 1s,").is_err());
+    }
+
+    #[test]
+    fn test_progress() {
+        let list = TransitionList::parse_str("width 2s linear 1s, opacity 0s 1s").unwrap();
+        assert_eq!(list.progress("width", 0.5), 0.0);
+        assert_eq!(list.progress("width", 2.0), 0.5);
+        assert_eq!(list.progress("width", 5.0), 1.0);
+        // A `0s` transition waits for its delay, then changes at once
+        assert_eq!(list.progress("opacity", 0.5), 0.0);
+        assert_eq!(list.progress("opacity", 1.0), 1.0);
+        assert_eq!(list.progress("height", 0.0), 1.0);
+        assert_eq!(list.total_duration(), 3.0);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,58 @@
+use std::fmt;
+use cssparser::{
+    Parser, ToCss,
+    match_ignore_ascii_case, _cssparser_internal_to_lowercase,
+};
+use crate::{
+    errors::{BevyCssParsingError, BevyCssParsingErrorKind},
+    values::Parse,
+};
+
+/// The value of the `visibility` property.  `collapse` hides the node like `hidden`, but also
+/// removes it from the layout (like `display: none`), as for a collapsed flex item.
+/// See also: https://drafts.csswg.org/css-display/#visibility
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Visibility {
+    Visible,
+    Hidden,
+    Collapse,
+}
+
+impl Visibility {
+    #[inline]
+    pub fn is_visible(&self) -> bool {
+        matches!(*self, Self::Visible)
+    }
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Self::Visible
+    }
+}
+
+impl Parse for Visibility {
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, BevyCssParsingError<'i>> {
+        let start = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { ident,
+            "visible" => Self::Visible,
+            "hidden" => Self::Hidden,
+            "collapse" => Self::Collapse,
+            _ => return Err(start.new_custom_error(
+                BevyCssParsingErrorKind::InvalidValue(ident.clone(), None)
+            ))
+        })
+    }
+}
+
+impl ToCss for Visibility {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result where W: fmt::Write {
+        dest.write_str(match *self {
+            Self::Visible => "visible",
+            Self::Hidden => "hidden",
+            Self::Collapse => "collapse",
+        })
+    }
+}
\ No newline at end of file